@@ -0,0 +1,62 @@
+// Wall time for a mixed workload on one object: a slow writer appending to
+// AuditFacet while reader threads read AccountFacet. The baseline wraps
+// every access in one object-wide RwLock, which is how facet storage used
+// to be locked; with per-facet locks readers and the writer never wait on
+// each other.
+//
+// Run with `cargo bench --bench facet_contention`.
+
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dynamic_entities::{AccountFacet, AuditFacet, Employee, FacetedObject};
+
+const READERS: usize = 4;
+const READS_PER_READER: usize = 200_000;
+const WRITES: usize = 500;
+const WRITE_HOLD: Duration = Duration::from_micros(200);
+
+fn employee() -> FacetedObject {
+    let employee = FacetedObject::new(Employee::new("Bench User", "BENCH001", "Engineering"));
+    employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+    employee.attach_facet(AuditFacet::new()).unwrap();
+    employee
+}
+
+// `global` emulates the single table lock
+fn run(global: Option<&RwLock<()>>) -> Duration {
+    let employee = employee();
+    let started = Instant::now();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..WRITES {
+                let _table = global.map(|lock| lock.write().unwrap());
+                employee.with_facet_mut::<AuditFacet, _>(|audit| {
+                    audit.log_operation("bench", "slow write");
+                    thread::sleep(WRITE_HOLD);
+                }).unwrap();
+            }
+        });
+
+        for _ in 0..READERS {
+            scope.spawn(|| {
+                for _ in 0..READS_PER_READER {
+                    let _table = global.map(|lock| lock.read().unwrap());
+                    employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+                }
+            });
+        }
+    });
+    started.elapsed()
+}
+
+fn main() {
+    let baseline = run(Some(&RwLock::new(())));
+    let per_facet = run(None);
+
+    println!("one object lock: {:>8.1} ms", baseline.as_secs_f64() * 1000.0);
+    println!("per-facet locks: {:>8.1} ms", per_facet.as_secs_f64() * 1000.0);
+    println!("speedup: {:.1}x", baseline.as_secs_f64() / per_facet.as_secs_f64());
+}
@@ -0,0 +1,63 @@
+// Latency of with_facet and has_facet reads while another thread keeps
+// attaching and detaching a facet on the same object. The baseline wraps
+// every read in a shared lock that attach and detach take exclusively,
+// which is how the facet table used to be locked; the copy-on-write table
+// lets reads go ahead while the table is being updated.
+//
+// Run with `cargo bench --bench facet_lookup`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::thread;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dynamic_entities::{AccountFacet, Employee, FacetedObject, NotificationFacet};
+
+fn employee() -> FacetedObject {
+    let employee = FacetedObject::new(Employee::new("Bench User", "BENCH001", "Engineering"));
+    employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+    employee
+}
+
+// Run `bench` while a background thread churns NotificationFacet on
+// `employee`, holding `table` exclusively around each attach and detach
+fn with_churn(employee: &FacetedObject, table: Option<&RwLock<()>>, bench: impl FnOnce()) {
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            while !stop.load(Ordering::Relaxed) {
+                {
+                    let _table = table.map(|lock| lock.write().unwrap());
+                    employee.attach_facet(NotificationFacet::new()).unwrap();
+                }
+                let _table = table.map(|lock| lock.write().unwrap());
+                employee.detach_facet::<NotificationFacet>().unwrap();
+            }
+        });
+        bench();
+        stop.store(true, Ordering::Relaxed);
+    });
+}
+
+fn lookup(c: &mut Criterion) {
+    let employee = employee();
+    let table = RwLock::new(());
+
+    for (label, table) in [("table lock", Some(&table)), ("copy-on-write", None)] {
+        let mut group = c.benchmark_group(label);
+        with_churn(&employee, table, || {
+            group.bench_function("with_facet", |b| b.iter(|| {
+                let _table = table.map(|lock| lock.read().unwrap());
+                black_box(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap())
+            }));
+            group.bench_function("has_facet", |b| b.iter(|| {
+                let _table = table.map(|lock| lock.read().unwrap());
+                black_box(employee.has_facet::<AccountFacet>())
+            }));
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
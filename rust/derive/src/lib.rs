@@ -0,0 +1,166 @@
+// #[derive(Facet)] for dynamic_entities. Generates the Any plumbing every
+// facet needs, plus the optional hooks selected with #[facet(...)]:
+//
+//   #[derive(Facet)]
+//   #[facet(name = "account", summarize, reflect)]
+//   pub struct AccountFacet { ... }
+//
+// `name` sets Facet::facet_name, used by registries and introspection;
+// `description = "..."` and `tags("a", "b")` are added to Facet::metadata;
+// `summarize`, `reflect` and `serialize` return Some(self) from
+// as_summarizable, as_reflect/as_reflect_mut and as_serializable, so the
+// type must implement Summarizable, ReflectFacet or serde's Serialize.
+// `on_event = "path"` forwards Facet::on_event to a function taking
+// (&mut Self, &dyn FacetEvent), e.g. `on_event = "Self::record_event"`.
+// `version = N` sets Facet::schema_version, saved with snapshots so older
+// payloads can be migrated.
+// `traits(A, B)` registers auxiliary traits the facet can be looked up by
+// with `with_facet_as::<dyn A, _>`, like facet_traits! does.
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, ExprPath, LitInt, LitStr, Path};
+
+#[derive(Default)]
+struct FacetAttributes {
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+    tags: Vec<LitStr>,
+    summarize: bool,
+    reflect: bool,
+    serialize: bool,
+    on_event: Option<ExprPath>,
+    version: Option<LitInt>,
+    traits: Vec<Path>,
+}
+
+impl FacetAttributes {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attributes = Self::default();
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("facet")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    attributes.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("description") {
+                    attributes.description = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("tags") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let tags = content.parse_terminated(<LitStr as syn::parse::Parse>::parse, syn::Token![,])?;
+                    attributes.tags.extend(tags);
+                } else if meta.path.is_ident("summarize") {
+                    attributes.summarize = true;
+                } else if meta.path.is_ident("reflect") {
+                    attributes.reflect = true;
+                } else if meta.path.is_ident("serialize") {
+                    attributes.serialize = true;
+                } else if meta.path.is_ident("on_event") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    attributes.on_event = Some(path.parse()?);
+                } else if meta.path.is_ident("version") {
+                    let version: LitInt = meta.value()?.parse()?;
+                    version.base10_parse::<u32>()?;
+                    attributes.version = Some(version);
+                } else if meta.path.is_ident("traits") {
+                    meta.parse_nested_meta(|item| {
+                        attributes.traits.push(item.path);
+                        Ok(())
+                    })?;
+                } else {
+                    return Err(meta.error(
+                        "expected `name = \"...\"`, `description = \"...\"`, `tags(...)`, `summarize`, `reflect`, `serialize`, `on_event = \"...\"`, `version = N` or `traits(...)`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attributes)
+    }
+}
+
+#[proc_macro_derive(Facet, attributes(facet))]
+pub fn derive_facet(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let attributes = FacetAttributes::parse(input)?;
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    // The casters are constants, which cannot name the impl's parameters
+    if !attributes.traits.is_empty() && !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "`traits(...)` is not supported on generic facets"));
+    }
+
+    let name = attributes.name.map(|name| quote! {
+        fn facet_name(&self) -> &'static str {
+            #name
+        }
+    });
+    let description = attributes.description.as_ref().map(|description| quote! { .description(#description) });
+    let tags = &attributes.tags;
+    let tags = (!tags.is_empty()).then(|| quote! { .tags(&[#(#tags),*]) });
+    let metadata = (description.is_some() || tags.is_some()).then(|| quote! {
+        fn metadata(&self) -> ::dynamic_entities::FacetMetadata {
+            ::dynamic_entities::FacetMetadata::of(self)#description #tags
+        }
+    });
+    let summarize = attributes.summarize.then(|| quote! {
+        fn as_summarizable(&self) -> ::core::option::Option<&dyn ::dynamic_entities::Summarizable> {
+            ::core::option::Option::Some(self)
+        }
+    });
+    let reflect = attributes.reflect.then(|| quote! {
+        fn as_reflect(&self) -> ::core::option::Option<&dyn ::dynamic_entities::ReflectFacet> {
+            ::core::option::Option::Some(self)
+        }
+
+        fn as_reflect_mut(&mut self) -> ::core::option::Option<&mut dyn ::dynamic_entities::ReflectFacet> {
+            ::core::option::Option::Some(self)
+        }
+    });
+    let serialize = attributes.serialize.then(|| quote! {
+        fn as_serializable(&self) -> ::core::option::Option<&dyn ::dynamic_entities::SerializableFacet> {
+            ::core::option::Option::Some(self)
+        }
+    });
+    let on_event = attributes.on_event.map(|handler| quote! {
+        fn on_event(&mut self, event: &dyn ::dynamic_entities::FacetEvent) {
+            #handler(self, event)
+        }
+    });
+
+    let version = attributes.version.map(|version| quote! {
+        fn schema_version(&self) -> u32 {
+            #version
+        }
+    });
+    let traits = &attributes.traits;
+    let traits = (!traits.is_empty()).then(|| quote! {
+        ::dynamic_entities::facet_traits!(#ident: #(#traits),*);
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::dynamic_entities::Facet for #ident #type_generics #where_clause {
+            fn as_any(&self) -> &dyn ::core::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+                self
+            }
+
+            #name
+            #metadata
+            #summarize
+            #reflect
+            #serialize
+            #on_event
+            #version
+            #traits
+        }
+    })
+}
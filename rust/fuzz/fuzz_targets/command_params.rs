@@ -0,0 +1,36 @@
+#![no_main]
+
+// Feeds arbitrary JSON documents through CommandBus parameter validation and
+// dispatch. Malformed input must be rejected with an error, never a panic,
+// and no accepted command may leave the account with a negative balance.
+
+use std::sync::Arc;
+
+use dynamic_entities::{
+    AccountFacet, AuditFacet, CommandBus, Employee, FacetedObject, ObjectRegistry, PermissionFacet,
+};
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(Value::Object(request)) = serde_json::from_slice::<Value>(data) else {
+        return;
+    };
+
+    let objects = Arc::new(ObjectRegistry::new());
+    let employee = FacetedObject::new(Employee::new("Fuzz User", "FUZZ001", "Engineering"));
+    employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+    employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+    employee.attach_facet(AuditFacet::new()).unwrap();
+    let employee = objects.insert("FUZZ001", employee).unwrap();
+
+    let bus = CommandBus::new(objects);
+    bus.register_builtin_commands().unwrap();
+
+    let command = request.get("command").and_then(Value::as_str).unwrap_or("deposit");
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let _ = bus.dispatch("FUZZ001", command, params);
+
+    let balance = employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+    assert!(balance >= 0.0);
+});
@@ -0,0 +1,41 @@
+/*
+ * C interface to the facets library, built with the `ffi` feature:
+ *
+ *   cargo rustc --lib --release --features ffi --crate-type cdylib
+ *
+ * Objects are opaque handles; facet state, command parameters and results
+ * are JSON strings. Failing calls return NULL or -1 and leave a message for
+ * facets_last_error on the calling thread.
+ */
+#ifndef FACETS_H
+#define FACETS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct FacetedObjectHandle FacetedObjectHandle;
+
+/* New object whose core is core_json; release with faceted_object_free */
+FacetedObjectHandle *faceted_object_new(const char *core_json);
+
+/* Attach the facet registered as name ("account", "permissions", "audit")
+ * restored from state_json; 0 on success, -1 on failure */
+int facet_attach_json(const FacetedObjectHandle *object, const char *name, const char *state_json);
+
+/* Invoke a registered command ("deposit", "withdraw", "balance") and return
+ * its JSON result; release with facets_string_free */
+char *facet_invoke(const FacetedObjectHandle *object, const char *command, const char *params_json);
+
+void faceted_object_free(FacetedObjectHandle *object);
+
+void facets_string_free(char *string);
+
+/* Last failure on this thread, or NULL; valid until the next failing call */
+const char *facets_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FACETS_H */
@@ -0,0 +1,53 @@
+// Generates an extension trait with one named accessor pair per facet type,
+// so callers can write `employee.account()?.deposit(amount)?` instead of
+// nesting `with_facet_mut::<AccountFacet, _>(|account| ...)` closures.
+//
+//     facet_accessors! {
+//         pub trait PayrollAccess {
+//             payroll, payroll_ref => PayrollFacet;
+//         }
+//     }
+//
+// `payroll()` returns a FacetRefMut and `payroll_ref()` a FacetRef. Each
+// guard holds the object's facet lock for as long as it lives, so keep it
+// to a single expression or drop it before touching other facets.
+#[macro_export]
+macro_rules! facet_accessors {
+    (
+        $vis:vis trait $trait_name:ident {
+            $($name:ident, $ref_name:ident => $facet:ty;)+
+        }
+    ) => {
+        $vis trait $trait_name {
+            $(
+                fn $name(&self) -> ::core::result::Result<
+                    $crate::core::FacetRefMut<'_, $facet>,
+                    $crate::error::FacetError,
+                >;
+
+                fn $ref_name(&self) -> ::core::result::Result<
+                    $crate::core::FacetRef<'_, $facet>,
+                    $crate::error::FacetError,
+                >;
+            )+
+        }
+
+        impl $trait_name for $crate::core::FacetedObject {
+            $(
+                fn $name(&self) -> ::core::result::Result<
+                    $crate::core::FacetRefMut<'_, $facet>,
+                    $crate::error::FacetError,
+                > {
+                    self.facet_mut::<$facet>()
+                }
+
+                fn $ref_name(&self) -> ::core::result::Result<
+                    $crate::core::FacetRef<'_, $facet>,
+                    $crate::error::FacetError,
+                > {
+                    self.facet_ref::<$facet>()
+                }
+            )+
+        }
+    };
+}
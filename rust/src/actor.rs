@@ -0,0 +1,149 @@
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{Facet, FacetError, FacetedObject};
+
+// Work item executed by the actor task against the object it owns
+type Command = Box<dyn FnOnce(&mut FacetedObject) + Send>;
+
+const DEFAULT_MAILBOX_CAPACITY: usize = 64;
+
+// Actor-style wrapper: the FacetedObject lives on a dedicated task and every
+// access is sent through an mpsc mailbox, so mutations are naturally
+// serialized and callers never contend on the object's locks.
+#[derive(Clone)]
+pub struct ActorFacetedObject {
+    mailbox: mpsc::Sender<Command>,
+}
+
+impl ActorFacetedObject {
+    // Spawn the actor task on the current tokio runtime
+    pub fn spawn(object: FacetedObject) -> Self {
+        Self::spawn_with_capacity(object, DEFAULT_MAILBOX_CAPACITY)
+    }
+
+    // A command that panics fails only its own call; the actor keeps the
+    // object, in whatever state the command left it, and serves the next
+    pub fn spawn_with_capacity(mut object: FacetedObject, capacity: usize) -> Self {
+        let (mailbox, mut commands) = mpsc::channel::<Command>(capacity);
+
+        tokio::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                let _ = catch_unwind(AssertUnwindSafe(|| command(&mut object)));
+            }
+        });
+
+        Self { mailbox }
+    }
+
+    // Run an arbitrary operation on the owned object and await its result
+    pub async fn execute<R: Send + 'static>(
+        &self,
+        operation: impl FnOnce(&mut FacetedObject) -> R + Send + 'static,
+    ) -> Result<R, FacetError> {
+        let (reply, response) = oneshot::channel();
+        let command: Command = Box::new(move |object| {
+            let _ = reply.send(operation(object));
+        });
+
+        self.mailbox.send(command).await
+            .map_err(|_| FacetError::Other("Actor mailbox is closed".to_string()))?;
+        // The reply is only dropped unsent if the operation panicked
+        response.await
+            .map_err(|_| FacetError::Other("Actor operation panicked".to_string()))
+    }
+
+    pub async fn attach_facet<F: Facet + 'static>(&self, facet: F) -> Result<(), FacetError> {
+        self.execute(move |object| object.attach_facet(facet)).await?
+    }
+
+    pub async fn with_facet<F: Facet + 'static, R: Send + 'static>(
+        &self,
+        operation: impl FnOnce(&F) -> R + Send + 'static,
+    ) -> Result<R, FacetError> {
+        self.execute(move |object| object.with_facet::<F, R>(operation)).await?
+    }
+
+    pub async fn with_facet_mut<F: Facet + 'static, R: Send + 'static>(
+        &self,
+        operation: impl FnOnce(&mut F) -> R + Send + 'static,
+    ) -> Result<R, FacetError> {
+        self.execute(move |object| object.with_facet_mut::<F, R>(operation)).await?
+    }
+
+    pub async fn has_facet<F: Facet + 'static>(&self) -> Result<bool, FacetError> {
+        self.execute(|object| object.has_facet::<F>()).await
+    }
+
+    // Read the core object; fails if the core is not of type T
+    pub async fn with_core<T: Any + Send + Sync, R: Send + 'static>(
+        &self,
+        operation: impl FnOnce(&T) -> R + Send + 'static,
+    ) -> Result<R, FacetError> {
+        self.execute(move |object| {
+            object.get_core::<T>()
+                .map(|core| operation(&core))
+                .ok_or(FacetError::CoreTypeMismatch { type_name: std::any::type_name::<T>() })
+        }).await?
+    }
+
+    // True once the actor task has stopped and no more commands are accepted
+    pub fn is_closed(&self) -> bool {
+        self.mailbox.is_closed()
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Employee, Money};
+
+    #[tokio::test]
+    async fn test_actor_serializes_mutations() {
+        let actor = ActorFacetedObject::spawn(FacetedObject::new(
+            Employee::new("Test User", "TEST001", "Engineering"),
+        ));
+        actor.attach_facet(AccountFacet::new("ACC001")).await.unwrap();
+
+        let mut handles = Vec::new();
+        for _ in 0..10 {
+            let actor = actor.clone();
+            handles.push(tokio::spawn(async move {
+                actor.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10)))
+                    .await
+                    .unwrap()
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let balance = actor.with_facet::<AccountFacet, _>(|account| account.get_balance())
+            .await
+            .unwrap();
+        assert_eq!(balance, Money::usd(100));
+
+        let name = actor.with_core::<Employee, _>(|employee| employee.name.clone())
+            .await
+            .unwrap();
+        assert_eq!(name, "Test User");
+    }
+
+    #[tokio::test]
+    async fn test_actor_reports_missing_facet() {
+        let actor = ActorFacetedObject::spawn(FacetedObject::new(
+            Employee::new("Test User", "TEST001", "Engineering"),
+        ));
+
+        assert!(!actor.has_facet::<AccountFacet>().await.unwrap());
+        assert!(actor.with_facet::<AccountFacet, _>(|account| account.get_balance()).await.is_err());
+
+        // A panicking operation fails its own call without stopping the actor
+        let panicked = actor.execute(|_| -> () { panic!("operation failed") }).await;
+        assert_eq!(panicked, Err(FacetError::Other("Actor operation panicked".to_string())));
+        assert!(!actor.is_closed());
+        assert!(!actor.has_facet::<AccountFacet>().await.unwrap());
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::Instant;
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::error::FacetError;
+
+// Bounds on mutable facet access for one object: up to `max_concurrent`
+// writers proceed to the facet lock, up to `max_queued` more wait for a
+// slot, and anything beyond that is rejected as busy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteLimits {
+    pub max_concurrent: usize,
+    pub max_queued: usize,
+}
+
+impl WriteLimits {
+    pub fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        Self { max_concurrent: max_concurrent.max(1), max_queued }
+    }
+}
+
+#[derive(Default)]
+struct Load {
+    active: usize,
+    queued: usize,
+}
+
+pub(crate) struct WriteAdmission {
+    limits: WriteLimits,
+    load: Mutex<Load>,
+    released: Condvar,
+}
+
+impl WriteAdmission {
+    pub(crate) fn new(limits: WriteLimits) -> Self {
+        Self {
+            limits,
+            load: Mutex::new(Load::default()),
+            released: Condvar::new(),
+        }
+    }
+
+    // Wait for a writer slot, or fail immediately if the queue is full
+    pub(crate) fn admit(&self) -> Result<WritePermit<'_>, FacetError> {
+        self.admit_until(None)
+    }
+
+    // As admit, but give up as busy if no slot frees up by `deadline`
+    pub(crate) fn admit_until(&self, deadline: Option<Instant>) -> Result<WritePermit<'_>, FacetError> {
+        let mut load = self.load.lock();
+
+        if load.active >= self.limits.max_concurrent {
+            if load.queued >= self.limits.max_queued {
+                return Err(FacetError::Busy);
+            }
+            load.queued += 1;
+            let saturated = |load: &mut Load| load.active >= self.limits.max_concurrent;
+            let timed_out = match deadline {
+                Some(deadline) => self.released.wait_while_until(&mut load, saturated, deadline).timed_out(),
+                None => {
+                    self.released.wait_while(&mut load, saturated);
+                    false
+                }
+            };
+            load.queued -= 1;
+            if timed_out {
+                return Err(FacetError::Busy);
+            }
+        }
+
+        load.active += 1;
+        Ok(WritePermit { admission: self })
+    }
+
+    // Take a writer slot only if one is free, for callers that must not
+    // block on the queue (async access, try_with_facet_mut)
+    pub(crate) fn try_admit(&self) -> Result<WritePermit<'_>, FacetError> {
+        let mut load = self.load.lock();
+        if load.active >= self.limits.max_concurrent {
+            return Err(FacetError::Busy);
+        }
+        load.active += 1;
+        Ok(WritePermit { admission: self })
+    }
+
+    pub(crate) fn limits(&self) -> WriteLimits {
+        self.limits
+    }
+
+    pub(crate) fn queued(&self) -> usize {
+        self.load.lock().queued
+    }
+}
+
+// Held for the duration of one mutable access
+pub(crate) struct WritePermit<'a> {
+    admission: &'a WriteAdmission,
+}
+
+impl Drop for WritePermit<'_> {
+    fn drop(&mut self) {
+        self.admission.load.lock().active -= 1;
+        self.admission.released.notify_one();
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+    use crate::{AccountFacet, Employee, FacetedObject, Money};
+
+    #[test]
+    fn test_saturated_object_rejects_writes() {
+        let employee = Arc::new(
+            FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"))
+                .with_write_limits(WriteLimits::new(1, 1)),
+        );
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        // One writer holds the facet, a second waits in the queue
+        let guard = employee.facet_mut::<AccountFacet>().unwrap();
+        let waiter = {
+            let employee = Arc::clone(&employee);
+            thread::spawn(move || employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))))
+        };
+        while employee.queued_writes() == 0 {
+            thread::yield_now();
+        }
+
+        let rejected = employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(1)));
+        assert_eq!(rejected.unwrap_err(), FacetError::Busy);
+        drop(guard);
+
+        assert_eq!(waiter.join().unwrap().unwrap().unwrap(), Money::usd(5));
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(5));
+    }
+
+    #[test]
+    fn test_queued_writers_all_complete() {
+        let employee = Arc::new(
+            FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"))
+                .with_write_limits(WriteLimits::new(1, 8)),
+        );
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let barrier = Arc::new(Barrier::new(8));
+        let writers: Vec<_> = (0..8).map(|_| {
+            let (employee, barrier) = (Arc::clone(&employee), Arc::clone(&barrier));
+            thread::spawn(move || {
+                barrier.wait();
+                employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(1))).unwrap().unwrap();
+            })
+        }).collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(8));
+    }
+}
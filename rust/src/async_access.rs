@@ -0,0 +1,98 @@
+// Facet access for async services. The operations may await while they
+// use the facet, e.g. to call out over the network. Async callers of one
+// facet queue on a tokio lock instead of blocking the executor. Sync
+// accessors of the same facet still block their thread until the async
+// operation finishes, so async code should stick to these methods.
+
+use std::any::TypeId;
+
+use crate::core::{downcast_mut, downcast_ref};
+use crate::{Facet, FacetError, FacetedObject};
+
+impl FacetedObject {
+    pub async fn with_facet_async<F: Facet + 'static, R>(
+        &self,
+        operation: impl AsyncFnOnce(&F) -> R,
+    ) -> Result<R, FacetError> {
+        let (cell, gate) = self.async_cell::<F>(false)?;
+        let _gate = gate.read().await;
+        let slot = cell.read_arc();
+        Ok(operation(downcast_ref::<F>(&slot)?).await)
+    }
+
+    // With write limits set, async writers are not queued: they get Busy
+    // when every writer slot is taken
+    pub async fn with_facet_mut_async<F: Facet + 'static, R>(
+        &self,
+        operation: impl AsyncFnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        let _permit = self.try_admit_write()?;
+        let (cell, gate) = self.async_cell::<F>(true)?;
+        let _gate = gate.write().await;
+        let mut slot = cell.write_arc();
+        let result = operation(downcast_mut::<F>(&mut slot)?).await;
+
+        drop(slot);
+        self.notify_mutation(TypeId::of::<F>());
+        Ok(result)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::{AccountFacet, AuditFacet, Employee, Money};
+
+    fn employee() -> Arc<FacetedObject> {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        Arc::new(employee)
+    }
+
+    #[tokio::test]
+    async fn test_async_writers_take_turns() {
+        let employee = employee();
+
+        let tasks: Vec<_> = (0..4).map(|_| {
+            let employee = Arc::clone(&employee);
+            tokio::spawn(async move {
+                employee.with_facet_mut_async::<AccountFacet, _>(async |account| {
+                    let balance = account.get_balance();
+                    tokio::task::yield_now().await;
+                    // Nobody else changed the balance while this task awaited
+                    account.deposit(Money::usd(10)).and_then(|new_balance| new_balance.checked_sub(balance))
+                }).await
+            })
+        }).collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap().unwrap(), Money::usd(10));
+        }
+        let balance = employee.with_facet_async::<AccountFacet, _>(async |account| account.get_balance()).await;
+        assert_eq!(balance.unwrap(), Money::usd(40));
+    }
+
+    #[tokio::test]
+    async fn test_other_facets_usable_while_awaiting() {
+        let employee = employee();
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let writer = {
+            let employee = Arc::clone(&employee);
+            tokio::spawn(async move {
+                employee.with_facet_mut_async::<AuditFacet, _>(async |audit| {
+                    released.await.unwrap();
+                    audit.log_operation("sync", "remote call finished");
+                }).await
+            })
+        };
+        tokio::task::yield_now().await;
+
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))).unwrap().unwrap();
+        release.send(()).unwrap();
+        writer.await.unwrap().unwrap();
+        assert_eq!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 1);
+    }
+}
@@ -0,0 +1,176 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+
+use crate::core::{Facet, FacetContext, FacetedObject, DEFAULT_INSTANCE};
+use crate::error::FacetError;
+
+type FacetFactory = Arc<dyn Fn(&FacetContext<'_>) -> Result<Box<dyn Facet>, FacetError> + Send + Sync>;
+
+// Reusable, named bundle of facets, e.g. "standard_employee". Holds
+// factories rather than facets so one preset can equip any number of
+// objects; each factory sees the core of the object being built.
+#[derive(Clone)]
+pub struct FacetPreset {
+    name: String,
+    factories: Vec<(String, FacetFactory)>,
+}
+
+impl FacetPreset {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), factories: Vec::new() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn with<F: Facet + 'static>(self, factory: impl Fn(&FacetContext<'_>) -> F + Send + Sync + 'static) -> Self {
+        self.try_with(move |ctx| Ok(factory(ctx)))
+    }
+
+    // Factory that can reject the object, e.g. when its core lacks data
+    // the facet needs
+    pub fn try_with<F: Facet + 'static>(
+        self,
+        factory: impl Fn(&FacetContext<'_>) -> Result<F, FacetError> + Send + Sync + 'static,
+    ) -> Self {
+        self.with_named_factory(DEFAULT_INSTANCE, factory)
+    }
+
+    pub fn with_named<F: Facet + 'static>(
+        self,
+        name: &str,
+        factory: impl Fn(&FacetContext<'_>) -> F + Send + Sync + 'static,
+    ) -> Self {
+        self.with_named_factory(name, move |ctx| Ok(factory(ctx)))
+    }
+
+    // Everything `other` attaches, ahead of this preset's later additions
+    pub fn include(mut self, other: &FacetPreset) -> Self {
+        self.factories.extend(other.factories.iter().cloned());
+        self
+    }
+
+    fn with_named_factory<F: Facet + 'static>(
+        mut self,
+        name: &str,
+        factory: impl Fn(&FacetContext<'_>) -> Result<F, FacetError> + Send + Sync + 'static,
+    ) -> Self {
+        let factory: FacetFactory = Arc::new(move |ctx| Ok(Box::new(factory(ctx)?) as Box<dyn Facet>));
+        self.factories.push((name.to_string(), factory));
+        self
+    }
+}
+
+// Collects facets for a new object and attaches them in one step, in
+// dependency order. A facet added for a type and instance that already
+// has one replaces it, so explicit facets can override a preset's.
+pub struct FacetedObjectBuilder {
+    object: FacetedObject,
+    facets: Vec<(String, Box<dyn Facet>)>,
+    // First preset factory failure, reported by build
+    error: Option<FacetError>,
+}
+
+impl FacetedObject {
+    pub fn builder<T: Any + Send + Sync>(core: T) -> FacetedObjectBuilder {
+        FacetedObjectBuilder { object: FacetedObject::new(core), facets: Vec::new(), error: None }
+    }
+}
+
+impl FacetedObjectBuilder {
+    pub fn with<F: Facet + 'static>(self, facet: F) -> Self {
+        self.with_named(DEFAULT_INSTANCE, facet)
+    }
+
+    pub fn with_named<F: Facet + 'static>(mut self, name: &str, facet: F) -> Self {
+        self.push(name, Box::new(facet));
+        self
+    }
+
+    pub fn preset(mut self, preset: &FacetPreset) -> Self {
+        for (name, factory) in &preset.factories {
+            match self.object.with_context(|ctx| factory(ctx)) {
+                Ok(facet) => self.push(name, facet),
+                Err(e) => {
+                    self.error.get_or_insert(e);
+                }
+            }
+        }
+        self
+    }
+
+    // Attach everything collected. Fails with the first preset or attach
+    // error; the partially built object is dropped.
+    pub fn build(self) -> Result<FacetedObject, FacetError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let (unnamed, named): (Vec<_>, Vec<_>) = self.facets.into_iter().partition(|(name, _)| name == DEFAULT_INSTANCE);
+        self.object.attach_facets_ordered(unnamed.into_iter().map(|(_, facet)| facet).collect())?;
+        for (name, facet) in named {
+            self.object.attach_boxed(facet.as_any().type_id(), &name, facet)?;
+        }
+        Ok(self.object)
+    }
+
+    fn push(&mut self, name: &str, facet: Box<dyn Facet>) {
+        let type_id: TypeId = facet.as_any().type_id();
+        self.facets.retain(|(existing, other)| !(existing == name && other.as_any().type_id() == type_id));
+        self.facets.push((name.to_string(), facet));
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, PermissionFacet};
+
+    #[test]
+    fn test_builder_attaches_in_one_step() {
+        let employee = FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with(AccountFacet::new("ACC001"))
+            .with(PermissionFacet::new("manager"))
+            .with_named("savings", AccountFacet::new("SAV001"))
+            .build()
+            .unwrap();
+
+        assert!(employee.has_facet::<PermissionFacet>());
+        assert_eq!(
+            employee.with_named_facet::<AccountFacet, _>("savings", |account| account.get_account_number().to_string()).unwrap(),
+            "SAV001",
+        );
+
+        let duplicate = FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with_named("savings", AccountFacet::new("SAV001"))
+            .with_named("savings", AccountFacet::new("SAV002"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            duplicate.with_named_facet::<AccountFacet, _>("savings", |account| account.get_account_number().to_string()).unwrap(),
+            "SAV002",
+        );
+    }
+
+    #[test]
+    fn test_presets_build_from_core_and_can_be_overridden() {
+        let preset = Employee::standard_preset();
+        assert_eq!(preset.name(), "standard_employee");
+
+        let manager = FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .preset(&preset)
+            .with(PermissionFacet::new("manager"))
+            .build()
+            .unwrap();
+        assert_eq!(manager.with_facet::<AccountFacet, _>(|account| account.get_account_number().to_string()).unwrap(), "ACC-TEST001");
+        assert_eq!(manager.with_facet::<PermissionFacet, _>(|permissions| permissions.get_role().to_string()).unwrap(), "manager");
+        assert!(manager.has_facet::<AuditFacet>());
+
+        // Presets that need an Employee core reject other cores
+        assert!(FacetedObject::builder("not an employee").preset(&preset).build().is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+use crate::event::FacetEvent;
+use crate::metadata::FacetMetadata;
+
+struct CacheEntry<V> {
+    value: V,
+    expires_at: Option<Timestamp>,
+    // Value of the cache's use counter when the entry was last read or
+    // written; the lowest is evicted first
+    last_used: u64,
+}
+
+// Memoized values on an object, e.g. rendered summaries. Holds at most
+// `capacity` entries, evicting the least recently used, and entries expire
+// after their TTL. Attached with FacetedObject::attach_cache it is cleared
+// whenever one of the facet types it depends on is attached, detached or
+// mutated, and whenever one of its invalidating events is emitted.
+pub struct CacheFacet<K, V> {
+    entries: HashMap<K, CacheEntry<V>>,
+    capacity: usize,
+    ttl: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    depends_on: Vec<TypeId>,
+    invalidating_events: Vec<TypeId>,
+    uses: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Eq + Hash + Clone + Send + Sync + 'static, V: Send + Sync + 'static> CacheFacet<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_clock(capacity, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(capacity: usize, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            ttl: None,
+            clock,
+            depends_on: Vec::new(),
+            invalidating_events: Vec::new(),
+            uses: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // Default TTL of entries inserted without one
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    // Clear the cache whenever a facet of type F changes
+    pub fn depends_on<F: Facet + 'static>(mut self) -> Self {
+        self.depends_on.push(TypeId::of::<F>());
+        self
+    }
+
+    // Clear the cache whenever an event of type E is emitted on the object
+    pub fn invalidate_on<E: FacetEvent>(mut self) -> Self {
+        self.invalidating_events.push(TypeId::of::<E>());
+        self
+    }
+
+    // The value for `key`, unless it is missing or expired
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let now = self.clock.now();
+        if self.entries.get(key).is_some_and(|entry| entry.expires_at.is_some_and(|expires_at| expires_at <= now)) {
+            self.entries.remove(key);
+        }
+        self.uses += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                self.hits += 1;
+                entry.last_used = self.uses;
+                Some(&entry.value)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        let ttl = self.ttl;
+        self.insert_with_ttl(key, value, ttl);
+    }
+
+    // Insert with a TTL of its own, or none to keep it until invalidated
+    // or evicted; a TTL too long to represent is treated as none
+    pub fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.uses += 1;
+        let expires_at = ttl.and_then(|ttl| self.clock.now().checked_add(ttl));
+        self.entries.insert(key, CacheEntry { value, expires_at, last_used: self.uses });
+    }
+
+    pub fn invalidate(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key).map(|entry| entry.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Entries held, including expired ones not yet dropped
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // (hits, misses) of `get` so far
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+
+    // Drop expired entries, or else the least recently used one
+    fn evict(&mut self) {
+        let now = self.clock.now();
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.expires_at.is_none_or(|expires_at| expires_at > now));
+        if self.entries.len() < before {
+            return;
+        }
+        let oldest = self.entries.iter().min_by_key(|(_, entry)| entry.last_used).map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + Send + Sync + 'static, V: Send + Sync + 'static> Facet for CacheFacet<K, V> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn facet_name(&self) -> &'static str {
+        "cache"
+    }
+
+    fn metadata(&self) -> FacetMetadata {
+        FacetMetadata::of(self).description("Memoized values with TTL and LRU eviction")
+    }
+
+    fn on_event(&mut self, event: &dyn FacetEvent) {
+        if self.invalidating_events.contains(&Any::type_id(event.as_any())) {
+            self.clear();
+        }
+    }
+}
+
+impl FacetedObject {
+    // Attach `cache` and clear it whenever one of the facet types it
+    // depends on changes
+    pub fn attach_cache<K, V>(&self, cache: CacheFacet<K, V>) -> Result<(), FacetError>
+    where
+        K: Eq + Hash + Clone + Send + Sync + 'static,
+        V: Send + Sync + 'static,
+    {
+        let depends_on = cache.depends_on.clone();
+        self.attach_facet(cache)?;
+        for type_id in depends_on {
+            self.add_mutation_observer(type_id, Arc::new(|object: &FacetedObject| {
+                let _ = object.with_facet_mut::<CacheFacet<K, V>, _>(CacheFacet::clear);
+            }))?;
+        }
+        Ok(())
+    }
+
+    // The cached value for `key`, computing and caching it on a miss. The
+    // cache is not locked while `compute` runs, so it may read any facet,
+    // and concurrent misses may compute the same value twice.
+    pub fn cached<K, V>(&self, key: K, compute: impl FnOnce(&FacetedObject) -> Result<V, FacetError>) -> Result<V, FacetError>
+    where
+        K: Eq + Hash + Clone + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
+    {
+        if let Some(value) = self.with_facet_mut::<CacheFacet<K, V>, _>(|cache| cache.get(&key).cloned())? {
+            return Ok(value);
+        }
+        let value = compute(self)?;
+        self.with_facet_mut::<CacheFacet<K, V>, _>(|cache| cache.insert(key, value.clone()))?;
+        Ok(value)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::facets::BalanceChanged;
+    use crate::{AccountFacet, Employee, EmployeeOperations, ManualClock, Money};
+
+    #[test]
+    fn test_ttl_and_lru_eviction() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let mut cache = CacheFacet::with_clock(2, clock.clone()).with_ttl(Duration::from_secs(10));
+        cache.insert("a", 1);
+        cache.insert_with_ttl("b", 2, None);
+        assert_eq!(cache.get(&"a"), Some(&1));
+
+        // "b" was used least recently
+        cache.insert("c", 3);
+        assert_eq!(cache.get(&"b"), None);
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.get(&"c"), None);
+        assert_eq!(cache.stats(), (1, 3));
+    }
+
+    #[test]
+    fn test_cache_invalidated_by_mutations_and_events() {
+        let object = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        object.attach_cache(CacheFacet::<&str, String>::new(8).depends_on::<AccountFacet>().invalidate_on::<BalanceChanged>()).unwrap();
+
+        let renders = std::cell::Cell::new(0);
+        let summary = || object.cached("summary", |object| {
+            renders.set(renders.get() + 1);
+            Ok(EmployeeOperations::get_employee_summary(object))
+        }).unwrap();
+        assert_eq!(summary(), summary());
+        assert_eq!(renders.get(), 1);
+
+        object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+        assert!(summary().contains("10.00 USD"));
+        assert_eq!(renders.get(), 2);
+
+        object.emit(&BalanceChanged { account_number: "ACC001".to_string(), previous: Money::usd(0), balance: Money::usd(10) }).unwrap();
+        summary();
+        assert_eq!(renders.get(), 3);
+    }
+}
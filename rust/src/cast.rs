@@ -0,0 +1,178 @@
+use core::any::{type_name, Any, TypeId};
+
+use crate::core::{Facet, FacetCell, FacetedObject};
+use crate::error::FacetError;
+use crate::interceptor::FacetAccess;
+
+// Casts a facet, seen as `dyn Any`, to the trait object type T. Facets hand
+// these out through Facet::trait_caster for every trait registered with
+// facet_traits!.
+pub struct TraitCaster<T: ?Sized + 'static> {
+    pub cast: fn(&dyn Any) -> Option<&T>,
+    pub cast_mut: fn(&mut dyn Any) -> Option<&mut T>,
+}
+
+// Register the auxiliary traits a facet can be looked up by, e.g.
+// `with_facet_as::<dyn Auditable, _>`. Expands to Facet::trait_caster, so
+// it goes inside the facet's `impl Facet` block:
+//
+//   impl Facet for LedgerAudit {
+//       fn as_any(&self) -> &dyn Any { self }
+//       fn as_any_mut(&mut self) -> &mut dyn Any { self }
+//       facet_traits!(LedgerAudit: Auditable, Summarizable);
+//   }
+//
+// #[facet(traits(Auditable))] on the derive does the same.
+#[macro_export]
+macro_rules! facet_traits {
+    ($facet:ty: $($trait_path:path),+ $(,)?) => {
+        fn trait_caster(&self, target: ::core::any::TypeId) -> ::core::option::Option<&'static dyn ::core::any::Any> {
+            $(
+                if target == ::core::any::TypeId::of::<dyn $trait_path>() {
+                    const CASTER: $crate::TraitCaster<dyn $trait_path> = $crate::TraitCaster {
+                        cast: |facet| facet.downcast_ref::<$facet>().map(|facet| facet as &dyn $trait_path),
+                        cast_mut: |facet| facet.downcast_mut::<$facet>().map(|facet| facet as &mut dyn $trait_path),
+                    };
+                    return ::core::option::Option::Some(&CASTER);
+                }
+            )+
+            ::core::option::Option::None
+        }
+    };
+}
+
+pub(crate) fn caster<T: ?Sized + 'static>(facet: &dyn Facet) -> Option<&'static TraitCaster<T>> {
+    facet.trait_caster(TypeId::of::<T>())?.downcast_ref::<TraitCaster<T>>()
+}
+
+fn provides<T: ?Sized + 'static>(facet: &dyn Facet) -> bool {
+    caster::<T>(facet).is_some()
+}
+
+impl FacetedObject {
+    // Run `operation` on the first facet, in attach order, registered as
+    // implementing T, e.g. `with_facet_as::<dyn Auditable, _>`
+    pub fn with_facet_as<T: ?Sized + 'static, R>(&self, operation: impl FnOnce(&T) -> R) -> Result<R, FacetError> {
+        let not_found = FacetError::NotFound { type_name: type_name::<T>() };
+        let (type_id, name, cell) = self.find_cell(provides::<T>)?.ok_or(not_found.clone())?;
+        let facet_type = cell.read().as_deref().map_or(type_name::<T>(), Facet::facet_type_name);
+
+        let access = FacetAccess { type_id, type_name: facet_type, instance: &name, mutable: false };
+        let interception = self.intercept(access)?;
+        self.record_instance_access(type_id, &name, false);
+        let slot = cell.read();
+        let cast = slot.as_deref().and_then(|facet| caster::<T>(facet).and_then(|caster| (caster.cast)(facet.as_any())));
+        let result = cast.map(operation).ok_or(not_found);
+        drop(slot);
+        interception.finish(result)
+    }
+
+    pub fn with_facet_as_mut<T: ?Sized + 'static, R>(
+        &self,
+        operation: impl FnOnce(&mut T) -> R,
+    ) -> Result<R, FacetError> {
+        let not_found = FacetError::NotFound { type_name: type_name::<T>() };
+        let (type_id, name, cell) = self.find_cell(provides::<T>)?.ok_or(not_found)?;
+        let facet_type = cell.read().as_deref().map_or(type_name::<T>(), Facet::facet_type_name);
+
+        let access = FacetAccess { type_id, type_name: facet_type, instance: &name, mutable: true };
+        let interception = self.intercept(access)?;
+        let result = self.admit_write().and_then(|_permit| self.cast_mut(type_id, &name, &cell, operation));
+        interception.finish(result)
+    }
+
+    // As with_facet_as_mut, without interceptors, guards or write admission
+    #[cfg(feature = "builtin-facets")]
+    pub(crate) fn with_facet_as_unchecked_mut<T: ?Sized + 'static, R>(
+        &self,
+        operation: impl FnOnce(&mut T) -> R,
+    ) -> Result<R, FacetError> {
+        let not_found = FacetError::NotFound { type_name: type_name::<T>() };
+        let (type_id, name, cell) = self.find_cell(provides::<T>)?.ok_or(not_found)?;
+        self.cast_mut(type_id, &name, &cell, operation)
+    }
+
+    fn cast_mut<T: ?Sized + 'static, R>(
+        &self,
+        type_id: TypeId,
+        name: &str,
+        cell: &FacetCell,
+        operation: impl FnOnce(&mut T) -> R,
+    ) -> Result<R, FacetError> {
+        let not_found = FacetError::NotFound { type_name: type_name::<T>() };
+        self.record_instance_access(type_id, name, true);
+        let mut slot = cell.write();
+        let facet = slot.as_deref_mut().ok_or(not_found.clone())?;
+        let caster = caster::<T>(facet).ok_or(not_found.clone())?;
+        let result = operation((caster.cast_mut)(facet.as_any_mut()).ok_or(not_found)?);
+
+        drop(slot);
+        self.notify_mutation(type_id);
+        Ok(result)
+    }
+
+    // Whether any attached facet is registered as implementing T
+    pub fn has_facet_as<T: ?Sized + 'static>(&self) -> bool {
+        self.find_cell(provides::<T>).is_ok_and(|found| found.is_some())
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::facets::Auditable;
+    use crate::{AuditFacet, Employee, Summarizable};
+
+    // Alternative audit implementation that only keeps operation names
+    #[derive(Default)]
+    struct OperationLog(Vec<String>);
+
+    impl Auditable for OperationLog {
+        fn log_operation(&mut self, operation: &str, _details: &str) {
+            self.0.push(operation.to_string());
+        }
+    }
+
+    impl Facet for OperationLog {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        facet_traits!(OperationLog: Auditable);
+    }
+
+    fn log_hire(object: &FacetedObject) -> Result<(), FacetError> {
+        object.with_facet_as_mut::<dyn Auditable, _>(|audit| audit.log_operation("hire", "Hired"))
+    }
+
+    #[test]
+    fn test_lookup_by_trait() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        assert!(matches!(log_hire(&employee), Err(FacetError::NotFound { .. })));
+
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        assert!(employee.has_facet_as::<dyn Auditable>());
+        assert!(employee.has_facet_as::<dyn Summarizable>());
+        log_hire(&employee).unwrap();
+
+        let entries = employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap();
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn test_alternative_implementation_swapped_in() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(OperationLog::default()).unwrap();
+        assert!(!employee.has_facet_as::<dyn Summarizable>());
+
+        log_hire(&employee).unwrap();
+        assert_eq!(employee.with_facet::<OperationLog, _>(|log| log.0.clone()).unwrap(), ["hire"]);
+    }
+}
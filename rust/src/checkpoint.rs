@@ -0,0 +1,146 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::{type_name, Any, TypeId};
+
+use crate::cast::caster;
+use crate::core::FacetedObject;
+use crate::error::FacetError;
+
+// Facet whose state can be captured and put back without the caller knowing
+// its type. Register it with `facet_traits!` or `#[facet(traits(...))]` so
+// FacetedObject::checkpoint picks the facet up.
+pub trait SnapshotFacet {
+    fn capture(&self) -> Box<dyn Any + Send + Sync>;
+
+    // `state` is a value returned by capture on this facet type
+    fn restore(&mut self, state: &(dyn Any + Send + Sync)) -> Result<(), FacetError>;
+}
+
+// Downcast a captured state back to the type the facet stored
+pub fn captured<S: 'static>(state: &(dyn Any + Send + Sync)) -> Result<&S, FacetError> {
+    state.downcast_ref::<S>().ok_or(FacetError::DowncastFailed { type_name: type_name::<S>() })
+}
+
+// States of an object's snapshot facets at one point in time
+pub struct Checkpoint {
+    states: Vec<(TypeId, String, Box<dyn Any + Send + Sync>)>,
+}
+
+impl Checkpoint {
+    // Facet instances captured
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+impl FacetedObject {
+    // Capture every attached facet that implements SnapshotFacet. Facets
+    // are captured one at a time, so take checkpoints while no other thread
+    // is mutating the object.
+    pub fn checkpoint(&self) -> Result<Checkpoint, FacetError> {
+        let mut states = Vec::new();
+        for (type_id, name, cell) in self.cells_in_order() {
+            let slot = cell.read();
+            let Some(facet) = slot.as_deref() else { continue };
+            if let Some(snapshot) = caster::<dyn SnapshotFacet>(facet).and_then(|caster| (caster.cast)(facet.as_any())) {
+                states.push((type_id, name, snapshot.capture()));
+            }
+        }
+        Ok(Checkpoint { states })
+    }
+
+    // Put the facets captured in `checkpoint` back into that state. Facets
+    // attached since are left alone and detached ones are skipped. The
+    // checkpoint stays valid, so it can be returned to repeatedly.
+    pub fn undo_to(&self, checkpoint: &Checkpoint) -> Result<(), FacetError> {
+        let _permit = self.admit_write()?;
+        let cells = self.cells_in_order();
+
+        for (type_id, name, state) in &checkpoint.states {
+            let Some((_, _, cell)) = cells.iter().find(|(attached, instance, _)| attached == type_id && instance == name) else {
+                continue;
+            };
+            let mut slot = cell.write();
+            let Some(facet) = slot.as_deref_mut() else { continue };
+            let caster = caster::<dyn SnapshotFacet>(facet)
+                .ok_or(FacetError::NotFound { type_name: type_name::<dyn SnapshotFacet>() })?;
+            if let Some(snapshot) = (caster.cast_mut)(facet.as_any_mut()) {
+                snapshot.restore(state.as_ref())?;
+            }
+
+            drop(slot);
+            self.record_instance_access(*type_id, name, true);
+            self.notify_mutation(*type_id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet};
+
+    fn employee() -> FacetedObject {
+        FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with(AccountFacet::new("ACC001"))
+            .with(PermissionFacet::new("manager"))
+            .with(AuditFacet::new())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_undo_batch_of_operations() {
+        let employee = employee();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(100))).unwrap().unwrap();
+        let checkpoint = employee.checkpoint().unwrap();
+        assert_eq!(checkpoint.len(), 3);
+
+        for amount in [10, 20, 30] {
+            employee.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(amount))).unwrap().unwrap();
+            employee.with_facet_mut::<AuditFacet, _>(|audit| audit.log_operation("withdraw", &amount.to_string())).unwrap();
+        }
+        employee.with_facet_mut::<PermissionFacet, _>(|permissions| permissions.revoke_permission("write")).unwrap();
+
+        employee.undo_to(&checkpoint).unwrap();
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.ledger().len()).unwrap(), 1);
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(100));
+        assert!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().is_empty()).unwrap());
+        assert!(employee.with_facet::<PermissionFacet, _>(|permissions| permissions.has_permission("write")).unwrap());
+    }
+
+    struct Theme;
+
+    impl crate::Facet for Theme {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_is_reusable_and_ignores_other_facets() {
+        let employee = employee();
+        let checkpoint = employee.checkpoint().unwrap();
+
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))).unwrap().unwrap();
+        employee.undo_to(&checkpoint).unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(7))).unwrap().unwrap();
+        employee.undo_to(&checkpoint).unwrap();
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(0));
+
+        // Facets without SnapshotFacet are neither captured nor restored
+        let plain = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        plain.attach_facet(Theme).unwrap();
+        assert!(plain.checkpoint().unwrap().is_empty());
+    }
+}
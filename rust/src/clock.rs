@@ -0,0 +1,111 @@
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+// Point in time measured from the UNIX epoch. Used instead of SystemTime so
+// time-dependent code works without `std` given a suitable Clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Timestamp(Duration);
+
+impl Timestamp {
+    pub const UNIX_EPOCH: Timestamp = Timestamp(Duration::ZERO);
+
+    pub const fn from_duration_since_epoch(since_epoch: Duration) -> Self {
+        Self(since_epoch)
+    }
+
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(Duration::from_millis(millis))
+    }
+
+    pub fn duration_since_epoch(&self) -> Duration {
+        self.0
+    }
+
+    pub fn as_millis(&self) -> u128 {
+        self.0.as_millis()
+    }
+
+    // `duration` later, or None if that can't be represented
+    pub fn checked_add(&self, duration: Duration) -> Option<Timestamp> {
+        self.0.checked_add(duration).map(Timestamp)
+    }
+
+    // Time elapsed since `earlier`, or zero if `earlier` is in the future
+    pub fn saturating_duration_since(&self, earlier: Timestamp) -> Duration {
+        self.0.saturating_sub(earlier.0)
+    }
+}
+
+
+#[cfg(feature = "std")]
+impl From<std::time::SystemTime> for Timestamp {
+    fn from(time: std::time::SystemTime) -> Self {
+        Timestamp(time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = i64::try_from(self.0.as_secs()).unwrap_or(i64::MAX);
+        match chrono::DateTime::from_timestamp(secs, self.0.subsec_nanos()) {
+            Some(time) => write!(f, "{}", time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+            None => write!(f, "{}ms", self.0.as_millis()),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}ms", self.0.as_millis())
+    }
+}
+
+// Source of the current time, pluggable so facets can run against a fake
+// clock in tests or a hardware timer on targets without `std`
+pub trait Clock: fmt::Debug + Send + Sync {
+    fn now(&self) -> Timestamp;
+}
+
+// Wall-clock time from the operating system
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::from(std::time::SystemTime::now())
+    }
+}
+
+// Clock that only moves when told to, with millisecond resolution
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    millis: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(start: Timestamp) -> Self {
+        Self { millis: AtomicU64::new(start.as_millis() as u64) }
+    }
+
+    pub fn set(&self, time: Timestamp) {
+        self.millis.store(time.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.millis.fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::from_millis(self.millis.load(Ordering::SeqCst))
+    }
+}
@@ -0,0 +1,120 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::{type_name, Any};
+
+use crate::cast::caster;
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+
+// Facet that can be duplicated without knowing its type, implemented for
+// every Clone facet. Register it with `facet_traits!` or
+// `#[facet(traits(...))]` so FacetedObject::deep_clone can copy the facet.
+pub trait CloneFacet {
+    fn clone_box(&self) -> Box<dyn Facet>;
+}
+
+impl<F: Facet + Clone> CloneFacet for F {
+    fn clone_box(&self) -> Box<dyn Facet> {
+        Box::new(self.clone())
+    }
+}
+
+impl FacetedObject {
+    // Copy of this object with a clone of its core, of type T, and of every
+    // facet instance under the same names, in the same order and with the
+    // same TTLs, e.g. to template a new employee from a prototype. Facets
+    // run on_attach against the cloned core. The clock, expiry callback,
+    // write limits, guards and interceptors carry over, interceptors being
+    // shared rather than copied; observers don't. Fails without cloning anything
+    // if some facet doesn't register CloneFacet.
+    pub fn deep_clone<T: Clone + Any + Send + Sync>(&self) -> Result<FacetedObject, FacetError> {
+        let core = self.get_core::<T>().ok_or(FacetError::CoreTypeMismatch { type_name: type_name::<T>() })?.clone();
+
+        let mut facets = Vec::new();
+        let mut not_cloneable = Vec::new();
+        for (type_id, name, cell) in self.cells_in_order() {
+            let slot = cell.read();
+            let Some(facet) = slot.as_deref() else { continue };
+            match caster::<dyn CloneFacet>(facet).and_then(|caster| (caster.cast)(facet.as_any())) {
+                Some(cloneable) => facets.push((type_id, name, cloneable.clone_box())),
+                None => not_cloneable.push(facet.facet_type_name()),
+            }
+        }
+        if !not_cloneable.is_empty() {
+            return Err(FacetError::NotCloneable { type_names: not_cloneable });
+        }
+
+        let mut clone = FacetedObject::new(core);
+        clone.clock = self.clock.clone();
+        clone.on_expired = self.on_expired.clone();
+        #[cfg(feature = "std")]
+        if let Some(limits) = self.write_limits() {
+            clone = clone.with_write_limits(limits);
+        }
+        *clone.interceptors.write() = self.interceptors.read().clone();
+        #[cfg(feature = "std")]
+        clone.guards.copy_from(&self.guards);
+
+        for (type_id, name, facet) in facets {
+            let expires_at = self.instance_expiry(type_id, &name);
+            clone.attach_boxed_until(type_id, &name, facet, expires_at)?;
+        }
+        Ok(clone)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet};
+
+    #[test]
+    fn test_deep_clone_copies_core_and_facets() {
+        let prototype = FacetedObject::builder(Employee::new("Template", "PROTO", "Engineering"))
+            .with(AccountFacet::new("ACC-PROTO"))
+            .with(PermissionFacet::new("manager"))
+            .with(AuditFacet::new())
+            .with_named("savings", AccountFacet::new("SAV-PROTO"))
+            .build()
+            .unwrap();
+        prototype.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(50))).unwrap().unwrap();
+
+        let copy = prototype.deep_clone::<Employee>().unwrap();
+        copy.with_core_mut::<Employee, _>(|employee| employee.id = "E002".into()).unwrap();
+        copy.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(25))).unwrap().unwrap();
+
+        assert_eq!(copy.get_core::<Employee>().unwrap().id, "E002");
+        assert_eq!(prototype.get_core::<Employee>().unwrap().id, "PROTO");
+        assert_eq!(copy.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(75));
+        assert_eq!(prototype.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(50));
+        assert_eq!(copy.facet_instance_names::<AccountFacet>(), ["default", "savings"]);
+        assert_eq!(copy.facet_count(), prototype.facet_count());
+        assert!(prototype.deep_clone::<String>().is_err());
+    }
+
+    struct Session;
+
+    impl Facet for Session {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_uncloneable_facets_are_listed() {
+        let object = FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with(AccountFacet::new("ACC001"))
+            .with(Session)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            object.deep_clone::<Employee>().err(),
+            Some(FacetError::NotCloneable { type_names: alloc::vec![type_name::<Session>()] }),
+        );
+    }
+}
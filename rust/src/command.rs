@@ -0,0 +1,558 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+#[cfg(feature = "builtin-facets")]
+use serde_json::json;
+use serde_json::{Map, Value};
+
+use crate::registry::ObjectRegistry;
+use crate::sync::RwLock;
+use crate::{FacetError, FacetedObject};
+#[cfg(feature = "builtin-facets")]
+use crate::{AccountFacet, AuditFacet, Money, PermissionFacet, PermissionGranted};
+
+// Type of a declared command parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    Number,
+    Text,
+    Bool,
+}
+
+impl ParamType {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            ParamType::Number => value.is_number(),
+            ParamType::Text => value.is_string(),
+            ParamType::Bool => value.is_boolean(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamSpec {
+    pub name: String,
+    pub param_type: ParamType,
+    pub required: bool,
+}
+
+// Parameter schema and permission requirement of a named command
+#[derive(Debug, Clone, Default)]
+pub struct CommandSpec {
+    params: Vec<ParamSpec>,
+    permission: Option<String>,
+    unaudited: bool,
+}
+
+impl CommandSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn param(mut self, name: &str, param_type: ParamType) -> Self {
+        self.params.push(ParamSpec { name: name.to_string(), param_type, required: true });
+        self
+    }
+
+    pub fn optional_param(mut self, name: &str, param_type: ParamType) -> Self {
+        self.params.push(ParamSpec { name: name.to_string(), param_type, required: false });
+        self
+    }
+
+    // Permission the object's PermissionFacet must grant before dispatch
+    pub fn requires_permission(mut self, permission: &str) -> Self {
+        self.permission = Some(permission.to_string());
+        self
+    }
+
+    // Don't record dispatches in the AuditFacet, for commands whose
+    // handler writes the audit entry itself
+    pub fn without_audit(mut self) -> Self {
+        self.unaudited = true;
+        self
+    }
+
+    pub fn params(&self) -> &[ParamSpec] {
+        &self.params
+    }
+
+    pub fn permission(&self) -> Option<&str> {
+        self.permission.as_deref()
+    }
+
+    // JSON Schema of the parameter object, with the required permission
+    // as "x-permission"
+    pub fn json_schema(&self) -> Value {
+        let properties: Map<String, Value> = self.params.iter()
+            .map(|param| {
+                let param_type = match param.param_type {
+                    ParamType::Number => "number",
+                    ParamType::Text => "string",
+                    ParamType::Bool => "boolean",
+                };
+                (param.name.clone(), serde_json::json!({ "type": param_type }))
+            })
+            .collect();
+        let required: Vec<&str> = self.params.iter()
+            .filter(|param| param.required)
+            .map(|param| param.name.as_str())
+            .collect();
+        let mut schema = serde_json::json!({ "type": "object", "properties": properties, "required": required });
+        if let Some(permission) = &self.permission {
+            schema["x-permission"] = Value::from(permission.as_str());
+        }
+        schema
+    }
+
+    // Check a JSON parameter object against the schema
+    fn validate(&self, params: &Value) -> Result<Params, FacetError> {
+        let values = match params {
+            Value::Object(values) => values.clone(),
+            Value::Null => Map::new(),
+            _ => return Err(FacetError::Invalid("Command parameters must be a JSON object".to_string())),
+        };
+
+        for name in values.keys() {
+            if !self.params.iter().any(|spec| &spec.name == name) {
+                return Err(FacetError::Invalid(format!("Unknown parameter '{}'", name)));
+            }
+        }
+
+        for spec in &self.params {
+            match values.get(&spec.name) {
+                Some(value) if !spec.param_type.matches(value) => {
+                    return Err(FacetError::Invalid(format!("Parameter '{}' must be of type {:?}", spec.name, spec.param_type)));
+                }
+                None if spec.required => {
+                    return Err(FacetError::Invalid(format!("Missing required parameter '{}'", spec.name)));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Params { values })
+    }
+}
+
+// Validated parameters handed to a command handler
+#[derive(Debug, Clone)]
+pub struct Params {
+    values: Map<String, Value>,
+}
+
+impl Params {
+    pub fn number(&self, name: &str) -> Result<f64, FacetError> {
+        self.values.get(name)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| FacetError::Invalid(format!("Parameter '{}' is not a number", name)))
+    }
+
+    pub fn text(&self, name: &str) -> Result<&str, FacetError> {
+        self.values.get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(|| FacetError::Invalid(format!("Parameter '{}' is not text", name)))
+    }
+
+    pub fn bool(&self, name: &str) -> Result<bool, FacetError> {
+        self.values.get(name)
+            .and_then(Value::as_bool)
+            .ok_or_else(|| FacetError::Invalid(format!("Parameter '{}' is not a bool", name)))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+}
+
+// Specs of the built-in commands, with the facet each one operates on
+#[cfg(feature = "builtin-facets")]
+pub(crate) fn builtin_specs() -> Vec<(&'static str, &'static str, CommandSpec)> {
+    vec![
+        ("account", "deposit", CommandSpec::new().param("amount", ParamType::Number).requires_permission("financial_operations")),
+        ("account", "withdraw", CommandSpec::new().param("amount", ParamType::Number).requires_permission("financial_operations")),
+        ("account", "balance", CommandSpec::new().requires_permission("read")),
+        ("permissions", "grant", CommandSpec::new().param("permission", ParamType::Text).requires_permission("write")),
+        ("audit", "log", CommandSpec::new().param("operation", ParamType::Text).param("details", ParamType::Text).requires_permission("write").without_audit()),
+    ]
+}
+
+#[cfg(feature = "builtin-facets")]
+fn builtin_spec(command: &str) -> CommandSpec {
+    builtin_specs().into_iter()
+        .find(|(_, name, _)| *name == command)
+        .map(|(_, _, spec)| spec)
+        .unwrap_or_default()
+}
+
+pub type CommandHandler =
+    Arc<dyn Fn(&FacetedObject, &Params) -> Result<Value, FacetError> + Send + Sync>;
+
+struct RegisteredCommand {
+    spec: CommandSpec,
+    handler: CommandHandler,
+}
+
+// (object id, idempotency key)
+type IdempotencyKey = (String, String);
+
+struct CompletedCommand {
+    command: String,
+    result: Value,
+}
+
+// Idempotency keys being dispatched and the results of the most recent
+// completed ones, oldest first in `order`
+#[derive(Default)]
+struct Idempotency {
+    in_flight: HashSet<IdempotencyKey>,
+    completed: HashMap<IdempotencyKey, CompletedCommand>,
+    order: VecDeque<IdempotencyKey>,
+}
+
+// Releases an idempotency key when its dispatch ends, even by panicking
+struct InFlight<'a> {
+    idempotency: &'a Mutex<Idempotency>,
+    key: IdempotencyKey,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.idempotency.lock().in_flight.remove(&self.key);
+    }
+}
+
+// Idempotency keys remembered by default
+const DEFAULT_IDEMPOTENCY_CAPACITY: usize = 10_000;
+
+// Routes named commands to registry objects, enforcing the declared schema
+// and permission, de-duplicating retried requests by idempotency key, and
+// recording successful dispatches in the object's AuditFacet
+pub struct CommandBus {
+    objects: Arc<ObjectRegistry>,
+    commands: RwLock<HashMap<String, RegisteredCommand>>,
+    idempotency: Mutex<Idempotency>,
+    idempotency_capacity: usize,
+}
+
+impl CommandBus {
+    pub fn new(objects: Arc<ObjectRegistry>) -> Self {
+        Self {
+            objects,
+            commands: RwLock::new(HashMap::new()),
+            idempotency: Mutex::new(Idempotency::default()),
+            idempotency_capacity: DEFAULT_IDEMPOTENCY_CAPACITY,
+        }
+    }
+
+    // Remember the results of at most `capacity` idempotency keys; the
+    // oldest are forgotten first, and a retry with a forgotten key runs
+    // the command again
+    pub fn idempotency_capacity(mut self, capacity: usize) -> Self {
+        self.idempotency_capacity = capacity;
+        self
+    }
+
+    pub fn objects(&self) -> &Arc<ObjectRegistry> {
+        &self.objects
+    }
+
+    pub fn register<H>(&self, name: &str, spec: CommandSpec, handler: H) -> Result<(), FacetError>
+    where
+        H: Fn(&FacetedObject, &Params) -> Result<Value, FacetError> + Send + Sync + 'static,
+    {
+        let mut commands = self.commands.write();
+
+        if commands.contains_key(name) {
+            return Err(FacetError::Invalid(format!("Command '{}' already registered", name)));
+        }
+
+        commands.insert(name.to_string(), RegisteredCommand { spec, handler: Arc::new(handler) });
+        Ok(())
+    }
+
+    // Register deposit, withdraw and balance commands for AccountFacet,
+    // grant for PermissionFacet, and log for AuditFacet
+    #[cfg(feature = "builtin-facets")]
+    pub fn register_builtin_commands(&self) -> Result<(), FacetError> {
+        self.register(
+            "deposit",
+            builtin_spec("deposit"),
+            |object, params| {
+                let amount = params.number("amount")?;
+                let balance = object.with_facet_mut::<AccountFacet, _>(|account| {
+                    account.deposit(Money::from_f64(amount, account.currency())?)
+                })??;
+                Ok(json!({ "balance": balance }))
+            },
+        )?;
+        self.register(
+            "withdraw",
+            builtin_spec("withdraw"),
+            |object, params| {
+                let amount = params.number("amount")?;
+                let balance = object.with_facet_mut::<AccountFacet, _>(|account| {
+                    account.withdraw(Money::from_f64(amount, account.currency())?)
+                })??;
+                Ok(json!({ "balance": balance }))
+            },
+        )?;
+        self.register(
+            "balance",
+            builtin_spec("balance"),
+            |object, _| {
+                let balance = object.with_facet::<AccountFacet, _>(|account| account.get_balance())?;
+                Ok(json!({ "balance": balance }))
+            },
+        )?;
+        self.register(
+            "grant",
+            builtin_spec("grant"),
+            |object, params| {
+                let permission = params.text("permission")?;
+                let role = object.with_facet_mut::<PermissionFacet, _>(|permissions| {
+                    permissions.grant_permission(permission);
+                    permissions.get_role().to_string()
+                })?;
+                object.emit(&PermissionGranted { role, permission: permission.to_string() })?;
+                Ok(json!({ "granted": permission }))
+            },
+        )?;
+
+        self.register(
+            "log",
+            builtin_spec("log"),
+            |object, params| {
+                let operation = params.text("operation")?;
+                let details = params.text("details")?;
+                object.with_facet_mut::<AuditFacet, _>(|audit| audit.log_operation(operation, details))?;
+                Ok(Value::Null)
+            },
+        )
+    }
+
+    // Registered command names in sorted order
+    pub fn command_names(&self) -> Vec<String> {
+        let commands = self.commands.read();
+        let mut names: Vec<String> = commands.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn spec(&self, name: &str) -> Option<CommandSpec> {
+        let commands = self.commands.read();
+        commands.get(name).map(|command| command.spec.clone())
+    }
+
+    pub fn dispatch(&self, object_id: &str, name: &str, params: Value) -> Result<Value, FacetError> {
+        let object = self.objects.get(object_id)
+            .ok_or_else(|| FacetError::UnknownObject { id: object_id.to_string() })?;
+        self.execute(&object, name, params)
+    }
+
+    // Run a command against an object held outside the bus's registry,
+    // e.g. one in a FacetWorld, with the same checks as dispatch
+    pub fn execute(&self, object: &FacetedObject, name: &str, params: Value) -> Result<Value, FacetError> {
+        // Handlers may call back into the bus, so the table isn't held
+        // while one runs
+        let (spec, handler) = {
+            let commands = self.commands.read();
+            let command = commands.get(name)
+                .ok_or_else(|| FacetError::UnknownCommand { name: name.to_string() })?;
+            (command.spec.clone(), Arc::clone(&command.handler))
+        };
+
+        let params = spec.validate(&params)?;
+        Self::authorize(object, name, &spec)?;
+
+        let result = handler(object, &params)?;
+        if !spec.unaudited {
+            Self::record(object, name, &result);
+        }
+
+        Ok(result)
+    }
+
+    // Whether the object's permissions allow running the command, e.g. to
+    // report a denial apart from other failures
+    pub fn check_permission(&self, object: &FacetedObject, name: &str) -> Result<(), FacetError> {
+        let spec = self.spec(name).ok_or_else(|| FacetError::UnknownCommand { name: name.to_string() })?;
+        Self::authorize(object, name, &spec)
+    }
+
+    fn authorize(object: &FacetedObject, name: &str, spec: &CommandSpec) -> Result<(), FacetError> {
+        match spec.permission() {
+            Some(permission) if !Self::is_permitted(object, permission) => Err(FacetError::PermissionDenied {
+                operation: name.to_string(),
+                permission: permission.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "builtin-facets")]
+    fn is_permitted(object: &FacetedObject, permission: &str) -> bool {
+        object.with_facet::<PermissionFacet, bool>(|permissions| {
+            permissions.has_permission(permission)
+        }).unwrap_or(false)
+    }
+
+    // Without the built-in PermissionFacet nothing can grant a permission
+    #[cfg(not(feature = "builtin-facets"))]
+    fn is_permitted(_object: &FacetedObject, _permission: &str) -> bool {
+        false
+    }
+
+    #[cfg(feature = "builtin-facets")]
+    fn record(object: &FacetedObject, name: &str, result: &Value) {
+        let _ = object.with_facet_mut::<AuditFacet, ()>(|audit| {
+            audit.log_operation(name, &format!("Result: {}", result));
+        });
+    }
+
+    #[cfg(not(feature = "builtin-facets"))]
+    fn record(_object: &FacetedObject, _name: &str, _result: &Value) {}
+
+    // Dispatch at most once per (object, key): a retry with the same key
+    // returns the recorded result instead of executing the command again.
+    // Failed dispatches are not recorded and may be retried. A retry that
+    // arrives while the key is still being dispatched fails instead of
+    // waiting; other keys are dispatched concurrently.
+    pub fn dispatch_idempotent(
+        &self,
+        object_id: &str,
+        name: &str,
+        params: Value,
+        idempotency_key: &str,
+    ) -> Result<Value, FacetError> {
+        let key = (object_id.to_string(), idempotency_key.to_string());
+        let in_flight = {
+            let mut idempotency = self.idempotency.lock();
+            if let Some(previous) = idempotency.completed.get(&key) {
+                if previous.command != name {
+                    return Err(FacetError::Invalid(format!(
+                        "Idempotency key '{}' was already used for command '{}'",
+                        idempotency_key, previous.command
+                    )));
+                }
+                return Ok(previous.result.clone());
+            }
+            if !idempotency.in_flight.insert(key.clone()) {
+                return Err(FacetError::Invalid(format!("Idempotency key '{}' is already being dispatched", idempotency_key)));
+            }
+            InFlight { idempotency: &self.idempotency, key }
+        };
+
+        let result = self.dispatch(object_id, name, params)?;
+        let mut idempotency = self.idempotency.lock();
+        if self.idempotency_capacity > 0 {
+            while idempotency.order.len() >= self.idempotency_capacity {
+                let Some(oldest) = idempotency.order.pop_front() else { break };
+                idempotency.completed.remove(&oldest);
+            }
+            idempotency.order.push_back(in_flight.key.clone());
+            idempotency.completed.insert(in_flight.key.clone(), CompletedCommand { command: name.to_string(), result: result.clone() });
+        }
+        drop(idempotency);
+        drop(in_flight);
+        Ok(result)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::Employee;
+    use serde_json::json;
+
+    fn bus_with_employee(role: &str) -> CommandBus {
+        let objects = Arc::new(ObjectRegistry::new());
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new(role)).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        objects.insert("TEST001", employee).unwrap();
+
+        let bus = CommandBus::new(objects);
+        bus.register_builtin_commands().unwrap();
+        bus
+    }
+
+    #[test]
+    fn test_dispatch_validates_and_audits() {
+        let bus = bus_with_employee("manager");
+
+        let result = bus.dispatch("TEST001", "deposit", json!({ "amount": 500.0 })).unwrap();
+        assert_eq!(result, json!({ "balance": { "minor": 50_000, "currency": "USD" } }));
+
+        assert!(bus.dispatch("TEST001", "deposit", json!({ "amount": "lots" })).is_err());
+        assert!(bus.dispatch("TEST001", "deposit", json!({})).is_err());
+        assert!(bus.dispatch("TEST001", "deposit", json!({ "amount": 1.0, "memo": "x" })).is_err());
+        assert_eq!(
+            bus.dispatch("TEST001", "transfer", json!({})),
+            Err(FacetError::UnknownCommand { name: "transfer".to_string() })
+        );
+        assert_eq!(
+            bus.dispatch("NOBODY", "balance", Value::Null),
+            Err(FacetError::UnknownObject { id: "NOBODY".to_string() })
+        );
+
+        let object = bus.objects().get("TEST001").unwrap();
+        let logged = object.with_facet::<AuditFacet, usize>(|audit| audit.get_audit_trail().len()).unwrap();
+        assert_eq!(logged, 1);
+    }
+
+    #[test]
+    fn test_dispatch_checks_permissions() {
+        let bus = bus_with_employee("employee");
+
+        assert!(bus.dispatch("TEST001", "deposit", json!({ "amount": 10.0 })).is_err());
+        assert_eq!(bus.dispatch("TEST001", "balance", Value::Null).unwrap(), json!({ "balance": { "minor": 0, "currency": "USD" } }));
+    }
+
+    #[test]
+    fn test_idempotent_dispatch_runs_once() {
+        let bus = bus_with_employee("manager");
+
+        let first = bus.dispatch_idempotent("TEST001", "deposit", json!({ "amount": 100.0 }), "req-1").unwrap();
+        let retry = bus.dispatch_idempotent("TEST001", "deposit", json!({ "amount": 100.0 }), "req-1").unwrap();
+        assert_eq!(first, retry);
+        assert_eq!(bus.dispatch("TEST001", "balance", Value::Null).unwrap(), json!({ "balance": { "minor": 10_000, "currency": "USD" } }));
+
+        assert!(bus.dispatch_idempotent("TEST001", "withdraw", json!({ "amount": 1.0 }), "req-1").is_err());
+    }
+
+    #[test]
+    fn test_idempotency_is_per_key_and_bounded() {
+        let bus = Arc::new(bus_with_employee("manager").idempotency_capacity(2));
+        let inner = Arc::downgrade(&bus);
+        bus.register("nested", CommandSpec::new().param("key", ParamType::Text), move |_, params| {
+            let bus = inner.upgrade().ok_or_else(|| FacetError::Other("bus dropped".to_string()))?;
+            bus.dispatch_idempotent("TEST001", "deposit", json!({ "amount": 1.0 }), params.text("key")?)
+        }).unwrap();
+
+        // Handlers can dispatch other keys, but not the one in flight
+        assert!(bus.dispatch_idempotent("TEST001", "nested", json!({ "key": "inner" }), "outer").is_ok());
+        assert!(bus.dispatch_idempotent("TEST001", "nested", json!({ "key": "self" }), "self").is_err());
+
+        // "inner" is forgotten once two newer keys completed
+        bus.dispatch_idempotent("TEST001", "deposit", json!({ "amount": 1.0 }), "third").unwrap();
+        bus.dispatch_idempotent("TEST001", "deposit", json!({ "amount": 1.0 }), "inner").unwrap();
+        assert_eq!(bus.dispatch("TEST001", "balance", Value::Null).unwrap(), json!({ "balance": { "minor": 300, "currency": "USD" } }));
+    }
+
+    #[test]
+    fn test_handlers_can_register_commands() {
+        let bus = Arc::new(bus_with_employee("manager"));
+        let inner = Arc::downgrade(&bus);
+        bus.register("define", CommandSpec::new(), move |_, _| {
+            let bus = inner.upgrade().ok_or_else(|| FacetError::Other("bus dropped".to_string()))?;
+            bus.register("defined", CommandSpec::new(), |_, _| Ok(json!("ok")))?;
+            Ok(Value::Null)
+        }).unwrap();
+
+        bus.dispatch("TEST001", "define", Value::Null).unwrap();
+        assert_eq!(bus.dispatch("TEST001", "defined", Value::Null).unwrap(), json!("ok"));
+    }
+}
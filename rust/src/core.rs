@@ -0,0 +1,1736 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::{type_name, Any, TypeId};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::admission::{WriteAdmission, WriteLimits, WritePermit};
+#[cfg(feature = "std")]
+use crate::clock::SystemClock;
+use crate::clock::{Clock, Timestamp};
+use crate::error::FacetError;
+use crate::event::FacetEvent;
+#[cfg(feature = "std")]
+use crate::guard::Guards;
+#[cfg(feature = "metrics")]
+use crate::metrics::FacetMetrics;
+use crate::interceptor::{FacetAccess, Interceptors};
+use crate::metadata::FacetMetadata;
+use crate::observe::Observers;
+use crate::reflect::{FieldValue, ReflectFacet, ReflectedFacet};
+#[cfg(feature = "std")]
+use crate::snapshot::SerializableFacet;
+use crate::summary::{FacetSummary, Summarizable, SummaryCollector};
+use crate::ttl::{ExpiredFacet, ExpiryCallback};
+use crate::sync::{CowCell, FacetLock, FacetReadGuard, FacetWriteGuard, MappedReadGuard, RawFacetLock, RwLock};
+
+// Facet storage: HashMap with `std`, BTreeMap when only `alloc` is available
+#[cfg(feature = "std")]
+type TypeMap<V> = std::collections::HashMap<TypeId, V>;
+
+#[cfg(not(feature = "std"))]
+type TypeMap<V> = alloc::collections::BTreeMap<TypeId, V>;
+
+// Each attached facet sits behind its own lock, so accesses to different
+// facets of one object never wait on each other. The slot is emptied when
+// the facet is detached, so holders of a stale cell see it as missing.
+pub(crate) type FacetSlot = Option<Box<dyn Facet>>;
+pub(crate) type FacetCell = Arc<FacetLock<FacetSlot>>;
+
+// Per-facet lock async accessors wait on before taking the facet's cell,
+// so they queue without blocking the executor
+#[cfg(feature = "async")]
+pub(crate) type AsyncGate = Arc<tokio::sync::RwLock<()>>;
+
+// Admission to mutable access; writes are only throttled with `std`
+#[cfg(feature = "std")]
+pub(crate) type Permit<'a> = Option<WritePermit<'a>>;
+
+#[cfg(not(feature = "std"))]
+pub(crate) type Permit<'a> = PhantomData<&'a ()>;
+
+// How long an accessor waits for a facet's lock
+#[derive(Clone, Copy)]
+enum LockWait {
+    Block,
+    Never,
+    #[cfg(feature = "std")]
+    Until(Instant),
+}
+
+impl LockWait {
+    fn read<'a>(self, cell: &'a FacetCell) -> Option<lock_api::RwLockReadGuard<'a, RawFacetLock, FacetSlot>> {
+        match self {
+            LockWait::Block => Some(cell.read()),
+            LockWait::Never => cell.try_read(),
+            #[cfg(feature = "std")]
+            LockWait::Until(deadline) => cell.try_read_until(deadline),
+        }
+    }
+
+    fn write<'a>(self, cell: &'a FacetCell) -> Option<lock_api::RwLockWriteGuard<'a, RawFacetLock, FacetSlot>> {
+        match self {
+            LockWait::Block => Some(cell.write()),
+            LockWait::Never => cell.try_write(),
+            #[cfg(feature = "std")]
+            LockWait::Until(deadline) => cell.try_write_until(deadline),
+        }
+    }
+}
+
+// The core object sits behind its own lock so it can be updated in place.
+// Locks are always taken in the order core, facet table, facet.
+type CoreCell = FacetLock<Box<dyn Any + Send + Sync>>;
+
+// Shared access to the core object, from FacetedObject::get_core
+pub type CoreRef<'a, T> = MappedReadGuard<'a, T>;
+
+// Core facet trait that all facets must implement
+pub trait Facet: Any + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    // Facets that can describe themselves return Some(self)
+    fn as_summarizable(&self) -> Option<&dyn Summarizable> {
+        None
+    }
+
+    // Facets exposing their fields at runtime return Some(self)
+    fn as_reflect(&self) -> Option<&dyn ReflectFacet> {
+        None
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn ReflectFacet> {
+        None
+    }
+
+    // Facets that can be saved in snapshots return Some(self)
+    #[cfg(feature = "std")]
+    fn as_serializable(&self) -> Option<&dyn SerializableFacet> {
+        None
+    }
+
+    // Version of the serialized layout, saved with snapshots so older
+    // payloads can be migrated on restore; bump it when fields change
+    #[cfg(feature = "std")]
+    fn schema_version(&self) -> u32 {
+        1
+    }
+
+    // Caster for an auxiliary trait registered with facet_traits!, as a
+    // TraitCaster<dyn Trait>
+    fn trait_caster(&self, _target: TypeId) -> Option<&'static dyn Any> {
+        None
+    }
+
+    fn facet_type_name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    // Name registries and introspection address the facet by, e.g.
+    // "account"; #[facet(name = "...")] on the derive sets it
+    fn facet_name(&self) -> &'static str {
+        self.facet_type_name()
+    }
+
+    // Name, description, version and capability tags for tooling;
+    // #[facet(description = "...", tags(...))] on the derive adds to the
+    // defaults
+    fn metadata(&self) -> FacetMetadata {
+        FacetMetadata::of(self)
+    }
+
+    // When the facet stops being valid; expired facets are removed by
+    // registry garbage collection
+    fn expires_at(&self) -> Option<Timestamp> {
+        None
+    }
+
+    // Called before the facet joins an object, e.g. to initialize itself
+    // from the core object. An error aborts the attach.
+    fn on_attach(&mut self, _ctx: &FacetContext<'_>) -> Result<(), FacetError> {
+        Ok(())
+    }
+
+    // Called before the facet leaves an object, e.g. to release resources.
+    // An error keeps the facet attached.
+    fn on_detach(&mut self) -> Result<(), FacetError> {
+        Ok(())
+    }
+
+    // Called after the core object was changed with with_core_mut or
+    // replace_core, e.g. to refresh values derived from it
+    fn on_core_changed(&mut self, _ctx: &FacetContext<'_>) {}
+
+    // Called for every event emitted on the object the facet is attached to
+    fn on_event(&mut self, _event: &dyn FacetEvent) {}
+
+    // Facet types that must already be attached before this one can be;
+    // attach_facet rejects the facet otherwise
+    fn dependencies(&self) -> Vec<TypeId> {
+        Vec::new()
+    }
+}
+
+fn missing_dependencies(facet: &dyn Facet, attached: impl Fn(&TypeId) -> bool) -> Vec<TypeId> {
+    facet.dependencies()
+        .into_iter()
+        .filter(|dependency| !attached(dependency))
+        .collect()
+}
+
+// What a facet sees of the object it is attached to
+pub struct FacetContext<'a> {
+    core: &'a (dyn Any + Send + Sync),
+}
+
+impl FacetContext<'_> {
+    pub fn core<T: 'static>(&self) -> Option<&T> {
+        self.core.downcast_ref::<T>()
+    }
+}
+
+// Walks the facets attached to an object without knowing their types
+pub trait FacetVisitor {
+    fn visit(&mut self, type_id: TypeId, facet: &dyn Facet);
+}
+
+impl<V: FnMut(TypeId, &dyn Facet)> FacetVisitor for V {
+    fn visit(&mut self, type_id: TypeId, facet: &dyn Facet) {
+        self(type_id, facet)
+    }
+}
+
+// Name of the instance the unnamed facet API (attach_facet, with_facet, ...)
+// works on; other names let an object carry several facets of one type
+pub const DEFAULT_INSTANCE: &str = "default";
+
+// One attached facet instance: its cell, a count of all accesses for usage
+// telemetry, when its TTL runs out and, for async access, its gate
+struct Instance {
+    cell: FacetCell,
+    accesses: AtomicU64,
+    expires_at: Option<Timestamp>,
+    #[cfg(feature = "async")]
+    gate: AsyncGate,
+}
+
+// Facet instances by type and name plus their attach order, so visits are
+// deterministic, and a counter per facet type bumped on every mutable
+// access to any of its instances. The table is copy-on-write: lookups load
+// the current version without locking, while attach and detach publish a
+// new one. Instances and counters are shared between versions, so counts
+// made through any version are kept.
+#[derive(Default, Clone)]
+struct FacetStore {
+    instances: TypeMap<BTreeMap<String, Arc<Instance>>>,
+    order: Vec<(TypeId, String)>,
+    generations: TypeMap<Arc<AtomicU64>>,
+}
+
+// Usage data for one attached facet instance
+#[cfg(feature = "std")]
+pub(crate) struct FacetUsage {
+    pub(crate) type_id: TypeId,
+    pub(crate) name: String,
+    pub(crate) type_name: &'static str,
+    pub(crate) accesses: u64,
+    pub(crate) expires_at: Option<Timestamp>,
+    pub(crate) dependencies: Vec<TypeId>,
+}
+
+impl FacetStore {
+    fn instance(&self, type_id: &TypeId, name: &str) -> Option<&Instance> {
+        self.instances.get(type_id)?.get(name).map(|instance| &**instance)
+    }
+
+    fn contains(&self, type_id: &TypeId, name: &str) -> bool {
+        self.instance(type_id, name).is_some()
+    }
+
+    // Whether any instance of the type is attached
+    fn contains_type(&self, type_id: &TypeId) -> bool {
+        self.instances.contains_key(type_id)
+    }
+
+    fn cell(&self, type_id: &TypeId, name: &str) -> Option<FacetCell> {
+        self.instance(type_id, name).map(|instance| Arc::clone(&instance.cell))
+    }
+
+    fn cells_in_order(&self) -> Vec<(TypeId, String, FacetCell)> {
+        self.order.iter()
+            .filter_map(|(type_id, name)| Some((*type_id, name.clone(), self.cell(type_id, name)?)))
+            .collect()
+    }
+
+    fn insert(&mut self, type_id: TypeId, name: &str, facet: Box<dyn Facet>, expires_at: Option<Timestamp>) {
+        self.order.push((type_id, name.to_string()));
+        self.instances.entry(type_id).or_default().insert(name.to_string(), Arc::new(Instance {
+            cell: Arc::new(FacetLock::new(Some(facet))),
+            accesses: AtomicU64::new(0),
+            expires_at,
+            #[cfg(feature = "async")]
+            gate: AsyncGate::default(),
+        }));
+        self.generations.entry(type_id).or_default();
+        self.touch(type_id, name);
+    }
+
+    fn remove(&mut self, type_id: &TypeId, name: &str) -> Option<FacetCell> {
+        self.order.retain(|(attached, attached_name)| attached != type_id || attached_name != name);
+        let instances = self.instances.get_mut(type_id)?;
+        let removed = instances.remove(name);
+        if instances.is_empty() {
+            self.instances.remove(type_id);
+        }
+        // Generations are kept so they stay monotonic if the type is re-attached
+        removed.map(|instance| Arc::clone(&instance.cell))
+    }
+
+    fn touch(&self, type_id: TypeId, name: &str) {
+        if let Some(generation) = self.generations.get(&type_id) {
+            generation.fetch_add(1, Ordering::Relaxed);
+        }
+        self.record_access(&type_id, name);
+    }
+
+    fn record_access(&self, type_id: &TypeId, name: &str) {
+        if let Some(instance) = self.instance(type_id, name) {
+            instance.accesses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+pub(crate) fn downcast_ref<F: Facet>(slot: &FacetSlot) -> Result<&F, FacetError> {
+    let facet = slot.as_deref().ok_or(FacetError::NotFound { type_name: type_name::<F>() })?;
+    facet.as_any().downcast_ref::<F>().ok_or(FacetError::DowncastFailed { type_name: type_name::<F>() })
+}
+
+pub(crate) fn downcast_mut<F: Facet>(slot: &mut FacetSlot) -> Result<&mut F, FacetError> {
+    let facet = slot.as_deref_mut().ok_or(FacetError::NotFound { type_name: type_name::<F>() })?;
+    facet.as_any_mut().downcast_mut::<F>().ok_or(FacetError::DowncastFailed { type_name: type_name::<F>() })
+}
+
+// Only F itself being missing counts; a NotFound for another facet type,
+// e.g. from an interceptor, is passed on
+fn missing_as_none<F: Facet, R>(result: Result<R, FacetError>) -> Result<Option<R>, FacetError> {
+    match result {
+        Ok(result) => Ok(Some(result)),
+        Err(FacetError::NotFound { type_name }) if type_name == core::any::type_name::<F>() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+static NEXT_OBJECT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Faceted object that can have facets attached
+pub struct FacetedObject {
+    id: u64,
+    facets: CowCell<FacetStore>,
+    core_object: CoreCell,
+    pub(crate) observers: Observers,
+    pub(crate) interceptors: RwLock<Interceptors>,
+    // Time facet TTLs are measured against; TTLs can't be set without one
+    pub(crate) clock: Option<Arc<dyn Clock>>,
+    pub(crate) on_expired: Option<ExpiryCallback>,
+    #[cfg(feature = "std")]
+    admission: Option<WriteAdmission>,
+    #[cfg(feature = "std")]
+    pub(crate) guards: Guards,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<FacetMetrics>>,
+}
+
+impl FacetedObject {
+    pub fn new<T: Any + Send + Sync>(core: T) -> Self {
+        Self {
+            id: NEXT_OBJECT_ID.fetch_add(1, Ordering::Relaxed),
+            facets: CowCell::new(FacetStore::default()),
+            core_object: FacetLock::new(Box::new(core)),
+            observers: Arc::new(RwLock::new(Vec::new())),
+            interceptors: RwLock::new(Vec::new()),
+            #[cfg(feature = "std")]
+            clock: Some(Arc::new(SystemClock)),
+            #[cfg(not(feature = "std"))]
+            clock: None,
+            on_expired: None,
+            #[cfg(feature = "std")]
+            admission: None,
+            #[cfg(feature = "std")]
+            guards: Guards::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    // Unique within the process, e.g. to correlate traces and logs of one
+    // object; clones and restored objects get ids of their own
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    // Record operation counts, latencies and lock waits of this object's
+    // facets in `metrics`
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<FacetMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    // Run a facet operation, inside a span recording its outcome with the
+    // `tracing` feature and counted with `metrics`
+    pub(crate) fn instrumented<R>(
+        &self,
+        operation: &'static str,
+        facet: &'static str,
+        instance: &str,
+        body: impl FnOnce() -> Result<R, FacetError>,
+    ) -> Result<R, FacetError> {
+        #[cfg(feature = "tracing")]
+        let span = crate::trace::FacetSpan::enter(operation, self.id, facet, instance);
+        #[cfg(not(feature = "tracing"))]
+        let _ = instance;
+        #[cfg(feature = "metrics")]
+        let started = self.metrics.as_ref().map(|_| Instant::now());
+        let result = body();
+        #[cfg(feature = "tracing")]
+        span.finish(&result);
+        #[cfg(feature = "metrics")]
+        if let (Some(metrics), Some(started)) = (&self.metrics, started) {
+            metrics.observe_operation(operation, facet, started.elapsed(), result.is_ok());
+        }
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = (operation, facet);
+        result
+    }
+
+    // Take a facet's lock, recording the wait with `metrics`
+    fn lock_facet<G>(&self, facet: &'static str, access: &'static str, lock: impl FnOnce() -> Option<G>) -> Option<G> {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            let started = Instant::now();
+            let guard = lock();
+            metrics.observe_lock_wait(facet, access, started.elapsed());
+            return guard;
+        }
+        let _ = (facet, access);
+        lock()
+    }
+
+    // Bound concurrent mutable access to this object's facets; writers
+    // beyond the limits get a "Busy" error instead of piling up on the lock
+    #[cfg(feature = "std")]
+    pub fn with_write_limits(mut self, limits: WriteLimits) -> Self {
+        self.admission = Some(WriteAdmission::new(limits));
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn write_limits(&self) -> Option<WriteLimits> {
+        self.admission.as_ref().map(WriteAdmission::limits)
+    }
+
+    // Writers currently waiting for admission
+    #[cfg(feature = "std")]
+    pub fn queued_writes(&self) -> usize {
+        self.admission.as_ref().map_or(0, WriteAdmission::queued)
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn admit_write(&self) -> Result<Permit<'_>, FacetError> {
+        self.admission.as_ref().map(WriteAdmission::admit).transpose()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn admit_write(&self) -> Result<Permit<'_>, FacetError> {
+        Ok(PhantomData)
+    }
+
+    // Cell of instance `name` of facet F, counting the access. The table
+    // lock is released before the caller locks the cell.
+    fn cell<F: Facet>(&self, name: &str, mutating: bool) -> Result<FacetCell, FacetError> {
+        self.cell_of(TypeId::of::<F>(), type_name::<F>(), name, mutating)
+    }
+
+    // Run `operation` on instance `name` of F without interceptors, guards
+    // or write admission, for bookkeeping done on behalf of an access that
+    // already went through them, e.g. a transaction restoring a savepoint
+    pub(crate) fn with_facet_unchecked<F: Facet + 'static, R>(&self, name: &str, operation: impl FnOnce(&F) -> R) -> Result<R, FacetError> {
+        let cell = self.cell::<F>(name, false)?;
+        let slot = cell.read();
+        Ok(operation(downcast_ref::<F>(&slot)?))
+    }
+
+    pub(crate) fn with_facet_unchecked_mut<F: Facet + 'static, R>(&self, name: &str, operation: impl FnOnce(&mut F) -> R) -> Result<R, FacetError> {
+        let cell = self.cell::<F>(name, true)?;
+        let mut slot = cell.write();
+        let result = operation(downcast_mut::<F>(&mut slot)?);
+        drop(slot);
+        self.notify_mutation(TypeId::of::<F>());
+        Ok(result)
+    }
+
+    pub(crate) fn cell_of(&self, type_id: TypeId, type_name: &'static str, name: &str, mutating: bool) -> Result<FacetCell, FacetError> {
+        self.evict_if_expired(type_id, name);
+        let facets = self.facets.load();
+        let cell = facets.cell(&type_id, name).ok_or(FacetError::NotFound { type_name })?;
+
+        if mutating {
+            facets.touch(type_id, name);
+        } else {
+            facets.record_access(&type_id, name);
+        }
+        Ok(cell)
+    }
+
+    // Cell and async gate of the default instance of F, counting the access
+    #[cfg(feature = "async")]
+    pub(crate) fn async_cell<F: Facet>(&self, mutating: bool) -> Result<(FacetCell, AsyncGate), FacetError> {
+        let gate = self.facets.load()
+            .instance(&TypeId::of::<F>(), DEFAULT_INSTANCE)
+            .map(|instance| Arc::clone(&instance.gate))
+            .ok_or(FacetError::NotFound { type_name: type_name::<F>() })?;
+        Ok((self.cell::<F>(DEFAULT_INSTANCE, mutating)?, gate))
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn try_admit_write(&self) -> Result<Permit<'_>, FacetError> {
+        self.admission.as_ref().map(WriteAdmission::try_admit).transpose()
+    }
+
+    #[cfg(feature = "std")]
+    fn admit_write_waiting(&self, wait: LockWait) -> Result<Permit<'_>, FacetError> {
+        match wait {
+            LockWait::Block => self.admit_write(),
+            LockWait::Never => self.try_admit_write(),
+            LockWait::Until(deadline) => {
+                self.admission.as_ref().map(|admission| admission.admit_until(Some(deadline))).transpose()
+            }
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn admit_write_waiting(&self, _wait: LockWait) -> Result<Permit<'_>, FacetError> {
+        self.admit_write()
+    }
+
+    // Usage of every attached facet instance, in attach order
+    #[cfg(feature = "std")]
+    pub(crate) fn facet_usage(&self) -> Result<Vec<FacetUsage>, FacetError> {
+        let cells: Vec<(TypeId, String, FacetCell, u64, Option<Timestamp>)> = {
+            let facets = self.facets.load();
+            facets.cells_in_order()
+                .into_iter()
+                .map(|(type_id, name, cell)| {
+                    let instance = facets.instance(&type_id, &name);
+                    let accesses = instance.map_or(0, |instance| instance.accesses.load(Ordering::Relaxed));
+                    let ttl = instance.and_then(|instance| instance.expires_at);
+                    (type_id, name, cell, accesses, ttl)
+                })
+                .collect()
+        };
+
+        Ok(cells.into_iter()
+            .filter_map(|(type_id, name, cell, accesses, ttl)| {
+                let slot = cell.read();
+                let facet = slot.as_deref()?;
+                Some(FacetUsage {
+                    type_id,
+                    name,
+                    type_name: facet.facet_type_name(),
+                    accesses,
+                    // The earlier of the instance's TTL and the facet's own expiry
+                    expires_at: match (ttl, facet.expires_at()) {
+                        (Some(ttl), Some(own)) => Some(ttl.min(own)),
+                        (ttl, own) => ttl.or(own),
+                    },
+                    dependencies: facet.dependencies(),
+                })
+            })
+            .collect())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn detach_instance(&self, type_id: TypeId, name: &str) -> Result<Option<Box<dyn Facet>>, FacetError> {
+        let detached = {
+            let _permit = self.admit_write()?;
+            self.detach_cell(type_id, name)?
+        };
+        if detached.is_some() {
+            self.notify_layout(type_id);
+        }
+        Ok(detached)
+    }
+
+    // Run the facet's on_detach hook and remove it. Waits for in-flight
+    // accesses to the facet; the table is never updated while waiting on a
+    // facet, which would hold up other attaches and detaches.
+    fn detach_cell(&self, type_id: TypeId, name: &str) -> Result<Option<Box<dyn Facet>>, FacetError> {
+        let Some(cell) = self.facets.load().cell(&type_id, name) else {
+            return Ok(None);
+        };
+        let mut slot = cell.write();
+        let Some(facet) = slot.as_deref_mut() else {
+            return Ok(None);
+        };
+        facet.on_detach()?;
+
+        self.facets.update(|facets| Ok::<_, FacetError>(facets.remove(&type_id, name)))?;
+        Ok(slot.take())
+    }
+
+    // When the instance's TTL runs out, None if it has none
+    pub(crate) fn instance_expiry(&self, type_id: TypeId, name: &str) -> Option<Timestamp> {
+        self.facets.load().instance(&type_id, name).and_then(|instance| instance.expires_at)
+    }
+
+    // When the instance's TTL ran out, if it has
+    fn expiry(&self, instance: &Instance) -> Option<Timestamp> {
+        let expires_at = instance.expires_at?;
+        (expires_at <= self.clock.as_ref()?.now()).then_some(expires_at)
+    }
+
+    // Remove the instance if its TTL ran out, so expired facets are evicted
+    // the next time they are looked up
+    fn evict_if_expired(&self, type_id: TypeId, name: &str) {
+        let expired = {
+            let facets = self.facets.load();
+            facets.instance(&type_id, name)
+                .and_then(|instance| Some((Arc::clone(&instance.cell), self.expiry(instance)?)))
+        };
+        let Some((cell, expired_at)) = expired else {
+            return;
+        };
+
+        let mut slot = cell.write();
+        let removed = self.facets.update(|facets| {
+            // Another thread may have evicted, detached or re-attached it
+            if !facets.instance(&type_id, name).is_some_and(|instance| Arc::ptr_eq(&instance.cell, &cell)) {
+                return Err(());
+            }
+            Ok(facets.remove(&type_id, name))
+        });
+        if removed.is_err() {
+            return;
+        }
+        let Some(mut facet) = slot.take() else {
+            return;
+        };
+        drop(slot);
+
+        // Expiry can't be refused, so an on_detach error is ignored
+        let _ = facet.on_detach();
+        self.notify_layout(type_id);
+        if let Some(callback) = &self.on_expired {
+            callback(self, ExpiredFacet { name: name.to_string(), expired_at, facet });
+        }
+    }
+
+    // Times the facet has been attached or mutably accessed, None if absent
+    pub(crate) fn facet_generation(&self, type_id: TypeId) -> Option<u64> {
+        let facets = self.facets.load();
+        if !facets.contains_type(&type_id) {
+            return None;
+        }
+        facets.generations.get(&type_id).map(|generation| generation.load(Ordering::Relaxed))
+    }
+
+    // Attach a facet to this object
+    pub fn attach_facet<F: Facet + 'static>(&self, facet: F) -> Result<(), FacetError> {
+        self.attach_named_facet(DEFAULT_INSTANCE, facet)
+    }
+
+    // Attach another instance of a facet type under `name`, e.g. a savings
+    // account next to the default (checking) account
+    pub fn attach_named_facet<F: Facet + 'static>(&self, name: &str, facet: F) -> Result<(), FacetError> {
+        self.attach_boxed(TypeId::of::<F>(), name, Box::new(facet))
+    }
+
+    // Attach a batch of facets, each after the facets it depends on. The
+    // batch keeps its given order where dependencies allow, and nothing is
+    // attached if some dependency is neither attached nor in the batch.
+    pub fn attach_facets_ordered(&self, batch: Vec<Box<dyn Facet>>) -> Result<(), FacetError> {
+        self.attach_instances_ordered(batch.into_iter().map(|facet| (DEFAULT_INSTANCE.to_string(), facet, None)).collect())
+    }
+
+    // As attach_facets_ordered, for instances with a name and an optional
+    // absolute expiry each, e.g. when restoring a snapshot
+    pub(crate) fn attach_instances_ordered(&self, batch: Vec<(String, Box<dyn Facet>, Option<Timestamp>)>) -> Result<(), FacetError> {
+        let mut available: Vec<TypeId> = self.facets.load().order.iter().map(|(type_id, _)| *type_id).collect();
+        let mut pending = batch;
+        let mut ordered = Vec::with_capacity(pending.len());
+
+        while !pending.is_empty() {
+            let ready = pending.iter()
+                .position(|(_, facet, _)| facet.dependencies().iter().all(|dependency| available.contains(dependency)));
+            let Some(index) = ready else {
+                let (_, blocked, _) = &pending[0];
+                return Err(FacetError::MissingDependency {
+                    type_name: blocked.facet_type_name(),
+                    missing: missing_dependencies(blocked.as_ref(), |dependency| available.contains(dependency)),
+                });
+            };
+            let instance = pending.remove(index);
+            available.push(instance.1.as_any().type_id());
+            ordered.push(instance);
+        }
+
+        for (name, facet, expires_at) in ordered {
+            self.attach_boxed_until(facet.as_any().type_id(), &name, facet, expires_at)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn attach_boxed(&self, type_id: TypeId, name: &str, facet: Box<dyn Facet>) -> Result<(), FacetError> {
+        self.attach_boxed_until(type_id, name, facet, None)
+    }
+
+    // Attach an instance that is treated as absent from `expires_at` on
+    pub(crate) fn attach_boxed_until(
+        &self,
+        type_id: TypeId,
+        name: &str,
+        facet: Box<dyn Facet>,
+        expires_at: Option<Timestamp>,
+    ) -> Result<(), FacetError> {
+        self.instrumented("attach", facet.facet_type_name(), name, || {
+            self.insert_boxed(type_id, name, facet, expires_at).map_err(|(e, _)| e)
+        })
+    }
+
+    // Attach `facet`, handing it back with the error if that fails
+    fn insert_boxed(
+        &self,
+        type_id: TypeId,
+        name: &str,
+        facet: Box<dyn Facet>,
+        expires_at: Option<Timestamp>,
+    ) -> Result<(), (FacetError, Box<dyn Facet>)> {
+        self.evict_if_expired(type_id, name);
+        #[cfg(feature = "validation")]
+        if let Err(e) = self.validate_attach(facet.as_ref()) {
+            return Err((e, facet));
+        }
+        let core = self.core_object.read();
+        self.insert_with_core(core.as_ref(), type_id, name, facet, expires_at)?;
+        drop(core);
+        self.notify_layout(type_id);
+        Ok(())
+    }
+
+    // The table update of insert_boxed, for a caller already holding this
+    // object's core read lock; layout observers are left to the caller
+    fn insert_with_core(
+        &self,
+        core: &(dyn Any + Send + Sync),
+        type_id: TypeId,
+        name: &str,
+        mut facet: Box<dyn Facet>,
+        expires_at: Option<Timestamp>,
+    ) -> Result<(), (FacetError, Box<dyn Facet>)> {
+        self.facets.update(|facets| {
+            if facets.contains(&type_id, name) {
+                return Err((FacetError::AlreadyAttached { type_name: facet.facet_type_name() }, facet));
+            }
+            let missing = missing_dependencies(facet.as_ref(), |dependency| facets.contains_type(dependency));
+            if !missing.is_empty() {
+                return Err((FacetError::MissingDependency { type_name: facet.facet_type_name(), missing }, facet));
+            }
+            if let Err(e) = facet.on_attach(&FacetContext { core }) {
+                return Err((e, facet));
+            }
+            facets.insert(type_id, name, facet, expires_at);
+            Ok(())
+        })
+    }
+
+    // Run `operation` with a context over the core object, e.g. to build
+    // facets from it before attaching them
+    pub(crate) fn with_context<R>(&self, operation: impl FnOnce(&FacetContext<'_>) -> R) -> R {
+        let core = self.core_object.read();
+        operation(&FacetContext { core: core.as_ref() })
+    }
+
+    // Execute an operation that requires a specific facet (safe callback pattern)
+    pub fn with_facet<F: Facet + 'static, R>(
+        &self,
+        operation: impl FnOnce(&F) -> R
+    ) -> Result<R, FacetError> {
+        self.with_named_facet(DEFAULT_INSTANCE, operation)
+    }
+
+    pub fn with_named_facet<F: Facet + 'static, R>(
+        &self,
+        name: &str,
+        operation: impl FnOnce(&F) -> R
+    ) -> Result<R, FacetError> {
+        self.read_facet(name, LockWait::Block, operation)
+    }
+
+    // As with_facet, but fail with LockTimeout instead of waiting if the
+    // facet is being mutated
+    pub fn try_with_facet<F: Facet + 'static, R>(&self, operation: impl FnOnce(&F) -> R) -> Result<R, FacetError> {
+        self.read_facet(DEFAULT_INSTANCE, LockWait::Never, operation)
+    }
+
+    // As with_facet, but wait at most `timeout` for the facet's lock
+    #[cfg(feature = "std")]
+    pub fn with_facet_timeout<F: Facet + 'static, R>(
+        &self,
+        timeout: Duration,
+        operation: impl FnOnce(&F) -> R,
+    ) -> Result<R, FacetError> {
+        self.read_facet(DEFAULT_INSTANCE, LockWait::Until(Instant::now() + timeout), operation)
+    }
+
+    fn read_facet<F: Facet + 'static, R>(
+        &self,
+        name: &str,
+        wait: LockWait,
+        operation: impl FnOnce(&F) -> R,
+    ) -> Result<R, FacetError> {
+        self.instrumented("with_facet", type_name::<F>(), name, || {
+            let interception = self.intercept(FacetAccess::of::<F>(name, false))?;
+            let result = self.cell::<F>(name, false).and_then(|cell| {
+                let slot = self.lock_facet(type_name::<F>(), "read", || wait.read(&cell)).ok_or(FacetError::LockTimeout { type_name: type_name::<F>() })?;
+                Ok(operation(downcast_ref::<F>(&slot)?))
+            });
+            interception.finish(result)
+        })
+    }
+
+    // Execute a mutable operation on a facet. Only this facet is locked
+    // while the operation runs.
+    pub fn with_facet_mut<F: Facet + 'static, R>(
+        &self,
+        operation: impl FnOnce(&mut F) -> R
+    ) -> Result<R, FacetError> {
+        self.with_named_facet_mut(DEFAULT_INSTANCE, operation)
+    }
+
+    pub fn with_named_facet_mut<F: Facet + 'static, R>(
+        &self,
+        name: &str,
+        operation: impl FnOnce(&mut F) -> R
+    ) -> Result<R, FacetError> {
+        self.write_facet(name, LockWait::Block, operation)
+    }
+
+    // As with_facet_mut, but fail instead of waiting: with Busy if write
+    // limits are saturated, with LockTimeout if the facet is in use
+    pub fn try_with_facet_mut<F: Facet + 'static, R>(&self, operation: impl FnOnce(&mut F) -> R) -> Result<R, FacetError> {
+        self.write_facet(DEFAULT_INSTANCE, LockWait::Never, operation)
+    }
+
+    // As with_facet_mut, but wait at most `timeout` in total for write
+    // admission and the facet's lock
+    #[cfg(feature = "std")]
+    pub fn with_facet_mut_timeout<F: Facet + 'static, R>(
+        &self,
+        timeout: Duration,
+        operation: impl FnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        self.write_facet(DEFAULT_INSTANCE, LockWait::Until(Instant::now() + timeout), operation)
+    }
+
+    fn write_facet<F: Facet + 'static, R>(
+        &self,
+        name: &str,
+        wait: LockWait,
+        operation: impl FnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        self.instrumented("with_facet_mut", type_name::<F>(), name, || {
+            let interception = self.intercept(FacetAccess::of::<F>(name, true))?;
+            let result = self.admit_write_waiting(wait).and_then(|_permit| {
+                let type_id = TypeId::of::<F>();
+                let capture = self.change_capture(type_id);
+                let cell = self.cell::<F>(name, true)?;
+                let mut slot = self.lock_facet(type_name::<F>(), "write", || wait.write(&cell)).ok_or(FacetError::LockTimeout { type_name: type_name::<F>() })?;
+                let facet = downcast_mut::<F>(&mut slot)?;
+                let old = capture.as_ref().and_then(|capture| capture(facet.as_any()));
+                let result = operation(facet);
+                let new = capture.as_ref().and_then(|capture| capture(facet.as_any()));
+
+                drop(slot);
+                self.notify_mutation(type_id);
+                if let (Some(old), Some(new)) = (old, new) {
+                    self.notify_change(type_id, old.as_ref(), new.as_ref());
+                }
+                Ok(result)
+            });
+            interception.finish(result)
+        })
+    }
+
+    // As with_facet, but Ok(None) if F is not attached. Other failures,
+    // e.g. a denied access or a lock timeout, are still errors.
+    pub fn maybe_with_facet<F: Facet + 'static, R>(&self, operation: impl FnOnce(&F) -> R) -> Result<Option<R>, FacetError> {
+        missing_as_none::<F, R>(self.with_facet(operation))
+    }
+
+    // As with_facet_mut, but Ok(None) if F is not attached
+    pub fn maybe_with_facet_mut<F: Facet + 'static, R>(&self, operation: impl FnOnce(&mut F) -> R) -> Result<Option<R>, FacetError> {
+        missing_as_none::<F, R>(self.with_facet_mut(operation))
+    }
+
+    // As with_facet_mut, attaching F::default() first if F is not attached
+    pub fn with_facet_or_default<F: Facet + Default + 'static, R>(&self, operation: impl FnOnce(&mut F) -> R) -> Result<R, FacetError> {
+        self.with_facet_or_insert_with(F::default, operation)
+    }
+
+    // As with_facet_mut, attaching `make()` first if F is not attached.
+    // Concurrent callers all end up using the same facet; a facet made by
+    // a caller that lost the race to attach is dropped unused.
+    pub fn with_facet_or_insert_with<F: Facet + 'static, R>(
+        &self,
+        make: impl FnOnce() -> F,
+        operation: impl FnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        if !self.has_facet::<F>() {
+            match self.attach_facet(make()) {
+                Ok(()) | Err(FacetError::AlreadyAttached { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.with_facet_mut(operation)
+    }
+
+    // Remove a facet and hand it back, e.g. to move it to another object
+    pub fn detach_facet<F: Facet + 'static>(&self) -> Result<F, FacetError> {
+        self.detach_named_facet(DEFAULT_INSTANCE)
+    }
+
+    pub fn detach_named_facet<F: Facet + 'static>(&self, name: &str) -> Result<F, FacetError> {
+        self.instrumented("detach", type_name::<F>(), name, || {
+            let type_id = TypeId::of::<F>();
+            let facet = {
+                let _permit = self.admit_write()?;
+                let facet = self.detach_cell(type_id, name)?
+                    .ok_or(FacetError::NotFound { type_name: type_name::<F>() })?;
+
+                let facet: Box<dyn Any + Send + Sync> = facet;
+                match facet.downcast::<F>() {
+                    Ok(facet) => *facet,
+                    Err(_) => return Err(FacetError::DowncastFailed { type_name: type_name::<F>() }),
+                }
+            };
+
+            self.notify_layout(type_id);
+            Ok(facet)
+        })
+    }
+
+    // Move a facet to another object, e.g. an employee's account to the
+    // object built for their new department. Fails with AlreadyAttached,
+    // leaving both objects as they were, if `target` already has one. The
+    // facet's on_detach runs before it leaves and its on_attach against the
+    // target's core; if the target refuses it, it is put back here, running
+    // on_attach again, and the target's error is returned. Accessors of this
+    // object wait for the move instead of seeing the facet missing.
+    pub fn transfer_facet<F: Facet + 'static>(&self, target: &FacetedObject) -> Result<(), FacetError> {
+        self.transfer_named_facet::<F>(DEFAULT_INSTANCE, target)
+    }
+
+    pub fn transfer_named_facet<F: Facet + 'static>(&self, name: &str, target: &FacetedObject) -> Result<(), FacetError> {
+        self.instrumented("transfer", type_name::<F>(), name, || {
+            let type_id = TypeId::of::<F>();
+            if core::ptr::eq(self, target) || target.has_named_facet::<F>(name) {
+                return Err(FacetError::AlreadyAttached { type_name: type_name::<F>() });
+            }
+            let _permit = self.admit_write()?;
+            target.evict_if_expired(type_id, name);
+            // Both cores stay read-locked for the move. They are locked in
+            // id order, so opposite transfers between two objects cannot
+            // each hold one core while a queued writer blocks the other.
+            let (core, target_core) = if self.id < target.id {
+                let core = self.core_object.read();
+                (core, target.core_object.read())
+            } else {
+                let target_core = target.core_object.read();
+                (self.core_object.read(), target_core)
+            };
+            let cell = self.cell::<F>(name, true)?;
+            let expires_at = self.instance_expiry(type_id, name);
+
+            let mut slot = cell.write();
+            downcast_mut::<F>(&mut slot)?.on_detach()?;
+            let facet = slot.take().ok_or(FacetError::NotFound { type_name: type_name::<F>() })?;
+            #[cfg(feature = "validation")]
+            let inserted = match target.validate_attach(facet.as_ref()) {
+                Ok(()) => target.insert_with_core(target_core.as_ref(), type_id, name, facet, expires_at),
+                Err(e) => Err((e, facet)),
+            };
+            #[cfg(not(feature = "validation"))]
+            let inserted = target.insert_with_core(target_core.as_ref(), type_id, name, facet, expires_at);
+            if let Err((e, mut facet)) = inserted {
+                // Back to where it was; the target's error is the one to report
+                let _ = facet.on_attach(&FacetContext { core: core.as_ref() });
+                *slot = Some(facet);
+                return Err(e);
+            }
+
+            self.facets.update(|facets| Ok::<_, FacetError>(facets.remove(&type_id, name)))?;
+            drop(slot);
+            drop((core, target_core));
+            target.notify_layout(type_id);
+            self.notify_layout(type_id);
+            Ok(())
+        })
+    }
+
+    // Swap in a new instance of an attached facet, returning the old one,
+    // e.g. a PermissionFacet for a user's new role. The facet stays locked
+    // throughout, so other accessors see either the old or the new instance.
+    // Hooks run as for an attach of the new instance followed by a detach
+    // of the old one; if either fails the old instance stays attached.
+    pub fn swap_facet<F: Facet + 'static>(&self, facet: F) -> Result<F, FacetError> {
+        self.swap_named_facet(DEFAULT_INSTANCE, facet)
+    }
+
+    pub fn swap_named_facet<F: Facet + 'static>(&self, name: &str, mut facet: F) -> Result<F, FacetError> {
+        let core = self.core_object.read();
+        self.with_named_facet_mut::<F, _>(name, |current| {
+            facet.on_attach(&FacetContext { core: core.as_ref() })?;
+            current.on_detach()?;
+            Ok(core::mem::replace(current, facet))
+        })?
+    }
+
+    // swap_named_facet for a facet whose type is only known at runtime,
+    // e.g. one rebuilt through a FacetRegistry; `facet` must be of the
+    // attached instance's type
+    #[cfg(feature = "wasm")]
+    pub(crate) fn swap_boxed(&self, name: &str, mut facet: Box<dyn Facet>) -> Result<Box<dyn Facet>, FacetError> {
+        let type_id = facet.as_any().type_id();
+        let access = FacetAccess { type_id, type_name: facet.facet_type_name(), instance: name, mutable: true };
+        let interception = self.intercept(access)?;
+        let result = self.admit_write().and_then(|_permit| {
+            let not_found = FacetError::NotFound { type_name: facet.facet_type_name() };
+            let core = self.core_object.read();
+            self.evict_if_expired(type_id, name);
+            let cell = {
+                let facets = self.facets.load();
+                let cell = facets.cell(&type_id, name).ok_or(not_found.clone())?;
+                facets.touch(type_id, name);
+                cell
+            };
+            let mut slot = cell.write();
+            let current = slot.as_mut().ok_or(not_found)?;
+            facet.on_attach(&FacetContext { core: core.as_ref() })?;
+            current.on_detach()?;
+            let old = core::mem::replace(current, facet);
+
+            drop(slot);
+            drop(core);
+            self.notify_mutation(type_id);
+            Ok(old)
+        });
+        interception.finish(result)
+    }
+
+    // Check if a facet is attached
+    pub fn has_facet<F: Facet + 'static>(&self) -> bool {
+        self.has_named_facet::<F>(DEFAULT_INSTANCE)
+    }
+
+    pub fn has_named_facet<F: Facet + 'static>(&self, name: &str) -> bool {
+        self.evict_if_expired(TypeId::of::<F>(), name);
+        self.facets.load().contains(&TypeId::of::<F>(), name)
+    }
+
+    // Types of the attached facets in attach order, each listed once
+    // however many named instances it has
+    pub fn facet_type_ids(&self) -> Vec<TypeId> {
+        let facets = self.facets.load();
+        let mut type_ids: Vec<TypeId> = Vec::with_capacity(facets.order.len());
+        for (type_id, _) in &facets.order {
+            if !type_ids.contains(type_id) {
+                type_ids.push(*type_id);
+            }
+        }
+        type_ids
+    }
+
+    // Attached facet instances, counting every named instance
+    pub fn facet_count(&self) -> usize {
+        self.facets.load().order.len()
+    }
+
+    // Names of the attached instances of F, in sorted order
+    pub fn facet_instance_names<F: Facet + 'static>(&self) -> Vec<String> {
+        self.facets.load().instances.get(&TypeId::of::<F>())
+            .map(|instances| instances.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // Shared access to a facet that lasts as long as the returned guard.
+    // The guard read-locks this facet only: drop it before mutating the
+    // same facet.
+    pub fn facet_ref<F: Facet + 'static>(&self) -> Result<FacetRef<'_, F>, FacetError> {
+        #[cfg(feature = "std")]
+        self.check_guard(&FacetAccess::of::<F>(DEFAULT_INSTANCE, false))?;
+        let slot = self.cell::<F>(DEFAULT_INSTANCE, false)?.read_arc();
+        downcast_ref::<F>(&slot)?;
+        Ok(FacetRef { slot, _facet: PhantomData })
+    }
+
+    // Exclusive access to a facet that lasts as long as the returned guard.
+    // The guard write-locks this facet only: drop it before accessing the
+    // same facet again.
+    pub fn facet_mut<F: Facet + 'static>(&self) -> Result<FacetRefMut<'_, F>, FacetError> {
+        #[cfg(feature = "std")]
+        self.check_guard(&FacetAccess::of::<F>(DEFAULT_INSTANCE, true))?;
+        let permit = self.admit_write()?;
+        let mut slot = self.cell::<F>(DEFAULT_INSTANCE, true)?.write_arc();
+        downcast_mut::<F>(&mut slot)?;
+        Ok(FacetRefMut { slot: Some(slot), object: self, _permit: permit, _facet: PhantomData })
+    }
+
+    // Get the core object. The guard blocks with_core_mut and attaching
+    // facets until it is dropped.
+    pub fn get_core<T: 'static>(&self) -> Option<CoreRef<'_, T>> {
+        lock_api::RwLockReadGuard::try_map(self.core_object.read(), |core| core.downcast_ref::<T>()).ok()
+    }
+
+    // Update the core object in place, e.g. an employee's name or
+    // department, keeping the facets attached. `operation` must not access
+    // this object; facets are told through Facet::on_core_changed after it
+    // returns.
+    pub fn with_core_mut<T: 'static, R>(&self, operation: impl FnOnce(&mut T) -> R) -> Result<R, FacetError> {
+        let result = {
+            let mut core = self.core_object.write();
+            let core = core.downcast_mut::<T>().ok_or(FacetError::CoreTypeMismatch { type_name: type_name::<T>() })?;
+            operation(core)
+        };
+        self.notify_core_changed()?;
+        Ok(result)
+    }
+
+    // Swap the core object for another of the same type, returning the old one
+    pub fn replace_core<T: 'static>(&self, new: T) -> Result<T, FacetError> {
+        self.with_core_mut(|current: &mut T| core::mem::replace(current, new))
+    }
+
+    fn notify_core_changed(&self) -> Result<(), FacetError> {
+        let core = self.core_object.read();
+        let ctx = FacetContext { core: core.as_ref() };
+        self.for_each_facet_mut(|facet| facet.on_core_changed(&ctx))
+    }
+
+    // Visit every attached facet in attach order. Each facet is read-locked
+    // only while it is being visited.
+    pub fn visit_facets(&self, visitor: &mut dyn FacetVisitor) -> Result<(), FacetError> {
+        let cells = self.cells_in_order();
+
+        for (type_id, _, cell) in cells {
+            if let Some(facet) = cell.read().as_deref() {
+                visitor.visit(type_id, facet);
+            }
+        }
+        Ok(())
+    }
+
+    // Run `operation` on every attached facet in attach order, write-locking
+    // each only while it runs
+    pub(crate) fn for_each_facet_mut(&self, mut operation: impl FnMut(&mut dyn Facet)) -> Result<(), FacetError> {
+        let _permit = self.admit_write()?;
+        let cells = self.cells_in_order();
+
+        for (_, _, cell) in cells {
+            if let Some(facet) = cell.write().as_deref_mut() {
+                operation(facet);
+            }
+        }
+        Ok(())
+    }
+
+    // Summaries of all attached facets that implement Summarizable
+    pub fn summaries(&self) -> Result<Vec<FacetSummary>, FacetError> {
+        let mut collector = SummaryCollector::default();
+        self.visit_facets(&mut collector)?;
+        Ok(collector.summaries)
+    }
+
+    // Fields and values of every reflectable facet, in attach order
+    pub fn reflect(&self) -> Result<Vec<ReflectedFacet>, FacetError> {
+        let mut reflected = Vec::new();
+        self.visit_facets(&mut |_, facet: &dyn Facet| reflected.extend(ReflectedFacet::of(facet)))?;
+        Ok(reflected)
+    }
+
+    // Cell of the first facet in attach order matching `predicate`. Facets
+    // are only read-locked while they are checked.
+    pub(crate) fn find_cell(
+        &self,
+        predicate: impl Fn(&dyn Facet) -> bool,
+    ) -> Result<Option<(TypeId, String, FacetCell)>, FacetError> {
+        let cells = self.cells_in_order();
+        Ok(cells.into_iter().find(|(_, _, cell)| cell.read().as_deref().is_some_and(&predicate)))
+    }
+
+    // Every attached instance's cell, in attach order, skipping instances
+    // whose TTL ran out
+    pub(crate) fn cells_in_order(&self) -> Vec<(TypeId, String, FacetCell)> {
+        let facets = self.facets.load();
+        facets.cells_in_order()
+            .into_iter()
+            .filter(|(type_id, name, _)| {
+                facets.instance(type_id, name).is_some_and(|instance| self.expiry(instance).is_none())
+            })
+            .collect()
+    }
+
+    // Count an access made through a cell obtained from find_cell
+    pub(crate) fn record_instance_access(&self, type_id: TypeId, name: &str, mutating: bool) {
+        let facets = self.facets.load();
+        if mutating {
+            facets.touch(type_id, name);
+        } else {
+            facets.record_access(&type_id, name);
+        }
+    }
+
+    // Cell of the reflectable facet named `facet`
+    fn reflect_cell(&self, facet: &str) -> Result<(TypeId, String, FacetCell), FacetError> {
+        self.find_cell(|attached| attached.facet_name() == facet && attached.as_reflect().is_some())?
+            .ok_or_else(|| FacetError::UnknownFacet { name: facet.into() })
+    }
+
+    // Read a field of the reflectable facet named `facet`
+    pub fn get_field(&self, facet: &str, field: &str) -> Result<FieldValue, FacetError> {
+        let (_, _, cell) = self.reflect_cell(facet)?;
+        let slot = cell.read();
+
+        slot.as_deref()
+            .and_then(|attached| attached.as_reflect())
+            .ok_or_else(|| FacetError::UnknownFacet { name: facet.into() })?
+            .get_field(field)
+            .ok_or_else(|| FacetError::UnknownField { facet: facet.into(), field: field.into() })
+    }
+
+    // Update a field of the reflectable facet named `facet`
+    pub fn set_field(&self, facet: &str, field: &str, value: FieldValue) -> Result<(), FacetError> {
+        let (type_id, name, cell) = self.reflect_cell(facet)?;
+        let mut slot = cell.write();
+
+        slot.as_deref_mut()
+            .and_then(|attached| attached.as_reflect_mut())
+            .ok_or_else(|| FacetError::UnknownFacet { name: facet.into() })?
+            .set_field(field, value)?;
+
+        drop(slot);
+        self.record_instance_access(type_id, &name, true);
+        self.notify_mutation(type_id);
+        Ok(())
+    }
+}
+
+// Guard returned by FacetedObject::facet_ref, dereferencing to the facet
+pub struct FacetRef<'a, F> {
+    pub(crate) slot: FacetReadGuard<FacetSlot>,
+    pub(crate) _facet: PhantomData<&'a F>,
+}
+
+impl<F: Facet + 'static> Deref for FacetRef<'_, F> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        downcast_ref::<F>(&self.slot).expect("facet checked when the guard was created")
+    }
+}
+
+// Guard returned by FacetedObject::facet_mut, dereferencing to the facet.
+// Mutation observers run when the guard is dropped.
+pub struct FacetRefMut<'a, F: 'static> {
+    pub(crate) slot: Option<FacetWriteGuard<FacetSlot>>,
+    pub(crate) object: &'a FacetedObject,
+    pub(crate) _permit: Permit<'a>,
+    pub(crate) _facet: PhantomData<&'a mut F>,
+}
+
+impl<F: Facet + 'static> Deref for FacetRefMut<'_, F> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        self.slot.as_ref()
+            .and_then(|slot| downcast_ref::<F>(slot).ok())
+            .expect("facet checked when the guard was created")
+    }
+}
+
+impl<F: Facet + 'static> DerefMut for FacetRefMut<'_, F> {
+    fn deref_mut(&mut self) -> &mut F {
+        self.slot.as_mut()
+            .and_then(|slot| downcast_mut::<F>(slot).ok())
+            .expect("facet checked when the guard was created")
+    }
+}
+
+impl<F: 'static> Drop for FacetRefMut<'_, F> {
+    fn drop(&mut self) {
+        drop(self.slot.take());
+        self.object.notify_mutation(TypeId::of::<F>());
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet};
+
+    #[test]
+    fn test_facet_attachment() {
+        let employee = Employee::new("Test User", "TEST001", "Engineering");
+        let employee_obj = FacetedObject::new(employee);
+
+        // Test attaching facets
+        assert!(employee_obj.attach_facet(AccountFacet::new("ACC001")).is_ok());
+        assert!(employee_obj.has_facet::<AccountFacet>());
+
+        // Test duplicate attachment fails
+        assert!(employee_obj.attach_facet(AccountFacet::new("ACC002")).is_err());
+    }
+
+    #[test]
+    fn test_detach_and_swap_facet() {
+        let first = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        let second = FacetedObject::new(Employee::new("Other User", "TEST002", "Finance"));
+        first.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        first.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(75))).unwrap().unwrap();
+
+        // Move the account to another object
+        let account = first.detach_facet::<AccountFacet>().unwrap();
+        assert!(!first.has_facet::<AccountFacet>());
+        assert!(first.detach_facet::<AccountFacet>().is_err());
+        second.attach_facet(account).unwrap();
+
+        let old = second.swap_facet(AccountFacet::new("ACC002")).unwrap();
+        assert_eq!(old.get_balance(), Money::usd(75));
+        assert_eq!(second.with_facet::<AccountFacet, _>(|account| account.get_account_number().to_string()).unwrap(), "ACC002");
+        assert!(first.swap_facet(AccountFacet::new("ACC003")).is_err());
+
+        // Re-attaching after a detach puts the facet at the end of the order
+        second.attach_facet(PermissionFacet::new("employee")).unwrap();
+        second.attach_facet(second.detach_facet::<AccountFacet>().unwrap()).unwrap();
+        let titles: Vec<String> = second.summaries().unwrap().into_iter().map(|summary| summary.title).collect();
+        assert_eq!(titles, ["Permissions", "Account"]);
+    }
+
+    #[test]
+    fn test_summaries_follow_attach_order() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(PermissionFacet::new("employee")).unwrap();
+        employee_obj.attach_facet(AuditFacet::new()).unwrap();
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let titles: Vec<String> = employee_obj.summaries().unwrap()
+            .into_iter()
+            .map(|summary| summary.title)
+            .collect();
+        assert_eq!(titles, ["Permissions", "Audit", "Account"]);
+    }
+
+    #[derive(crate::Facet)]
+    struct Badge;
+
+    // Statement history that only makes sense on an account holder with
+    // permissions
+    struct Ledger;
+
+    impl Facet for Ledger {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn dependencies(&self) -> Vec<TypeId> {
+            vec![TypeId::of::<AccountFacet>(), TypeId::of::<PermissionFacet>()]
+        }
+    }
+
+    struct FacetNames(Vec<&'static str>);
+
+    impl FacetVisitor for FacetNames {
+        fn visit(&mut self, _: TypeId, facet: &dyn Facet) {
+            self.0.push(facet.facet_name());
+        }
+    }
+
+    #[test]
+    fn test_enumerate_facets() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(Badge).unwrap();
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let mut names = FacetNames(Vec::new());
+        employee_obj.visit_facets(&mut names).unwrap();
+        assert_eq!(names.0, [type_name::<Badge>(), "account"]);
+
+        employee_obj.attach_named_facet("savings", AccountFacet::new("SAV001")).unwrap();
+        assert_eq!(employee_obj.facet_count(), 3);
+        assert_eq!(employee_obj.facet_type_ids(), [TypeId::of::<Badge>(), TypeId::of::<AccountFacet>()]);
+        assert!(employee_obj.facet_ref::<AccountFacet>().unwrap().as_reflect().is_some());
+    }
+
+    #[test]
+    fn test_dependencies_checked_on_attach() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        assert_eq!(
+            employee_obj.attach_facet(Ledger),
+            Err(FacetError::MissingDependency {
+                type_name: type_name::<Ledger>(),
+                missing: vec![TypeId::of::<PermissionFacet>()],
+            }),
+        );
+
+        let batch: Vec<Box<dyn Facet>> = vec![Box::new(Ledger), Box::new(AuditFacet::new())];
+        assert!(employee_obj.attach_facets_ordered(batch).is_err());
+        assert!(!employee_obj.has_facet::<AuditFacet>());
+
+        let batch: Vec<Box<dyn Facet>> = vec![Box::new(Ledger), Box::new(PermissionFacet::new("employee"))];
+        employee_obj.attach_facets_ordered(batch).unwrap();
+        assert!(employee_obj.has_facet::<Ledger>());
+    }
+
+    #[test]
+    fn test_facets_lock_independently() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee_obj.attach_facet(AuditFacet::new()).unwrap();
+
+        // Holding one facet mutably leaves the others usable from any thread
+        let mut audit = employee_obj.facet_mut::<AuditFacet>().unwrap();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                employee_obj.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+            });
+        });
+        audit.log_operation("deposit", "10");
+        assert_eq!(employee_obj.facet_ref::<AccountFacet>().unwrap().get_balance(), Money::usd(10));
+        drop(audit);
+
+        assert_eq!(employee_obj.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 1);
+    }
+
+    // Facet whose on_attach moves `stage` to 1, then waits for it to be 2
+    struct SlowAttach {
+        stage: Arc<core::sync::atomic::AtomicUsize>,
+    }
+
+    impl Facet for SlowAttach {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn on_attach(&mut self, _ctx: &FacetContext<'_>) -> Result<(), FacetError> {
+            self.stage.store(1, Ordering::Release);
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while self.stage.load(Ordering::Acquire) != 2 {
+                if Instant::now() > deadline {
+                    return Err(FacetError::Invalid("reads were blocked by the attach".to_string()));
+                }
+                std::thread::yield_now();
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reads_proceed_during_attach() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        let stage = Arc::new(core::sync::atomic::AtomicUsize::new(0));
+
+        std::thread::scope(|scope| {
+            let attach = scope.spawn(|| employee_obj.attach_facet(SlowAttach { stage: Arc::clone(&stage) }));
+            while stage.load(Ordering::Acquire) != 1 {
+                std::thread::yield_now();
+            }
+            // Until the attach publishes, readers see the table without it
+            assert!(employee_obj.with_facet::<AccountFacet, _>(|account| account.get_balance()).is_ok());
+            assert!(!employee_obj.has_facet::<SlowAttach>());
+            stage.store(2, Ordering::Release);
+            attach.join().unwrap().unwrap();
+        });
+        assert!(employee_obj.has_facet::<SlowAttach>());
+        assert_eq!(employee_obj.facet_count(), 2);
+    }
+
+    // Badge printed from the employee's id, reprinted when the employee
+    // changes; refuses to be removed while the badge is still checked out
+    struct IdBadge {
+        printed: String,
+        checked_out: bool,
+    }
+
+    impl Facet for IdBadge {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn on_attach(&mut self, ctx: &FacetContext<'_>) -> Result<(), FacetError> {
+            let employee = ctx.core::<Employee>()
+                .ok_or(FacetError::CoreTypeMismatch { type_name: type_name::<Employee>() })?;
+            self.printed = format!("attached to {}", employee.id);
+            Ok(())
+        }
+
+        fn on_core_changed(&mut self, ctx: &FacetContext<'_>) {
+            if let Some(employee) = ctx.core::<Employee>() {
+                self.printed = format!("reprinted for {} in {}", employee.id, employee.department);
+            }
+        }
+
+        fn on_detach(&mut self) -> Result<(), FacetError> {
+            if self.checked_out {
+                return Err(FacetError::Invalid("Badge is checked out".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let badge = || IdBadge { printed: String::new(), checked_out: true };
+        assert!(FacetedObject::new("not an employee").attach_facet(badge()).is_err());
+
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(badge()).unwrap();
+        assert_eq!(employee_obj.facet_ref::<IdBadge>().unwrap().printed, "attached to TEST001");
+
+        assert_eq!(
+            employee_obj.detach_facet::<IdBadge>().err(),
+            Some(FacetError::Invalid("Badge is checked out".to_string())),
+        );
+        assert!(employee_obj.has_facet::<IdBadge>());
+
+        // Swapping runs both hooks and keeps the old badge if one fails
+        assert!(employee_obj.swap_facet(badge()).is_err());
+        assert_eq!(employee_obj.facet_ref::<IdBadge>().unwrap().printed, "attached to TEST001");
+        employee_obj.facet_mut::<IdBadge>().unwrap().checked_out = false;
+        let old = employee_obj.swap_facet(IdBadge { printed: String::new(), checked_out: false }).unwrap();
+        assert!(!old.checked_out);
+        assert_eq!(employee_obj.facet_ref::<IdBadge>().unwrap().printed, "attached to TEST001");
+
+        assert!(employee_obj.detach_facet::<IdBadge>().is_ok());
+    }
+
+    #[test]
+    fn test_transfer_moves_facets() {
+        let before = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        before.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        before.attach_facet(AuditFacet::new()).unwrap();
+        before.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(100))).unwrap().unwrap();
+        before.attach_facet(IdBadge { printed: String::new(), checked_out: false }).unwrap();
+
+        let after = FacetedObject::new(Employee::new("Test User", "TEST002", "Finance"));
+        before.transfer_facet::<AccountFacet>(&after).unwrap();
+        before.transfer_facet::<AuditFacet>(&after).unwrap();
+        before.transfer_facet::<IdBadge>(&after).unwrap();
+
+        assert_eq!(before.facet_count(), 0);
+        assert_eq!(after.facet_ref::<AccountFacet>().unwrap().get_balance(), Money::usd(100));
+        assert_eq!(after.facet_ref::<IdBadge>().unwrap().printed, "attached to TEST002");
+        assert!(matches!(before.transfer_facet::<AuditFacet>(&after), Err(FacetError::AlreadyAttached { .. })));
+    }
+
+    #[test]
+    fn test_failed_transfer_leaves_facet_in_place() {
+        let source = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        source.attach_facet(IdBadge { printed: String::new(), checked_out: true }).unwrap();
+        let occupied = FacetedObject::new(Employee::new("Other User", "TEST002", "Finance"));
+        occupied.attach_facet(IdBadge { printed: String::new(), checked_out: false }).unwrap();
+        let target = FacetedObject::new(Employee::new("Test User", "TEST003", "Finance"));
+
+        // Refused by the target, by the facet's own on_detach, and by the
+        // target's on_attach
+        assert!(matches!(source.transfer_facet::<IdBadge>(&occupied), Err(FacetError::AlreadyAttached { .. })));
+        assert!(matches!(source.transfer_facet::<IdBadge>(&target), Err(FacetError::Invalid(_))));
+        source.facet_mut::<IdBadge>().unwrap().checked_out = false;
+        let refused = source.transfer_facet::<IdBadge>(&FacetedObject::new("not an employee"));
+        assert!(matches!(refused, Err(FacetError::CoreTypeMismatch { .. })));
+
+        assert_eq!(source.facet_ref::<IdBadge>().unwrap().printed, "attached to TEST001");
+        assert!(!target.has_facet::<IdBadge>());
+        assert!(source.transfer_facet::<IdBadge>(&source).is_err());
+    }
+
+    #[test]
+    fn test_opposite_transfers_do_not_deadlock() {
+        let a = Arc::new(FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering")));
+        let b = Arc::new(FacetedObject::new(Employee::new("Other User", "TEST002", "Finance")));
+        a.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        b.attach_facet(AuditFacet::new()).unwrap();
+
+        // Each mover shuttles one facet back and forth while writers keep
+        // queueing on both cores
+        let movers: Vec<_> = [(Arc::clone(&a), Arc::clone(&b)), (Arc::clone(&b), Arc::clone(&a))].into_iter().enumerate()
+            .map(|(i, (from, to))| std::thread::spawn(move || {
+                for _ in 0..500 {
+                    if i == 0 {
+                        let _ = from.transfer_facet::<AccountFacet>(&to).and_then(|()| to.transfer_facet::<AccountFacet>(&from));
+                    } else {
+                        let _ = from.transfer_facet::<AuditFacet>(&to).and_then(|()| to.transfer_facet::<AuditFacet>(&from));
+                    }
+                }
+            }))
+            .collect();
+        let writers: Vec<_> = [Arc::clone(&a), Arc::clone(&b)].into_iter()
+            .map(|object| std::thread::spawn(move || {
+                for _ in 0..500 {
+                    object.with_core_mut(|employee: &mut Employee| employee.department.push('.')).unwrap();
+                }
+            }))
+            .collect();
+        for thread in movers.into_iter().chain(writers) {
+            thread.join().unwrap();
+        }
+
+        assert!(a.has_facet::<AccountFacet>() && b.has_facet::<AuditFacet>());
+    }
+
+    #[test]
+    fn test_swap_rotates_role() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(PermissionFacet::new("employee")).unwrap();
+        employee_obj.attach_named_facet("acting", PermissionFacet::new("employee")).unwrap();
+
+        let old = employee_obj.swap_named_facet("acting", PermissionFacet::new("manager")).unwrap();
+        assert_eq!(old.get_role(), "employee");
+        assert_eq!(employee_obj.with_named_facet::<PermissionFacet, _>("acting", |permissions| permissions.get_role().to_string()).unwrap(), "manager");
+        assert_eq!(employee_obj.with_facet::<PermissionFacet, _>(|permissions| permissions.get_role().to_string()).unwrap(), "employee");
+        assert!(employee_obj.swap_named_facet("missing", PermissionFacet::new("manager")).is_err());
+    }
+
+    #[test]
+    fn test_panicking_operation_leaves_object_usable() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            employee_obj.with_facet_mut::<AccountFacet, _>(|_| panic!("operation failed")).unwrap();
+        }));
+        assert!(panicked.is_err());
+
+        assert!(employee_obj.has_facet::<AccountFacet>());
+        employee_obj.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))).unwrap().unwrap();
+        assert_eq!(employee_obj.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(5));
+    }
+
+    #[test]
+    fn test_try_and_timeout_access() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        let locked = FacetError::LockTimeout { type_name: type_name::<AccountFacet>() };
+
+        let guard = employee_obj.facet_mut::<AccountFacet>().unwrap();
+        assert_eq!(employee_obj.try_with_facet::<AccountFacet, _>(|account| account.get_balance()), Err(locked.clone()));
+        assert_eq!(
+            employee_obj.with_facet_timeout::<AccountFacet, _>(std::time::Duration::from_millis(20), |account| account.get_balance()),
+            Err(locked.clone()),
+        );
+        drop(guard);
+
+        let reader = employee_obj.facet_ref::<AccountFacet>().unwrap();
+        assert!(employee_obj.try_with_facet::<AccountFacet, _>(|account| account.get_balance()).is_ok());
+        assert_eq!(employee_obj.try_with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(1))), Err(locked));
+        drop(reader);
+
+        employee_obj.with_facet_mut_timeout::<AccountFacet, _>(std::time::Duration::from_millis(20), |account| account.deposit(Money::usd(1)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(employee_obj.try_with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(1));
+    }
+
+    #[test]
+    fn test_named_facet_instances() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("CHK001")).unwrap();
+        employee_obj.attach_named_facet("savings", AccountFacet::new("SAV001")).unwrap();
+        assert!(employee_obj.attach_named_facet("savings", AccountFacet::new("SAV002")).is_err());
+
+        employee_obj.with_named_facet_mut::<AccountFacet, _>("savings", |savings| savings.deposit(Money::usd(300))).unwrap().unwrap();
+        assert_eq!(employee_obj.with_facet::<AccountFacet, _>(|checking| checking.get_balance()).unwrap(), Money::usd(0));
+        assert_eq!(employee_obj.with_named_facet::<AccountFacet, _>("savings", |savings| savings.get_balance()).unwrap(), Money::usd(300));
+        assert_eq!(employee_obj.facet_instance_names::<AccountFacet>(), ["default", "savings"]);
+        assert_eq!(employee_obj.summaries().unwrap().len(), 2);
+
+        let savings = employee_obj.detach_named_facet::<AccountFacet>("savings").unwrap();
+        assert_eq!(savings.get_account_number(), "SAV001");
+        assert!(employee_obj.has_facet::<AccountFacet>());
+        assert!(!employee_obj.has_named_facet::<AccountFacet>("savings"));
+    }
+
+    #[test]
+    fn test_maybe_with_facet() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        assert_eq!(employee_obj.maybe_with_facet::<AccountFacet, _>(|account| account.get_balance()), Ok(None));
+        assert_eq!(employee_obj.maybe_with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))), Ok(None));
+
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee_obj.maybe_with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))).unwrap().unwrap().unwrap();
+        assert_eq!(employee_obj.maybe_with_facet::<AccountFacet, _>(|account| account.get_balance()), Ok(Some(Money::usd(5))));
+
+        // A failing access is not mistaken for a missing facet
+        employee_obj.attach_facet(crate::RateLimiterFacet::per_minute(1)).unwrap();
+        employee_obj.add_interceptor(crate::RateLimitInterceptor::new().limit::<AccountFacet>()).unwrap();
+        assert_eq!(employee_obj.maybe_with_facet_mut::<AccountFacet, _>(|_| ()), Ok(Some(())));
+        assert!(matches!(employee_obj.maybe_with_facet_mut::<AccountFacet, _>(|_| ()), Err(FacetError::RateLimited { .. })));
+    }
+
+    #[test]
+    fn test_with_facet_or_default_attaches_once() {
+        let employee_obj = std::sync::Arc::new(FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering")));
+        let loggers: Vec<_> = (0..8).map(|i| {
+            let employee_obj = std::sync::Arc::clone(&employee_obj);
+            std::thread::spawn(move || {
+                employee_obj.with_facet_or_default::<AuditFacet, _>(|audit| audit.log_operation("login", &i.to_string())).unwrap();
+            })
+        }).collect();
+        for logger in loggers {
+            logger.join().unwrap();
+        }
+        assert_eq!(employee_obj.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 8);
+
+        let number = employee_obj.with_facet_or_insert_with(|| AccountFacet::new("ACC001"), |account| account.get_account_number().to_string());
+        assert_eq!(number.unwrap(), "ACC001");
+        let number = employee_obj.with_facet_or_insert_with(|| AccountFacet::new("ACC002"), |account| account.get_account_number().to_string());
+        assert_eq!(number.unwrap(), "ACC001");
+    }
+
+    #[test]
+    fn test_core_mutation() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(IdBadge { printed: String::new(), checked_out: false }).unwrap();
+
+        employee_obj.with_core_mut::<Employee, _>(|employee| employee.department = "Finance".to_string()).unwrap();
+        assert_eq!(employee_obj.get_core::<Employee>().unwrap().department, "Finance");
+        assert_eq!(employee_obj.facet_ref::<IdBadge>().unwrap().printed, "reprinted for TEST001 in Finance");
+
+        let previous = employee_obj.replace_core(Employee::new("Test User", "TEST002", "Sales")).unwrap();
+        assert_eq!(previous.id, "TEST001");
+        assert_eq!(employee_obj.facet_ref::<IdBadge>().unwrap().printed, "reprinted for TEST002 in Sales");
+        assert!(employee_obj.replace_core("not an employee").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Correlation ids tie together the audit entries written for one logical
+// operation, even when it touches several facets. Pipeline runs and
+// intercepted facet accesses open a scope on the calling thread; entries
+// recorded inside it, including those written by interceptors, event
+// handlers and nested accesses, carry its id.
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Id of the scope the current thread is in, if any
+pub fn current() -> Option<String> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+// Keeps a correlation id current on this thread until dropped
+pub struct CorrelationScope {
+    // Id to put back on drop; None if this scope reused the enclosing one
+    previous: Option<Option<String>>,
+}
+
+impl CorrelationScope {
+    // Join the enclosing scope, or start one with a new id if there is none
+    pub fn enter() -> Self {
+        if current().is_some() {
+            return Self { previous: None };
+        }
+        let id = format!("{:x}-{}", std::process::id(), NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        Self::with_id(&id)
+    }
+
+    // Start a scope with a given id, e.g. one received with a request
+    pub fn with_id(id: &str) -> Self {
+        let previous = CURRENT.with(|current| current.replace(Some(id.to_string())));
+        Self { previous: Some(previous) }
+    }
+
+    pub fn id(&self) -> String {
+        current().unwrap_or_default()
+    }
+}
+
+impl Drop for CorrelationScope {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_scopes_share_the_outer_id() {
+        assert_eq!(current(), None);
+        let outer = CorrelationScope::enter();
+        let id = outer.id();
+        {
+            let inner = CorrelationScope::enter();
+            assert_eq!(inner.id(), id);
+        }
+        assert_eq!(current(), Some(id));
+        drop(outer);
+        assert_eq!(current(), None);
+    }
+
+    #[test]
+    fn test_explicit_id_is_restored() {
+        let outer = CorrelationScope::with_id("request-7");
+        {
+            let _inner = CorrelationScope::with_id("retry");
+            assert_eq!(current().as_deref(), Some("retry"));
+        }
+        assert_eq!(outer.id(), "request-7");
+        assert_ne!(std::thread::spawn(current).join().unwrap().as_deref(), Some("request-7"));
+    }
+}
@@ -0,0 +1,176 @@
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+
+// Facet computed from other facets on the same object, e.g. net worth from
+// an account and loyalty points. It is recomputed whenever one of its
+// sources is attached or mutated.
+pub trait DerivedFacet: Send + Sync + Sized + 'static {
+    // Facet types the value is computed from
+    fn sources() -> Vec<TypeId>;
+
+    fn compute(object: &FacetedObject) -> Result<Self, FacetError>;
+}
+
+// Storage for a derived value plus the source generations it was computed
+// from, which is how staleness is detected
+pub struct Derived<D> {
+    value: D,
+    computed_from: Vec<(TypeId, Option<u64>)>,
+    last_error: Option<String>,
+}
+
+impl<D> Derived<D> {
+    pub fn value(&self) -> &D {
+        &self.value
+    }
+
+    // Why the most recent recompute failed, if it did
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+impl<D: DerivedFacet> Facet for Derived<D> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn source_generations<D: DerivedFacet>(object: &FacetedObject) -> Vec<(TypeId, Option<u64>)> {
+    D::sources()
+        .into_iter()
+        .map(|source| (source, object.facet_generation(source)))
+        .collect()
+}
+
+impl FacetedObject {
+    // Compute D now and keep it up to date as its sources change
+    pub fn attach_derived<D: DerivedFacet>(&self) -> Result<(), FacetError> {
+        let computed_from = source_generations::<D>(self);
+        let value = D::compute(self)?;
+        self.attach_facet(Derived { value, computed_from, last_error: None })?;
+
+        for source in D::sources() {
+            self.add_mutation_observer(source, Arc::new(|object: &FacetedObject| {
+                let _ = object.refresh_derived::<D>();
+            }))?;
+        }
+        Ok(())
+    }
+
+    // Recompute D from its sources. On failure the previous value is kept,
+    // the error is recorded and the value stays stale.
+    pub fn refresh_derived<D: DerivedFacet>(&self) -> Result<(), FacetError> {
+        let computed_from = source_generations::<D>(self);
+        match D::compute(self) {
+            Ok(value) => self.with_facet_mut::<Derived<D>, _>(|derived| {
+                derived.value = value;
+                derived.computed_from = computed_from;
+                derived.last_error = None;
+            }),
+            Err(e) => {
+                self.with_facet_mut::<Derived<D>, _>(|derived| derived.last_error = Some(e.to_string()))?;
+                Err(e)
+            }
+        }
+    }
+
+    pub fn with_derived<D: DerivedFacet, R>(&self, operation: impl FnOnce(&D) -> R) -> Result<R, FacetError> {
+        self.with_facet::<Derived<D>, R>(|derived| operation(&derived.value))
+    }
+
+    // Whether any source changed since D was last computed successfully
+    pub fn is_derived_stale<D: DerivedFacet>(&self) -> Result<bool, FacetError> {
+        let computed_from = self.with_facet::<Derived<D>, _>(|derived| derived.computed_from.clone())?;
+        Ok(computed_from != source_generations::<D>(self))
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Employee, Money};
+
+    struct LoyaltyPoints(u32);
+
+    impl Facet for LoyaltyPoints {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    // Balance plus points redeemable at one cent each; fails while the
+    // points would be worth more than $1000, to exercise staleness
+    struct NetWorth(Money);
+
+    impl DerivedFacet for NetWorth {
+        fn sources() -> Vec<TypeId> {
+            vec![TypeId::of::<AccountFacet>(), TypeId::of::<LoyaltyPoints>()]
+        }
+
+        fn compute(object: &FacetedObject) -> Result<Self, FacetError> {
+            let balance = object.with_facet::<AccountFacet, _>(|account| account.get_balance())?;
+            let points = object.with_facet::<LoyaltyPoints, _>(|points| points.0).unwrap_or(0);
+            if points > 100_000 {
+                return Err(FacetError::Invalid("Points need review".to_string()));
+            }
+            Ok(NetWorth(balance.checked_add(Money::from_minor(i64::from(points), balance.currency()))?))
+        }
+    }
+
+    // In cents
+    fn net_worth(object: &FacetedObject) -> i64 {
+        object.with_derived::<NetWorth, _>(|net_worth| net_worth.0.minor()).unwrap()
+    }
+
+    #[test]
+    fn test_derived_facet_follows_sources() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_derived::<NetWorth>().unwrap();
+        assert_eq!(net_worth(&employee), 0);
+
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(100))).unwrap().unwrap();
+        assert_eq!(net_worth(&employee), 10_000);
+
+        employee.attach_facet(LoyaltyPoints(250)).unwrap();
+        assert_eq!(net_worth(&employee), 10_250);
+
+        employee.facet_mut::<LoyaltyPoints>().unwrap().0 += 250;
+        assert_eq!(net_worth(&employee), 10_500);
+        assert!(!employee.is_derived_stale::<NetWorth>().unwrap());
+    }
+
+    #[test]
+    fn test_failed_recompute_leaves_value_stale() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(LoyaltyPoints(100)).unwrap();
+        employee.attach_derived::<NetWorth>().unwrap();
+
+        employee.with_facet_mut::<LoyaltyPoints, _>(|points| points.0 = 200_000).unwrap();
+        assert_eq!(net_worth(&employee), 100);
+        assert!(employee.is_derived_stale::<NetWorth>().unwrap());
+        assert_eq!(
+            employee.with_facet::<Derived<NetWorth>, _>(|derived| derived.last_error().map(str::to_string)).unwrap(),
+            Some("Points need review".to_string()),
+        );
+
+        employee.with_facet_mut::<LoyaltyPoints, _>(|points| points.0 = 300).unwrap();
+        assert_eq!(net_worth(&employee), 300);
+        assert!(!employee.is_derived_stale::<NetWorth>().unwrap());
+    }
+}
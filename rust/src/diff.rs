@@ -0,0 +1,223 @@
+// Differences between two snapshots, e.g. of one employee taken before and
+// after a batch job, or of the same object in two environments. Facets are
+// matched by name and instance; changes within a facet are reported per
+// field. Instances other than the default are labelled "name (instance)".
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::core::DEFAULT_INSTANCE;
+use crate::snapshot::{FacetedSnapshot, SerializedFacet};
+
+// One changed value, at a path such as "balances.USD" or "ledger[2]". A
+// value missing on one side is None there.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FacetChanges {
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+// What changed from one snapshot to another: facets only in the newer one
+// (added), only in the older one (removed), and field changes in the core
+// and in facets present in both
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FacetDiff {
+    pub core: Vec<FieldChange>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<FacetChanges>,
+}
+
+impl FacetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.core.is_empty() && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    // Changes to the facet named `name`, if it is in both snapshots and changed
+    pub fn facet(&self, name: &str) -> Option<&[FieldChange]> {
+        self.changed.iter()
+            .find(|facet| facet.name == name)
+            .map(|facet| facet.changes.as_slice())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn compare(path: String, before: Option<&Value>, after: Option<&Value>, changes: &mut Vec<FieldChange>) {
+    match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            for (key, value) in before {
+                compare(join(&path, key), Some(value), after.get(key), changes);
+            }
+            for (key, value) in after.iter().filter(|(key, _)| !before.contains_key(*key)) {
+                compare(join(&path, key), None, Some(value), changes);
+            }
+        }
+        (Some(Value::Array(before)), Some(Value::Array(after))) => {
+            for index in 0..before.len().max(after.len()) {
+                compare(format!("{}[{}]", path, index), before.get(index), after.get(index), changes);
+            }
+        }
+        (before, after) if before != after => {
+            changes.push(FieldChange { path, before: before.cloned(), after: after.cloned() });
+        }
+        _ => {}
+    }
+}
+
+fn same_instance(a: &SerializedFacet, b: &SerializedFacet) -> bool {
+    a.name == b.name && a.instance == b.instance
+}
+
+// The n-th facet in `facets` with the name and instance of `own[index]`,
+// where that is the n-th of them in its own snapshot
+fn counterpart<'a>(facets: &'a [SerializedFacet], own: &[SerializedFacet], index: usize) -> Option<&'a SerializedFacet> {
+    let facet = &own[index];
+    let nth = own[..index].iter().filter(|other| same_instance(other, facet)).count();
+    facets.iter().filter(|other| same_instance(other, facet)).nth(nth)
+}
+
+fn label(facet: &SerializedFacet) -> String {
+    if facet.instance == DEFAULT_INSTANCE {
+        facet.name.clone()
+    } else {
+        format!("{} ({})", facet.name, facet.instance)
+    }
+}
+
+impl FacetedSnapshot {
+    // Changes from this snapshot to `other`
+    pub fn diff(&self, other: &FacetedSnapshot) -> FacetDiff {
+        let mut diff = FacetDiff::default();
+        compare(String::new(), Some(&self.core), Some(&other.core), &mut diff.core);
+
+        for (index, facet) in self.facets.iter().enumerate() {
+            let Some(newer) = counterpart(&other.facets, &self.facets, index) else {
+                diff.removed.push(label(facet));
+                continue;
+            };
+            let mut changes = Vec::new();
+            compare(String::new(), Some(&facet.state), Some(&newer.state), &mut changes);
+            if !changes.is_empty() {
+                diff.changed.push(FacetChanges { name: label(facet), changes });
+            }
+        }
+        for (index, facet) in other.facets.iter().enumerate() {
+            if counterpart(&self.facets, &other.facets, index).is_none() {
+                diff.added.push(label(facet));
+            }
+        }
+        diff
+    }
+}
+
+fn describe(value: &Option<Value>) -> String {
+    value.as_ref().map_or_else(|| "(none)".to_string(), Value::to_string)
+}
+
+fn write_changes(f: &mut fmt::Formatter<'_>, changes: &[FieldChange]) -> fmt::Result {
+    for change in changes {
+        let path = if change.path.is_empty() { "(value)" } else { &change.path };
+        writeln!(f, "  {}: {} -> {}", path, describe(&change.before), describe(&change.after))?;
+    }
+    Ok(())
+}
+
+// One line per added or removed facet and per changed field, e.g.
+//
+//     core:
+//       department: "Engineering" -> "Finance"
+//     added: audit
+//     account:
+//       balances.USD: 100000 -> 75000
+impl fmt::Display for FacetDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no differences");
+        }
+        if !self.core.is_empty() {
+            writeln!(f, "core:")?;
+            write_changes(f, &self.core)?;
+        }
+        for name in &self.added {
+            writeln!(f, "added: {}", name)?;
+        }
+        for name in &self.removed {
+            writeln!(f, "removed: {}", name)?;
+        }
+        for facet in &self.changed {
+            writeln!(f, "{}:", facet.name)?;
+            write_changes(f, &facet.changes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, FacetedObject, Money, PermissionFacet};
+
+    #[test]
+    fn test_diff_reports_facet_and_field_changes() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(1000))).unwrap().unwrap();
+        let before = employee.snapshot::<Employee>().unwrap();
+
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(250))).unwrap().unwrap();
+        employee.swap_facet(PermissionFacet::new("admin")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee.with_core_mut(|core: &mut Employee| core.department = "Finance".to_string()).unwrap();
+        let after = employee.snapshot::<Employee>().unwrap();
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.core, [FieldChange { path: "department".to_string(), before: Some(json!("Engineering")), after: Some(json!("Finance")) }]);
+        assert_eq!(diff.added, ["audit"]);
+        assert!(diff.removed.is_empty());
+        let account = diff.facet("account").unwrap();
+        assert!(account.contains(&FieldChange { path: "balances.USD".to_string(), before: Some(json!(100000)), after: Some(json!(75000)) }));
+        assert!(account.iter().any(|change| change.path == "ledger[1]" && change.before.is_none()));
+        assert!(diff.facet("permissions").unwrap().iter().any(|change| change.after == Some(json!("admin"))));
+
+        assert!(before.diff(&before).is_empty());
+        assert_eq!(after.diff(&before).removed, ["audit"]);
+    }
+
+    #[test]
+    fn test_diff_renders_as_text_and_json() {
+        let snapshot = |role: &str, facets: Vec<SerializedFacet>| FacetedSnapshot {
+            core: json!({ "id": "TEST001" }),
+            facets: [vec![SerializedFacet::new("permissions", 1, json!({ "role": role }))], facets].concat(),
+        };
+        let notification = SerializedFacet::new("notification", 1, json!([]));
+        let diff = snapshot("manager", vec![notification]).diff(&snapshot("admin", vec![]));
+
+        assert_eq!(diff.to_string(), "removed: notification\npermissions:\n  role: \"manager\" -> \"admin\"\n");
+        let json: Value = serde_json::from_str(&diff.to_json()).unwrap();
+        assert_eq!(json["changed"][0]["changes"][0], json!({ "path": "role", "before": "manager", "after": "admin" }));
+        assert_eq!(FacetDiff::default().to_string(), "no differences\n");
+    }
+}
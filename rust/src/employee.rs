@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::builder::FacetPreset;
+use crate::facets::{Authorizer, StateMachineFacet};
+use crate::{AccountFacet, AuditFacet, FacetError, FacetedObject, PermissionFacet};
+
+// Example domain object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Employee {
+    pub name: String,
+    pub id: String,
+    pub department: String,
+}
+
+impl Employee {
+    pub fn new(name: &str, id: &str, department: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            id: id.to_string(),
+            department: department.to_string(),
+        }
+    }
+
+    // "standard_employee": an account numbered after the employee id,
+    // employee-level permissions and an audit trail
+    pub fn standard_preset() -> FacetPreset {
+        FacetPreset::new("standard_employee")
+            .try_with(|ctx| {
+                let employee = ctx.core::<Employee>().ok_or(FacetError::CoreTypeMismatch { type_name: "Employee" })?;
+                Ok(AccountFacet::new(&format!("ACC-{}", employee.id)))
+            })
+            .with(|_| PermissionFacet::new("employee"))
+            .with(|_| AuditFacet::new())
+    }
+
+    // Onboarding workflow: Applied -> Hired -> Active -> Terminated. Hiring
+    // needs "write" and terminating "delete" on the employee's permissions.
+    pub fn onboarding() -> StateMachineFacet<Onboarding, OnboardingEvent> {
+        StateMachineFacet::new(Onboarding::Applied)
+            .guarded_transition(Onboarding::Applied, OnboardingEvent::Hire, Onboarding::Hired, |object| {
+                require_permission(object, "hire", "write")
+            })
+            .transition(Onboarding::Hired, OnboardingEvent::Activate, Onboarding::Active)
+            .guarded_transition(Onboarding::Hired, OnboardingEvent::Terminate, Onboarding::Terminated, |object| {
+                require_permission(object, "terminate", "delete")
+            })
+            .guarded_transition(Onboarding::Active, OnboardingEvent::Terminate, Onboarding::Terminated, |object| {
+                require_permission(object, "terminate", "delete")
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Onboarding {
+    Applied,
+    Hired,
+    Active,
+    Terminated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingEvent {
+    Hire,
+    Activate,
+    Terminate,
+}
+
+// Objects without an Authorizer facet are denied
+fn require_permission(object: &FacetedObject, operation: &str, permission: &str) -> Result<(), FacetError> {
+    let allowed = object.with_facet_as::<dyn Authorizer, _>(|authorizer| authorizer.has_permission(permission)).unwrap_or(false);
+    if allowed {
+        Ok(())
+    } else {
+        Err(FacetError::PermissionDenied { operation: operation.to_string(), permission: permission.to_string() })
+    }
+}
@@ -0,0 +1,122 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::TypeId;
+use core::fmt;
+use core::time::Duration;
+
+use crate::money::{Currency, Money};
+use crate::reflect::FieldKind;
+
+// Failure modes of facet access and of the built-in facets' operations
+#[derive(Debug, Clone, PartialEq)]
+pub enum FacetError {
+    NotFound { type_name: &'static str },
+    AlreadyAttached { type_name: &'static str },
+    DowncastFailed { type_name: &'static str },
+    MissingFacets { type_names: Vec<&'static str> },
+    // Facet::dependencies of the facet being attached are not all attached
+    MissingDependency { type_name: &'static str, missing: Vec<TypeId> },
+    CoreTypeMismatch { type_name: &'static str },
+    // Facets of an object being deep-cloned that don't register CloneFacet
+    NotCloneable { type_names: Vec<&'static str> },
+    // Facet addressed by name (reflection, registries) is not attached
+    UnknownFacet { name: String },
+    // Object id with no entry in a registry
+    UnknownObject { id: String },
+    // Command name with no registered handler
+    UnknownCommand { name: String },
+    UnknownField { facet: String, field: String },
+    ReadOnlyField { facet: String, field: String },
+    FieldTypeMismatch { facet: String, field: String, expected: FieldKind, found: FieldKind },
+    // Write admission limits reached
+    Busy,
+    // The facet stayed locked by another accessor for the whole wait
+    LockTimeout { type_name: &'static str },
+    // Facet not among those locked together by lock_facets, or only
+    // locked for reading when `write` access was asked for
+    NotLocked { facet: &'static str, write: bool },
+    PermissionDenied { operation: String, permission: String },
+    // Try again once `retry_after` has passed
+    RateLimited { operation: String, retry_after: Duration },
+    InvalidAmount { amount: Money },
+    // No transition for `event` out of state `from` in a state machine
+    InvalidTransition { from: String, event: String },
+    InsufficientFunds { balance: Money, requested: Money },
+    // Account policy violations
+    WithdrawalLimitExceeded { requested: Money, limit: Money },
+    DailyLimitExceeded { withdrawn: Money, requested: Money, limit: Money },
+    // Enough funds, but part of them were deposited too recently
+    FundsOnHold { held: Money, requested: Money },
+    // Checked Money arithmetic left the representable range
+    Overflow,
+    CurrencyMismatch { expected: Currency, found: Currency },
+    NoExchangeRate { from: Currency, to: Currency },
+    // Persistence backend failed, e.g. an audit sink
+    Storage(String),
+    // Input rejected by validation
+    Invalid(String),
+    // Configuration file rejected; `entry` locates the offending entry,
+    // e.g. "roles.manager.inherits[0]" or "line 3, column 1"
+    InvalidConfig { entry: String, message: String },
+    // Error from a layer without its own variant
+    Other(String),
+}
+
+impl fmt::Display for FacetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FacetError::NotFound { type_name } => write!(f, "Required facet not found: {}", type_name),
+            FacetError::AlreadyAttached { type_name } => write!(f, "Facet of type {} already attached", type_name),
+            FacetError::DowncastFailed { type_name } => write!(f, "Failed to downcast facet {}", type_name),
+            FacetError::MissingFacets { type_names } => write!(f, "Required facets not attached: {}", type_names.join(", ")),
+            FacetError::MissingDependency { type_name, missing } => {
+                write!(f, "Cannot attach {}: {} required facet(s) not attached", type_name, missing.len())
+            }
+            FacetError::CoreTypeMismatch { type_name } => write!(f, "Core object is not of type {}", type_name),
+            FacetError::NotCloneable { type_names } => write!(f, "Facets cannot be cloned: {}", type_names.join(", ")),
+            FacetError::UnknownFacet { name } => write!(f, "Facet not found: {}", name),
+            FacetError::UnknownObject { id } => write!(f, "Object '{}' not found", id),
+            FacetError::UnknownCommand { name } => write!(f, "Command '{}' not registered", name),
+            FacetError::UnknownField { facet, field } => write!(f, "Unknown field '{}' on {}", field, facet),
+            FacetError::ReadOnlyField { facet, field } => write!(f, "Field '{}' on {} is read-only", field, facet),
+            FacetError::FieldTypeMismatch { facet, field, expected, found } => {
+                write!(f, "Field '{}' on {} expects {:?}, got {:?}", field, facet, expected, found)
+            }
+            FacetError::Busy => write!(f, "Busy: too many pending writes"),
+            FacetError::LockTimeout { type_name } => write!(f, "Timed out waiting for the lock on {}", type_name),
+            FacetError::NotLocked { facet, write: false } => write!(f, "{} is not locked", facet),
+            FacetError::NotLocked { facet, write: true } => write!(f, "{} is not locked for writing", facet),
+            FacetError::PermissionDenied { operation, permission } => {
+                write!(f, "Access denied: '{}' requires permission '{}'", operation, permission)
+            }
+            FacetError::RateLimited { operation, retry_after } => {
+                write!(f, "Rate limit exceeded for '{}', retry after {:?}", operation, retry_after)
+            }
+            FacetError::InvalidAmount { amount } => write!(f, "Amount must be positive, got {}", amount),
+            FacetError::InvalidTransition { from, event } => write!(f, "No transition from {} on {}", from, event),
+            FacetError::InsufficientFunds { balance, requested } => {
+                write!(f, "Insufficient funds: balance {}, requested {}", balance, requested)
+            }
+            FacetError::WithdrawalLimitExceeded { requested, limit } => {
+                write!(f, "Withdrawal of {} exceeds the limit of {}", requested, limit)
+            }
+            FacetError::DailyLimitExceeded { withdrawn, requested, limit } => {
+                write!(f, "Daily withdrawal limit of {} exceeded: {} withdrawn, requested {}", limit, withdrawn, requested)
+            }
+            FacetError::FundsOnHold { held, requested } => {
+                write!(f, "Funds on hold: {} of the balance is held, requested {}", held, requested)
+            }
+            FacetError::Overflow => write!(f, "Amount out of range"),
+            FacetError::CurrencyMismatch { expected, found } => {
+                write!(f, "Currency mismatch: expected {}, got {}", expected, found)
+            }
+            FacetError::NoExchangeRate { from, to } => write!(f, "No exchange rate from {} to {}", from, to),
+            FacetError::Storage(message) => write!(f, "Storage error: {}", message),
+            FacetError::InvalidConfig { entry, message } => write!(f, "Invalid configuration at {}: {}", entry, message),
+            FacetError::Invalid(message) | FacetError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl core::error::Error for FacetError {}
+
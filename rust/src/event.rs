@@ -0,0 +1,140 @@
+use core::any::{type_name, Any};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use serde_json::Value;
+
+#[cfg(feature = "std")]
+use crate::clock::Timestamp;
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+
+// Notification published to every facet of an object, e.g. a balance change
+// that audit or metrics facets react to without the account knowing them
+pub trait FacetEvent: Any + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    fn event_name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+}
+
+impl dyn FacetEvent {
+    pub fn downcast_ref<E: FacetEvent>(&self) -> Option<&E> {
+        self.as_any().downcast_ref::<E>()
+    }
+}
+
+// Durable record of a change to a facet's state, e.g. a deposit into an
+// account. Transactions collect them from the TransactionalFacets they
+// commit and append them to the object's AuditFacet, whose ordered log is
+// enough to rebuild those facets with TransactionalFacet::replay.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DomainEvent {
+    // Position in the log, starting at 1; 0 until appended
+    pub sequence: u64,
+    pub timestamp: Timestamp,
+    // Name and instance of the facet that changed
+    pub facet: String,
+    pub instance: String,
+    pub kind: String,
+    pub data: Value,
+}
+
+#[cfg(feature = "std")]
+impl DomainEvent {
+    // Sequence, timestamp and facet are filled in when the event is logged
+    pub fn new(kind: &str, data: Value) -> Self {
+        Self {
+            sequence: 0,
+            timestamp: Timestamp::UNIX_EPOCH,
+            facet: String::new(),
+            instance: String::new(),
+            kind: kind.to_string(),
+            data,
+        }
+    }
+}
+
+impl FacetedObject {
+    // Deliver `event` to Facet::on_event of every attached facet in attach
+    // order. Each facet is locked while it handles the event, so this must
+    // not be called while holding a guard on a facet of this object.
+    // Handlers' changes do not trigger mutation observers.
+    pub fn emit(&self, event: &dyn FacetEvent) -> Result<(), FacetError> {
+        self.for_each_facet_mut(|facet: &mut dyn Facet| facet.on_event(event))
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::facets::account::BalanceChanged;
+    use crate::{AccountFacet, AuditFacet, Employee, Money};
+
+    // Counts events by name
+    #[derive(Default)]
+    struct EventLog(alloc::vec::Vec<&'static str>);
+
+    impl Facet for EventLog {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn on_event(&mut self, event: &dyn FacetEvent) {
+            self.0.push(event.event_name());
+        }
+    }
+
+    struct Promoted;
+
+    impl FacetEvent for Promoted {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_emit_reaches_every_facet() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee.attach_facet(EventLog::default()).unwrap();
+
+        employee.emit(&Promoted).unwrap();
+        employee.emit(&BalanceChanged { account_number: "ACC001".to_string(), previous: Money::usd(0), balance: Money::usd(40) }).unwrap();
+
+        let names = employee.with_facet::<EventLog, _>(|log| log.0.clone()).unwrap();
+        assert_eq!(names, [type_name::<Promoted>(), type_name::<BalanceChanged>()]);
+
+        // The audit facet only records balance changes
+        let details: alloc::vec::Vec<String> = employee.with_facet::<AuditFacet, _>(|audit| {
+            audit.get_audit_trail().iter().map(|entry| entry.details.clone()).collect()
+        }).unwrap();
+        assert_eq!(details, ["Balance of ACC001 changed from 0.00 USD to 40.00 USD"]);
+    }
+
+    #[test]
+    fn test_typed_operation_publishes_balance_change() {
+        use crate::{EmployeeOperations, FinancialEmployee, PermissionFacet};
+
+        let employee = FinancialEmployee::require({
+            let object = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+            object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+            object.attach_facet(PermissionFacet::new("manager")).unwrap();
+            object.attach_facet(EventLog::default()).unwrap();
+            object
+        }).unwrap();
+
+        EmployeeOperations::perform_typed_financial_operation(&employee, |account| account.deposit(Money::usd(25))).unwrap();
+        let names = employee.object().with_facet::<EventLog, _>(|log| log.0.clone()).unwrap();
+        assert_eq!(names, [type_name::<BalanceChanged>()]);
+    }
+}
@@ -0,0 +1,139 @@
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use crate::error::FacetError;
+use crate::money::{Currency, Money};
+
+// Units of the target currency per unit of the source currency, in
+// millionths, e.g. 921_500 for 1 USD = 0.9215 EUR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExchangeRate {
+    micros: i64,
+}
+
+impl ExchangeRate {
+    pub const ONE: ExchangeRate = ExchangeRate { micros: 1_000_000 };
+
+    pub const fn from_micros(micros: i64) -> Self {
+        Self { micros }
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    // Rate for the opposite direction, rounded to the nearest millionth
+    pub fn inverse(&self) -> Option<ExchangeRate> {
+        (self.micros > 0).then(|| ExchangeRate { micros: div_round(1_000_000_000_000, i128::from(self.micros)) as i64 })
+    }
+
+    // Convert `amount` into `to`, rounding to the nearest minor unit
+    pub fn apply(&self, amount: Money, to: Currency) -> Result<Money, FacetError> {
+        let numerator = i128::from(amount.minor())
+            .checked_mul(i128::from(self.micros))
+            .and_then(|scaled| scaled.checked_mul(10_i128.pow(to.minor_units())))
+            .ok_or(FacetError::Overflow)?;
+        let denominator = 1_000_000 * 10_i128.pow(amount.currency().minor_units());
+        i64::try_from(div_round(numerator, denominator))
+            .map(|minor| Money::from_minor(minor, to))
+            .map_err(|_| FacetError::Overflow)
+    }
+}
+
+// Division rounding half away from zero, for a positive denominator
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let denominator = denominator.unsigned_abs();
+    let quotient = ((numerator.unsigned_abs() + denominator / 2) / denominator) as i128;
+    if numerator < 0 { -quotient } else { quotient }
+}
+
+// Source of exchange rates for AccountFacet conversions, e.g. a static
+// table or a client for a rates service
+pub trait ExchangeRateProvider: Send + Sync {
+    // None when the pair is not supported
+    fn rate(&self, from: Currency, to: Currency) -> Option<ExchangeRate>;
+
+    fn convert(&self, amount: Money, to: Currency) -> Result<Money, FacetError> {
+        if amount.currency() == to {
+            return Ok(amount);
+        }
+        self.rate(amount.currency(), to)
+            .ok_or(FacetError::NoExchangeRate { from: amount.currency(), to })?
+            .apply(amount, to)
+    }
+}
+
+impl fmt::Debug for dyn ExchangeRateProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExchangeRateProvider")
+    }
+}
+
+// Fixed rates. A pair without its own entry uses the inverse of the
+// opposite pair.
+#[derive(Debug, Clone, Default)]
+pub struct StaticRates {
+    rates: BTreeMap<(Currency, Currency), ExchangeRate>,
+}
+
+impl StaticRates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rate(mut self, from: Currency, to: Currency, rate: ExchangeRate) -> Self {
+        self.rates.insert((from, to), rate);
+        self
+    }
+}
+
+impl ExchangeRateProvider for StaticRates {
+    fn rate(&self, from: Currency, to: Currency) -> Option<ExchangeRate> {
+        if from == to {
+            return Some(ExchangeRate::ONE);
+        }
+        self.rates.get(&(from, to)).copied()
+            .or_else(|| self.rates.get(&(to, from)).and_then(ExchangeRate::inverse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates() -> StaticRates {
+        StaticRates::new()
+            .rate(Currency::USD, Currency::EUR, ExchangeRate::from_micros(921_500))
+            .rate(Currency::USD, Currency::JPY, ExchangeRate::from_micros(149_250_000))
+    }
+
+    #[test]
+    fn test_conversion_rounds_to_minor_units() {
+        let rates = rates();
+        assert_eq!(rates.convert(Money::usd(100), Currency::EUR).unwrap(), Money::from_minor(9_215, Currency::EUR));
+        assert_eq!(rates.convert(Money::from_minor(1, Currency::USD), Currency::JPY).unwrap(), Money::from_minor(1, Currency::JPY));
+        assert_eq!(rates.convert(Money::from_minor(-333, Currency::USD), Currency::EUR).unwrap().minor(), -307);
+        assert_eq!(rates.convert(Money::usd(5), Currency::USD).unwrap(), Money::usd(5));
+
+        // EUR -> USD falls back to the inverse of USD -> EUR
+        assert_eq!(rates.convert(Money::from_minor(9_215, Currency::EUR), Currency::USD).unwrap(), Money::usd(100));
+    }
+
+    #[test]
+    fn test_unsupported_pairs_and_overflow() {
+        let rates = rates();
+        assert_eq!(
+            rates.convert(Money::usd(1), Currency::GBP),
+            Err(FacetError::NoExchangeRate { from: Currency::USD, to: Currency::GBP }),
+        );
+        assert_eq!(
+            ExchangeRate::from_micros(2_000_000).apply(Money::from_minor(i64::MAX, Currency::USD), Currency::EUR),
+            Err(FacetError::Overflow),
+        );
+        assert_eq!(
+            ExchangeRate::from_micros(i64::MAX).apply(Money::from_minor(i64::MAX, Currency::JPY), Currency::new("BHD").unwrap()),
+            Err(FacetError::Overflow),
+        );
+        assert_eq!(ExchangeRate::from_micros(0).inverse(), None);
+    }
+}
@@ -0,0 +1,607 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::Facet;
+use crate::checkpoint::{captured, SnapshotFacet};
+use crate::clone::CloneFacet;
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::error::FacetError;
+use crate::event::{DomainEvent, FacetEvent};
+use crate::exchange::ExchangeRateProvider;
+use crate::facets::account_policy::{AccountPolicy, StandardPolicy};
+use crate::facets::ledger::{LedgerEntry, Statement};
+use crate::money::{Currency, Money};
+use crate::reflect::{check_writable, FieldInfo, FieldKind, FieldValue, ReflectFacet};
+use crate::snapshot::FacetMigration;
+use crate::summary::{FacetSummary, Summarizable};
+use crate::transaction::TransactionalFacet;
+
+// What AccountFacet does with amounts in a currency it holds no balance in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ForeignCurrency {
+    // Fail with FacetError::CurrencyMismatch
+    #[default]
+    Reject,
+    // Convert into the account currency with the account's exchange rates
+    Convert,
+}
+
+// Account facet for financial operations. Balances are kept per currency:
+// the account currency plus any currencies opened with hold_currency. Every
+// deposit and withdrawal is booked in a ledger.
+#[derive(Debug, Clone, Facet, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[facet(name = "account", description = "Balances per currency with a ledger of deposits and withdrawals", tags("financial"), summarize, reflect, serialize, version = 3, traits(CloneFacet, SnapshotFacet))]
+pub struct AccountFacet {
+    account_number: String,
+    currency: Currency,
+    // Minor units per held currency; always contains `currency`
+    balances: BTreeMap<Currency, i64>,
+    #[serde(default)]
+    foreign_currency: ForeignCurrency,
+    #[serde(default)]
+    ledger: Vec<LedgerEntry>,
+    #[serde(skip)]
+    rates: Option<Arc<dyn ExchangeRateProvider>>,
+    // Restored accounts stamp new entries from the system clock
+    #[serde(skip, default = "system_clock")]
+    clock: Arc<dyn Clock>,
+    // Policies are configuration, so restored accounts use the strict one
+    #[serde(skip, default = "strict_policy")]
+    policy: Arc<dyn AccountPolicy>,
+}
+
+fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+fn strict_policy() -> Arc<dyn AccountPolicy> {
+    Arc::new(StandardPolicy::strict())
+}
+
+// Published when an operation changed an account's balance
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChanged {
+    pub account_number: String,
+    pub previous: Money,
+    pub balance: Money,
+}
+
+impl FacetEvent for BalanceChanged {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl AccountFacet {
+    // USD account
+    pub fn new(account_number: &str) -> Self {
+        Self::with_currency(account_number, Currency::USD)
+    }
+
+    pub fn with_currency(account_number: &str, currency: Currency) -> Self {
+        Self {
+            account_number: account_number.to_string(),
+            currency,
+            balances: BTreeMap::from([(currency, 0)]),
+            foreign_currency: ForeignCurrency::default(),
+            ledger: Vec::new(),
+            rates: None,
+            clock: system_clock(),
+            policy: strict_policy(),
+        }
+    }
+
+    // Limits on withdrawals; StandardPolicy::strict() unless set
+    pub fn policy(mut self, policy: Arc<dyn AccountPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // Clock stamping ledger entries
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // Exchange rates for convert and ForeignCurrency::Convert
+    pub fn rates(mut self, rates: Arc<dyn ExchangeRateProvider>) -> Self {
+        self.rates = Some(rates);
+        self
+    }
+
+    pub fn foreign_currency(mut self, policy: ForeignCurrency) -> Self {
+        self.foreign_currency = policy;
+        self
+    }
+
+    // Keep a separate balance in `currency`; amounts in it are then
+    // deposited and withdrawn without conversion
+    pub fn hold_currency(mut self, currency: Currency) -> Self {
+        self.balances.entry(currency).or_insert(0);
+        self
+    }
+
+    // Deposit into the balance of the amount's currency. Amounts in a
+    // currency the account does not hold follow the ForeignCurrency policy.
+    // Returns the updated balance the amount was credited to.
+    pub fn deposit(&mut self, amount: Money) -> Result<Money, FacetError> {
+        self.deposit_with_memo(amount, "")
+    }
+
+    pub fn deposit_with_memo(&mut self, amount: Money, memo: &str) -> Result<Money, FacetError> {
+        if !amount.is_positive() {
+            return Err(FacetError::InvalidAmount { amount });
+        }
+        let amount = self.admit(amount)?;
+        let balance = self.balance_in(amount.currency()).checked_add(amount)?;
+        self.book(amount, balance, memo);
+        Ok(balance)
+    }
+
+    pub fn withdraw(&mut self, amount: Money) -> Result<Money, FacetError> {
+        self.withdraw_with_memo(amount, "")
+    }
+
+    pub fn withdraw_with_memo(&mut self, amount: Money, memo: &str) -> Result<Money, FacetError> {
+        if !amount.is_positive() {
+            return Err(FacetError::InvalidAmount { amount });
+        }
+        let requested = self.admit(amount)?;
+        let balance = self.balance_in(requested.currency());
+        let remaining = balance.checked_sub(requested)?;
+        self.check_policy(balance, requested, remaining)?;
+        self.book(requested.checked_neg()?, remaining, memo);
+        Ok(remaining)
+    }
+
+    // Apply the account policy to a withdrawal of `requested` from `balance`
+    fn check_policy(&self, balance: Money, requested: Money, remaining: Money) -> Result<(), FacetError> {
+        let currency = requested.currency();
+        if let Some(limit) = self.policy.max_withdrawal(currency).filter(|limit| requested > *limit) {
+            return Err(FacetError::WithdrawalLimitExceeded { requested, limit });
+        }
+        if let Some(limit) = self.policy.daily_withdrawal_cap(currency) {
+            let withdrawn = self.booked_within(currency, Duration::from_secs(24 * 60 * 60), Money::is_negative)?.checked_neg()?;
+            if withdrawn.checked_add(requested)? > limit {
+                return Err(FacetError::DailyLimitExceeded { withdrawn, requested, limit });
+            }
+        }
+
+        let floor = self.policy.overdraft_limit(currency).checked_neg()?;
+        if remaining < floor {
+            return Err(FacetError::InsufficientFunds { balance, requested });
+        }
+        let held = self.booked_within(currency, self.policy.hold_period(), Money::is_positive)?;
+        if remaining.checked_sub(held)? < floor {
+            return Err(FacetError::FundsOnHold { held, requested });
+        }
+        Ok(())
+    }
+
+    // Sum of the ledger amounts in `currency` booked less than `period` ago
+    // that pass `filter`. Replayed entries keep their original timestamps,
+    // so the ledger is not necessarily in time order and is scanned whole.
+    fn booked_within(&self, currency: Currency, period: Duration, filter: fn(&Money) -> bool) -> Result<Money, FacetError> {
+        let now = self.clock.now();
+        self.ledger.iter()
+            .filter(|entry| now.saturating_duration_since(entry.timestamp) < period)
+            .filter(|entry| entry.amount.currency() == currency && filter(&entry.amount))
+            .try_fold(Money::zero(currency), |total, entry| total.checked_add(entry.amount))
+    }
+
+    fn book(&mut self, amount: Money, balance: Money, memo: &str) {
+        self.balances.insert(balance.currency(), balance.minor());
+        self.ledger.push(LedgerEntry {
+            id: self.ledger.last().map_or(1, |entry| entry.id + 1),
+            timestamp: self.clock.now(),
+            amount,
+            balance,
+            memo: memo.to_string(),
+        });
+    }
+
+    // Every booked entry, oldest first
+    pub fn ledger(&self) -> &[LedgerEntry] {
+        &self.ledger
+    }
+
+    // Entries in the account currency booked within `period`
+    pub fn statement(&self, period: impl RangeBounds<Timestamp>) -> Result<Statement, FacetError> {
+        self.statement_in(self.currency, period)
+    }
+
+    pub fn statement_in(&self, currency: Currency, period: impl RangeBounds<Timestamp>) -> Result<Statement, FacetError> {
+        Statement::build(&self.account_number, currency, &self.ledger, period)
+    }
+
+    // `amount` as it will be booked: unchanged if its currency is held,
+    // otherwise converted into the account currency or rejected
+    fn admit(&self, amount: Money) -> Result<Money, FacetError> {
+        if self.balances.contains_key(&amount.currency()) {
+            return Ok(amount);
+        }
+        match self.foreign_currency {
+            ForeignCurrency::Reject => {
+                Err(FacetError::CurrencyMismatch { expected: self.currency, found: amount.currency() })
+            }
+            ForeignCurrency::Convert => self.convert(amount, self.currency),
+        }
+    }
+
+    // Convert with the account's exchange rates
+    pub fn convert(&self, amount: Money, to: Currency) -> Result<Money, FacetError> {
+        match &self.rates {
+            Some(rates) => rates.convert(amount, to),
+            None if amount.currency() == to => Ok(amount),
+            None => Err(FacetError::NoExchangeRate { from: amount.currency(), to }),
+        }
+    }
+
+    // Balance in the account currency
+    pub fn get_balance(&self) -> Money {
+        self.balance_in(self.currency)
+    }
+
+    // Zero for currencies the account does not hold
+    pub fn balance_in(&self, currency: Currency) -> Money {
+        Money::from_minor(self.balances.get(&currency).copied().unwrap_or(0), currency)
+    }
+
+    // Every held balance, ordered by currency code
+    pub fn balances(&self) -> Vec<Money> {
+        self.balances.iter().map(|(currency, minor)| Money::from_minor(*minor, *currency)).collect()
+    }
+
+    // All balances converted into the account currency
+    pub fn total_balance(&self) -> Result<Money, FacetError> {
+        self.balances().into_iter()
+            .try_fold(Money::zero(self.currency), |total, balance| total.checked_add(self.convert(balance, self.currency)?))
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn get_account_number(&self) -> &str {
+        &self.account_number
+    }
+}
+
+// Balances and the ledger length, which is truncated on rollback
+impl TransactionalFacet for AccountFacet {
+    type Savepoint = (BTreeMap<Currency, i64>, usize);
+
+    fn savepoint(&self) -> Self::Savepoint {
+        (self.balances.clone(), self.ledger.len())
+    }
+
+    fn rollback(&mut self, (balances, entries): Self::Savepoint) {
+        self.balances = balances;
+        self.ledger.truncate(entries);
+    }
+
+    // One "deposited" or "withdrawn" event per ledger entry booked since
+    // the savepoint, carrying the entry
+    fn changes_since(&self, (_, entries): &Self::Savepoint) -> Vec<DomainEvent> {
+        self.ledger.iter().skip(*entries)
+            .map(|entry| {
+                let kind = if entry.amount.is_positive() { "deposited" } else { "withdrawn" };
+                DomainEvent::new(kind, serde_json::to_value(entry).unwrap_or_default())
+            })
+            .collect()
+    }
+
+    // Books the entry again as it was, without the policy checks that
+    // passed when it was first booked
+    fn apply_event(&mut self, event: &DomainEvent) -> Result<(), FacetError> {
+        if event.kind != "deposited" && event.kind != "withdrawn" {
+            return Err(FacetError::Invalid(format!("account cannot apply {} events", event.kind)));
+        }
+        let entry: LedgerEntry = serde_json::from_value(event.data.clone())
+            .map_err(|e| FacetError::Invalid(format!("Invalid {} event {}: {}", event.kind, event.sequence, e)))?;
+        let balance = self.balance_in(entry.amount.currency()).checked_add(entry.amount)?;
+        self.book(entry.amount, balance, &entry.memo);
+        if let Some(booked) = self.ledger.last_mut() {
+            booked.timestamp = entry.timestamp;
+        }
+        Ok(())
+    }
+}
+
+// Captures the whole ledger rather than its length, so a checkpoint can
+// also be restored after entries past it were undone
+impl SnapshotFacet for AccountFacet {
+    fn capture(&self) -> Box<dyn Any + Send + Sync> {
+        Box::new((self.balances.clone(), self.ledger.clone()))
+    }
+
+    fn restore(&mut self, state: &(dyn Any + Send + Sync)) -> Result<(), FacetError> {
+        let (balances, ledger) = captured::<(BTreeMap<Currency, i64>, Vec<LedgerEntry>)>(state)?;
+        self.balances = balances.clone();
+        self.ledger = ledger.clone();
+        Ok(())
+    }
+}
+
+// Version 1 stored the balance as a floating-point number of dollars
+pub(crate) struct FloatBalanceToMoney;
+
+impl FacetMigration for FloatBalanceToMoney {
+    fn source_version(&self) -> u32 {
+        1
+    }
+
+    fn migrate(&self, mut state: Value) -> Result<Value, FacetError> {
+        let dollars = state["balance"].as_f64()
+            .ok_or_else(|| FacetError::Invalid("missing balance".to_string()))?;
+        state["balance"] = serde_json::to_value(Money::from_f64(dollars, Currency::USD)?)
+            .map_err(|e| FacetError::Invalid(e.to_string()))?;
+        Ok(state)
+    }
+}
+
+// Version 2 had a single Money balance
+pub(crate) struct SingleToPerCurrency;
+
+impl FacetMigration for SingleToPerCurrency {
+    fn source_version(&self) -> u32 {
+        2
+    }
+
+    fn migrate(&self, state: Value) -> Result<Value, FacetError> {
+        let balance: Money = serde_json::from_value(state["balance"].clone())
+            .map_err(|e| FacetError::Invalid(format!("Invalid balance: {}", e)))?;
+        Ok(serde_json::json!({
+            "account_number": state["account_number"],
+            "currency": balance.currency(),
+            "balances": { balance.currency().code(): balance.minor() },
+        }))
+    }
+}
+
+// Balance only changes through deposit/withdraw, so both fields are read-only
+impl ReflectFacet for AccountFacet {
+    fn fields(&self) -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::read_only("account_number", FieldKind::Text),
+            FieldInfo::read_only("balance", FieldKind::Number),
+        ]
+    }
+
+    fn get_field(&self, name: &str) -> Option<FieldValue> {
+        match name {
+            "account_number" => Some(FieldValue::Text(self.account_number.clone())),
+            "balance" => Some(FieldValue::Number(self.get_balance().to_f64())),
+            _ => None,
+        }
+    }
+
+    fn set_field(&mut self, name: &str, value: FieldValue) -> Result<(), FacetError> {
+        check_writable(self, name, &value)
+    }
+}
+
+impl Summarizable for AccountFacet {
+    fn summarize(&self) -> FacetSummary {
+        let summary = FacetSummary::new("Account")
+            .field("Number", &self.account_number)
+            .field("Balance", self.get_balance());
+        self.balances().into_iter()
+            .filter(|balance| balance.currency() != self.currency)
+            .fold(summary, |summary, balance| summary.field(&format!("Balance ({})", balance.currency()), balance))
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{BuiltinFacetAccess, Employee, FacetedObject, PermissionFacet};
+
+    #[test]
+    fn test_financial_operations() {
+        let employee = Employee::new("Test User", "TEST001", "Engineering");
+        let employee_obj = FacetedObject::new(employee);
+
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee_obj.attach_facet(PermissionFacet::new("manager")).unwrap();
+
+        // Test deposit
+        let result = employee_obj.with_facet_mut::<AccountFacet, Result<Money, FacetError>>(|account| {
+            account.deposit(Money::usd(1000))
+        }).unwrap();
+
+        assert_eq!(result.unwrap(), Money::usd(1000));
+
+        // Test balance check
+        let balance = employee_obj.with_facet::<AccountFacet, Money>(|account| {
+            account.get_balance()
+        }).unwrap();
+
+        assert_eq!(balance, Money::usd(1000));
+    }
+
+    #[test]
+    fn test_named_accessors() -> Result<(), FacetError> {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001"))?;
+
+        employee.account()?.deposit(Money::usd(100))?;
+        employee.account()?.withdraw(Money::usd(40))?;
+        assert_eq!(employee.account_ref()?.get_balance(), Money::usd(60));
+
+        assert!(employee.permissions().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_amount_errors_leave_balance_unchanged() {
+        let mut account = AccountFacet::new("ACC001");
+        account.deposit(Money::usd(50)).unwrap();
+
+        assert_eq!(account.deposit(Money::usd(0)), Err(FacetError::InvalidAmount { amount: Money::usd(0) }));
+        assert_eq!(
+            account.withdraw(Money::usd(80)).unwrap_err().to_string(),
+            "Insufficient funds: balance 50.00 USD, requested 80.00 USD",
+        );
+        assert_eq!(
+            account.deposit(Money::from_major(5, Currency::EUR).unwrap()),
+            Err(FacetError::CurrencyMismatch { expected: Currency::USD, found: Currency::EUR }),
+        );
+        assert_eq!(account.deposit(Money::from_minor(i64::MAX, Currency::USD)), Err(FacetError::Overflow));
+        assert_eq!(account.get_balance(), Money::usd(50));
+    }
+
+    #[test]
+    fn test_older_versions_migrate() {
+        use crate::{FacetRegistry, FacetedSnapshot};
+
+        let snapshot = FacetedSnapshot::from_json(r#"{
+            "core": {"name": "Test User", "id": "TEST001", "department": "Engineering"},
+            "facets": [{"name": "account", "state": {"account_number": "ACC001", "balance": 1234.56}}]
+        }"#).unwrap();
+
+        let restored = FacetedObject::restore::<Employee>(&snapshot, &FacetRegistry::builtin()).unwrap();
+        let balance = restored.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+        assert_eq!(balance, Money::from_minor(123_456, Currency::USD));
+        assert_eq!(restored.snapshot::<Employee>().unwrap().facets[0].version, 3);
+    }
+
+    #[test]
+    fn test_foreign_currency_policy() {
+        use crate::{ExchangeRate, StaticRates};
+
+        let rates = StaticRates::new()
+            .rate(Currency::EUR, Currency::USD, ExchangeRate::from_micros(1_100_000))
+            .rate(Currency::GBP, Currency::USD, ExchangeRate::from_micros(1_250_000));
+        let mut account = AccountFacet::new("ACC001").rates(Arc::new(rates)).hold_currency(Currency::EUR);
+        let eur = |major| Money::from_major(major, Currency::EUR).unwrap();
+        let gbp = |major| Money::from_major(major, Currency::GBP).unwrap();
+
+        // Held currencies are booked as they are
+        assert_eq!(account.deposit(eur(100)).unwrap(), eur(100));
+        assert!(matches!(account.withdraw(eur(150)), Err(FacetError::InsufficientFunds { .. })));
+        assert_eq!(
+            account.deposit(gbp(10)),
+            Err(FacetError::CurrencyMismatch { expected: Currency::USD, found: Currency::GBP }),
+        );
+
+        let mut account = account.foreign_currency(ForeignCurrency::Convert);
+        assert_eq!(account.deposit(gbp(10)).unwrap(), Money::from_minor(1_250, Currency::USD));
+        assert_eq!(account.balances(), [eur(100), Money::from_minor(1_250, Currency::USD)]);
+        assert_eq!(account.total_balance().unwrap(), Money::from_minor(12_250, Currency::USD));
+        assert_eq!(
+            account.convert(Money::from_major(1, Currency::JPY).unwrap(), Currency::USD),
+            Err(FacetError::NoExchangeRate { from: Currency::JPY, to: Currency::USD }),
+        );
+    }
+
+    #[test]
+    fn test_account_policy_limits() {
+        use crate::clock::ManualClock;
+
+        let clock = Arc::new(ManualClock::new(Timestamp::UNIX_EPOCH));
+        let policy = StandardPolicy::overdraft(Money::usd(100)).max_withdrawal(Money::usd(150)).daily_cap(Money::usd(200)).hold_for(Duration::from_secs(60));
+        let mut account = AccountFacet::new("ACC001").clock(clock.clone()).policy(Arc::new(policy));
+
+        account.deposit(Money::usd(50)).unwrap();
+        assert_eq!(account.withdraw(Money::usd(120)), Err(FacetError::FundsOnHold { held: Money::usd(50), requested: Money::usd(120) }));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(account.withdraw(Money::usd(140)).unwrap(), Money::usd(-90));
+        assert_eq!(account.withdraw(Money::usd(20)), Err(FacetError::InsufficientFunds { balance: Money::usd(-90), requested: Money::usd(20) }));
+        assert_eq!(account.withdraw(Money::usd(160)), Err(FacetError::WithdrawalLimitExceeded { requested: Money::usd(160), limit: Money::usd(150) }));
+
+        account.deposit(Money::usd(500)).unwrap();
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(
+            account.withdraw(Money::usd(80)),
+            Err(FacetError::DailyLimitExceeded { withdrawn: Money::usd(140), requested: Money::usd(80), limit: Money::usd(200) }),
+        );
+        clock.advance(Duration::from_secs(24 * 3600));
+        assert_eq!(account.withdraw(Money::usd(80)).unwrap(), Money::usd(330));
+
+        // Strict by default
+        assert!(matches!(AccountFacet::new("ACC002").withdraw(Money::usd(1)), Err(FacetError::InsufficientFunds { .. })));
+    }
+
+    #[test]
+    fn test_replayed_entries_do_not_hide_recent_ones() {
+        use crate::clock::ManualClock;
+
+        let clock = Arc::new(ManualClock::new(Timestamp::UNIX_EPOCH));
+        clock.advance(Duration::from_secs(2 * 24 * 3600));
+        let policy = StandardPolicy::strict().daily_cap(Money::usd(100));
+        let mut account = AccountFacet::new("ACC001").clock(clock).policy(Arc::new(policy));
+        account.deposit(Money::usd(500)).unwrap();
+        account.withdraw(Money::usd(80)).unwrap();
+
+        // An entry from two days ago lands after today's withdrawal
+        let old = LedgerEntry { id: 1, timestamp: Timestamp::UNIX_EPOCH, amount: Money::usd(10), balance: Money::usd(10), memo: String::new() };
+        account.apply_event(&DomainEvent::new("deposited", serde_json::to_value(old).unwrap())).unwrap();
+        assert_eq!(
+            account.withdraw(Money::usd(30)),
+            Err(FacetError::DailyLimitExceeded { withdrawn: Money::usd(80), requested: Money::usd(30), limit: Money::usd(100) }),
+        );
+    }
+
+    #[test]
+    fn test_policy_violations_are_audited() {
+        use crate::{AuditFacet, EmployeeOperations};
+
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        let policy = StandardPolicy::strict().max_withdrawal(Money::usd(25));
+        employee_obj.attach_facet(AccountFacet::new("ACC001").policy(Arc::new(policy))).unwrap();
+        employee_obj.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee_obj.attach_facet(AuditFacet::new()).unwrap();
+
+        EmployeeOperations::perform_financial_operation(&employee_obj, |account| account.deposit(Money::usd(100))).unwrap();
+        let result = EmployeeOperations::perform_financial_operation(&employee_obj, |account| account.withdraw(Money::usd(40)));
+        assert!(matches!(result, Err(FacetError::WithdrawalLimitExceeded { .. })));
+
+        let last = employee_obj.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().last().cloned()).unwrap().unwrap();
+        assert!(!last.is_success());
+        assert_eq!(last.details(), "Failed: Withdrawal of 40.00 USD exceeds the limit of 25.00 USD");
+        assert_eq!(employee_obj.account_ref().unwrap().get_balance(), Money::usd(100));
+    }
+
+    #[test]
+    fn test_account_rebuilt_from_events() {
+        use crate::{AuditFacet, EmployeeOperations};
+
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee_obj.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee_obj.attach_facet(AuditFacet::new()).unwrap();
+
+        EmployeeOperations::perform_financial_operation(&employee_obj, |account| account.deposit(Money::usd(100))).unwrap();
+        EmployeeOperations::perform_financial_operation(&employee_obj, |account| account.withdraw(Money::usd(500))).unwrap_err();
+        employee_obj.transaction(|tx| {
+            tx.with_facet_mut::<AccountFacet, _>(|account| account.withdraw_with_memo(Money::usd(30), "rent"))??;
+            tx.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5)))?
+        }).unwrap();
+
+        let events = employee_obj.with_facet::<AuditFacet, _>(|audit| audit.events().to_vec()).unwrap();
+        assert_eq!(events.iter().map(|event| event.kind.as_str()).collect::<Vec<_>>(), ["deposited", "withdrawn", "deposited"]);
+
+        let mut rebuilt = AccountFacet::new("ACC001");
+        rebuilt.replay(&events).unwrap();
+        let original = employee_obj.with_facet::<AccountFacet, _>(|account| account.clone()).unwrap();
+        assert_eq!(rebuilt.get_balance(), Money::usd(75));
+        assert_eq!(rebuilt.ledger(), original.ledger());
+
+        // Catching up from the last event seen
+        let mut incremental = AccountFacet::new("ACC001");
+        incremental.replay(&events[..1]).unwrap();
+        let rest = employee_obj.with_facet::<AuditFacet, _>(|audit| audit.events_since(events[0].sequence).to_vec()).unwrap();
+        incremental.replay(&rest).unwrap();
+        assert_eq!(incremental.ledger(), original.ledger());
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::money::{Currency, Money};
+
+// Limits AccountFacet enforces on withdrawals. Limits are given per
+// currency; a policy returns None (or zero overdraft) for currencies it has
+// no limit in.
+pub trait AccountPolicy: fmt::Debug + Send + Sync {
+    // How far below zero a balance in `currency` may go
+    fn overdraft_limit(&self, currency: Currency) -> Money {
+        Money::zero(currency)
+    }
+
+    // Largest single withdrawal
+    fn max_withdrawal(&self, _currency: Currency) -> Option<Money> {
+        None
+    }
+
+    // Most that can be withdrawn within any 24 hours
+    fn daily_withdrawal_cap(&self, _currency: Currency) -> Option<Money> {
+        None
+    }
+
+    // How long deposited funds are held before they can be withdrawn
+    fn hold_period(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+// Policy with fixed limits in one currency. `strict()`, the default, allows
+// no overdraft and sets no other limits, like accounts always did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StandardPolicy {
+    overdraft: Option<Money>,
+    max_withdrawal: Option<Money>,
+    daily_cap: Option<Money>,
+    hold: Duration,
+}
+
+fn in_currency(limit: Option<Money>, currency: Currency) -> Option<Money> {
+    limit.filter(|limit| limit.currency() == currency)
+}
+
+impl StandardPolicy {
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    // Balances in `limit`'s currency may go down to minus `limit`
+    pub fn overdraft(limit: Money) -> Self {
+        Self { overdraft: Some(limit), ..Self::default() }
+    }
+
+    pub fn max_withdrawal(mut self, limit: Money) -> Self {
+        self.max_withdrawal = Some(limit);
+        self
+    }
+
+    pub fn daily_cap(mut self, limit: Money) -> Self {
+        self.daily_cap = Some(limit);
+        self
+    }
+
+    pub fn hold_for(mut self, period: Duration) -> Self {
+        self.hold = period;
+        self
+    }
+}
+
+impl AccountPolicy for StandardPolicy {
+    fn overdraft_limit(&self, currency: Currency) -> Money {
+        in_currency(self.overdraft, currency).unwrap_or(Money::zero(currency))
+    }
+
+    fn max_withdrawal(&self, currency: Currency) -> Option<Money> {
+        in_currency(self.max_withdrawal, currency)
+    }
+
+    fn daily_withdrawal_cap(&self, currency: Currency) -> Option<Money> {
+        in_currency(self.daily_cap, currency)
+    }
+
+    fn hold_period(&self) -> Duration {
+        self.hold
+    }
+}
@@ -0,0 +1,496 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint::{captured, SnapshotFacet};
+use crate::clone::CloneFacet;
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::correlation;
+use crate::event::{DomainEvent, FacetEvent};
+use crate::facets::account::BalanceChanged;
+use crate::facets::audit_sink::{AuditQuery, AuditSink};
+use crate::interceptor::{FacetAccess, FacetInterceptor};
+use crate::{Facet, FacetError, FacetedObject};
+use crate::summary::{FacetSummary, Summarizable};
+use crate::transaction::TransactionalFacet;
+
+// Audit trail facet for tracking operations
+#[derive(Debug, Clone, Facet, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[facet(name = "audit", description = "Trail of the operations performed on the object", tags("compliance"), summarize, serialize, on_event = "Self::record_event", traits(Auditable, CloneFacet, Summarizable, SnapshotFacet))]
+pub struct AuditFacet {
+    entries: Vec<AuditEntry>,
+    // Domain events of committed transactions, in sequence order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    events: Vec<DomainEvent>,
+    // Restored audit trails stamp new entries from the system clock
+    #[serde(skip, default = "system_clock")]
+    clock: Arc<dyn Clock>,
+    // Entries are written through to the sink as they are logged
+    #[serde(skip)]
+    sink: Option<Arc<dyn AuditSink>>,
+    // First write-through failure since the last successful flush
+    #[serde(skip)]
+    sink_error: Option<FacetError>,
+}
+
+// What operations need from an audit trail, so another implementation can
+// stand in for AuditFacet; look it up with `with_facet_as::<dyn Auditable, _>`
+pub trait Auditable {
+    fn log_operation(&mut self, operation: &str, details: &str);
+
+    // Record a structured entry; implementations that only keep plain
+    // entries get its operation and details
+    fn record(&mut self, entry: AuditEntry) {
+        self.log_operation(&entry.operation, &entry.details);
+    }
+
+    // Keep the domain events of a committed transaction; implementations
+    // without an event log drop them
+    fn append_events(&mut self, _events: Vec<DomainEvent>) {}
+}
+
+fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+impl std::fmt::Debug for dyn AuditSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuditSink")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        [Severity::Info, Severity::Warning, Severity::Critical].into_iter().find(|severity| severity.as_str() == name)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Whether the audited operation succeeded
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    #[default]
+    Success,
+    Failure(String),
+}
+
+// Entries written before these fields existed deserialize with defaults:
+// no actor, info severity, success and no correlation id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuditEntry {
+    pub(crate) timestamp: Timestamp,
+    pub(crate) operation: String,
+    pub(crate) details: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) actor: Option<String>,
+    #[serde(default)]
+    pub(crate) severity: Severity,
+    #[serde(default)]
+    pub(crate) outcome: Outcome,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) attributes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) correlation_id: Option<String>,
+}
+
+impl AuditEntry {
+    // Successful info entry. AuditFacet::record stamps it and fills in the
+    // current correlation id unless one was set.
+    pub fn new(operation: &str, details: &str) -> Self {
+        Self {
+            timestamp: Timestamp::UNIX_EPOCH,
+            operation: operation.to_string(),
+            details: details.to_string(),
+            actor: None,
+            severity: Severity::Info,
+            outcome: Outcome::Success,
+            attributes: BTreeMap::new(),
+            correlation_id: None,
+        }
+    }
+
+    pub fn actor(mut self, actor: &str) -> Self {
+        self.actor = Some(actor.to_string());
+        self
+    }
+
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    // Mark the operation as failed; raises the severity to warning
+    pub fn failed(mut self, reason: impl fmt::Display) -> Self {
+        self.outcome = Outcome::Failure(reason.to_string());
+        self.severity = self.severity.max(Severity::Warning);
+        self
+    }
+
+    pub fn attribute(mut self, key: &str, value: impl fmt::Display) -> Self {
+        self.attributes.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn correlation_id(mut self, id: &str) -> Self {
+        self.correlation_id = Some(id.to_string());
+        self
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+
+    pub fn get_actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn outcome(&self) -> &Outcome {
+        &self.outcome
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.outcome == Outcome::Success
+    }
+
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+
+    pub fn get_correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+}
+
+// e.g. "2024-01-01T00:00:00.000Z WARNING withdraw failed (Insufficient
+// funds: ...): Withdrew 50.00 USD [actor=alice correlation=1f-3 teller=7]"
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.timestamp, self.severity.as_str().to_uppercase(), self.operation)?;
+        if let Outcome::Failure(reason) = &self.outcome {
+            write!(f, " failed ({})", reason)?;
+        }
+        write!(f, ": {}", self.details)?;
+
+        let context: Vec<String> = self.actor.iter().map(|actor| format!("actor={}", actor))
+            .chain(self.correlation_id.iter().map(|id| format!("correlation={}", id)))
+            .chain(self.attributes.iter().map(|(key, value)| format!("{}={}", key, value)))
+            .collect();
+        if !context.is_empty() {
+            write!(f, " [{}]", context.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for AuditFacet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditFacet {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    // Audit facet stamping entries from the given clock
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: Vec::new(),
+            events: Vec::new(),
+            clock,
+            sink: None,
+            sink_error: None,
+        }
+    }
+
+    // Audit facet continuing the trail stored in `sink`, e.g. after a restart
+    pub fn from_sink(sink: Arc<dyn AuditSink>) -> Result<Self, FacetError> {
+        let mut audit = Self::new();
+        audit.entries = sink.query(&AuditQuery::new())?;
+        Ok(audit.sink(sink))
+    }
+
+    // Write every new entry through to `sink`. Wrap slow backends in an
+    // AsyncSink so logging does not wait on them.
+    pub fn sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    pub fn log_operation(&mut self, operation: &str, details: &str) {
+        self.record(AuditEntry::new(operation, details));
+    }
+
+    pub fn record(&mut self, mut entry: AuditEntry) {
+        entry.timestamp = self.clock.now();
+        if entry.correlation_id.is_none() {
+            entry.correlation_id = correlation::current();
+        }
+        if let Some(Err(e)) = self.sink.as_ref().map(|sink| sink.append(&entry)) {
+            self.sink_error.get_or_insert(e);
+        }
+        self.entries.push(entry);
+    }
+
+    // Wait until the sink has stored every entry; reports the first
+    // write-through failure since the last flush
+    pub fn flush(&mut self) -> Result<(), FacetError> {
+        if let Some(e) = self.sink_error.take() {
+            return Err(e);
+        }
+        self.sink.as_ref().map_or(Ok(()), |sink| sink.flush())
+    }
+
+    // Events worth an audit entry; everything else is ignored
+    fn record_event(&mut self, event: &dyn FacetEvent) {
+        if let Some(change) = event.downcast_ref::<BalanceChanged>() {
+            let details = format!(
+                "Balance of {} changed from {} to {}",
+                change.account_number, change.previous, change.balance,
+            );
+            self.log_operation("balance_changed", &details);
+        }
+    }
+
+    pub fn get_audit_trail(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    // The page of entries matching `query`
+    pub fn query(&self, query: &AuditQuery) -> Vec<&AuditEntry> {
+        query.select_refs(&self.entries)
+    }
+
+    // Entries passing the query's filters, ignoring its offset and limit
+    pub fn count(&self, query: &AuditQuery) -> usize {
+        self.entries.iter().filter(|entry| query.matches(entry)).count()
+    }
+
+    pub fn get_recent_entries(&self, count: usize) -> &[AuditEntry] {
+        let start = if self.entries.len() > count {
+            self.entries.len() - count
+        } else {
+            0
+        };
+        &self.entries[start..]
+    }
+
+    // Number `events` on from the last logged one and stamp them
+    pub fn append_events(&mut self, events: impl IntoIterator<Item = DomainEvent>) {
+        let now = self.clock.now();
+        for mut event in events {
+            event.sequence = self.last_sequence() + 1;
+            event.timestamp = now;
+            self.events.push(event);
+        }
+    }
+
+    pub fn events(&self) -> &[DomainEvent] {
+        &self.events
+    }
+
+    // Events logged after `sequence`, for consumers that remember the last
+    // one they processed; 0 gives the whole log
+    pub fn events_since(&self, sequence: u64) -> &[DomainEvent] {
+        &self.events[self.events.partition_point(|event| event.sequence <= sequence)..]
+    }
+
+    // Sequence of the newest event, 0 if there is none
+    pub fn last_sequence(&self) -> u64 {
+        self.events.last().map_or(0, |event| event.sequence)
+    }
+}
+
+impl Auditable for AuditFacet {
+    fn log_operation(&mut self, operation: &str, details: &str) {
+        AuditFacet::log_operation(self, operation, details);
+    }
+
+    fn record(&mut self, entry: AuditEntry) {
+        AuditFacet::record(self, entry);
+    }
+
+    fn append_events(&mut self, events: Vec<DomainEvent>) {
+        AuditFacet::append_events(self, events);
+    }
+}
+
+// Entries are only ever appended, so rolling back drops the newer ones.
+// They stay in the sink, which is append-only.
+impl TransactionalFacet for AuditFacet {
+    type Savepoint = usize;
+
+    fn savepoint(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn rollback(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+}
+
+// Restoring does not remove entries from the sink either
+impl SnapshotFacet for AuditFacet {
+    fn capture(&self) -> Box<dyn Any + Send + Sync> {
+        Box::new(self.entries.clone())
+    }
+
+    fn restore(&mut self, state: &(dyn Any + Send + Sync)) -> Result<(), FacetError> {
+        self.entries = captured::<Vec<AuditEntry>>(state)?.clone();
+        Ok(())
+    }
+}
+
+impl Summarizable for AuditFacet {
+    fn summarize(&self) -> FacetSummary {
+        self.get_recent_entries(3).iter().fold(
+            FacetSummary::new("Audit").field("Entries", self.entries.len()),
+            |summary, entry| summary.item(format!("{}: {} ({})", entry.timestamp, entry.operation, entry.details)),
+        )
+    }
+}
+
+// Writes an entry to the object's audit trail, if it has one, for every
+// mutable access to its other facets, so callers need not log by hand
+pub struct AuditInterceptor;
+
+impl FacetInterceptor for AuditInterceptor {
+    fn name(&self) -> &str {
+        "audit"
+    }
+
+    fn after(&self, object: &FacetedObject, access: &FacetAccess<'_>, outcome: Result<(), &FacetError>) {
+        if !access.mutable || access.is::<AuditFacet>() {
+            return;
+        }
+        let facet = access.type_name.rsplit("::").next().unwrap_or(access.type_name);
+        let entry = match outcome {
+            Ok(()) => AuditEntry::new("facet_mut", &format!("{} modified", facet)),
+            Err(e) => AuditEntry::new("facet_mut", &format!("{} not modified: {}", facet, e)).failed(e),
+        };
+        let entry = entry.attribute("facet", facet).attribute("instance", access.instance);
+        let _ = object.with_facet_as_mut::<dyn Auditable, ()>(|audit| audit.record(entry));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::pipeline::{Audit, Pipeline};
+    use crate::{AccountFacet, Money};
+
+    #[test]
+    fn test_query_and_count() {
+        let clock = Arc::new(ManualClock::new(Timestamp::UNIX_EPOCH));
+        let mut audit = AuditFacet::with_clock(clock.clone());
+        for (operation, details) in [("deposit", "100.00 USD"), ("withdraw", "40.00 USD"), ("deposit", "5.00 EUR"), ("deposit", "1.00 USD")] {
+            audit.log_operation(operation, details);
+            clock.advance(std::time::Duration::from_secs(1));
+        }
+
+        let deposits = AuditQuery::new().operation("deposit");
+        assert_eq!(audit.count(&deposits), 3);
+        let page: Vec<&str> = audit.query(&deposits.clone().limit(2).newest_first()).iter().map(|entry| entry.details()).collect();
+        assert_eq!(page, ["1.00 USD", "5.00 EUR"]);
+        let older: Vec<&str> = audit.query(&deposits.offset(2).limit(2)).iter().map(|entry| entry.details()).collect();
+        assert_eq!(older, ["100.00 USD"]);
+
+        let usd = AuditQuery::new().search("usd").since(audit.get_audit_trail()[1].timestamp());
+        assert_eq!(audit.count(&usd), 2);
+        assert_eq!(audit.query(&usd.until(audit.get_audit_trail()[3].timestamp()))[0].operation(), "withdraw");
+    }
+
+    #[test]
+    fn test_structured_entries_share_correlation_id() {
+        let object = FacetedObject::new(());
+        object.attach_facet(AuditFacet::new()).unwrap();
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        object.add_interceptor(AuditInterceptor).unwrap();
+
+        let withdraw = Pipeline::new("withdraw").stage(Audit::new());
+        let result = withdraw.run(&object, |object| object.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(5)))?);
+        assert!(result.is_err());
+        object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))).unwrap().unwrap();
+
+        let trail = object.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().to_vec()).unwrap();
+        assert_eq!(trail.len(), 3);
+        assert_eq!(trail[0].attributes().get("facet").map(String::as_str), Some("AccountFacet"));
+        assert!(!trail[1].is_success() && trail[1].get_severity() == Severity::Warning);
+        assert!(trail[0].get_correlation_id().is_some());
+        assert_eq!(trail[0].get_correlation_id(), trail[1].get_correlation_id());
+        assert_ne!(trail[1].get_correlation_id(), trail[2].get_correlation_id());
+
+        let entry = AuditEntry::new("withdraw", "50.00 USD").actor("alice").failed("Insufficient funds").attribute("teller", 7).correlation_id("c-1");
+        let entry = AuditEntry { timestamp: Timestamp::from_millis(0), ..entry };
+        assert_eq!(entry.to_string(), "1970-01-01T00:00:00.000Z WARNING withdraw failed (Insufficient funds): 50.00 USD [actor=alice correlation=c-1 teller=7]");
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(serde_json::from_str::<AuditEntry>(&json).unwrap(), entry);
+        let plain: AuditEntry = serde_json::from_str(r#"{"timestamp":{"secs":0,"nanos":0},"operation":"deposit","details":"5"}"#).unwrap();
+        assert!(plain.is_success() && plain.get_actor().is_none());
+    }
+
+    #[test]
+    fn test_events_are_sequenced() {
+        let clock = Arc::new(ManualClock::new(Timestamp::UNIX_EPOCH));
+        let mut audit = AuditFacet::with_clock(clock.clone());
+        assert!(audit.events_since(0).is_empty());
+        audit.append_events(["opened", "deposited"].map(|kind| DomainEvent::new(kind, serde_json::Value::Null)));
+        clock.advance(std::time::Duration::from_secs(1));
+        audit.append_events([DomainEvent::new("withdrawn", serde_json::json!({ "minor": 500 }))]);
+
+        assert_eq!(audit.last_sequence(), 3);
+        let since = audit.events_since(1);
+        assert_eq!(since.iter().map(|event| (event.sequence, event.kind.as_str())).collect::<Vec<_>>(), [(2, "deposited"), (3, "withdrawn")]);
+        assert_eq!(since[1].timestamp, Timestamp::from_millis(1_000));
+        assert!(audit.events_since(3).is_empty());
+
+        let restored: AuditFacet = serde_json::from_str(&serde_json::to_string(&audit).unwrap()).unwrap();
+        assert_eq!(restored.events(), audit.events());
+        let plain: AuditFacet = serde_json::from_str(r#"{"entries":[]}"#).unwrap();
+        assert!(plain.events().is_empty());
+    }
+}
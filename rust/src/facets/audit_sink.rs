@@ -0,0 +1,485 @@
+use std::fmt;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use parking_lot::Mutex;
+
+use crate::clock::Timestamp;
+use crate::error::FacetError;
+use crate::facets::audit::AuditEntry;
+#[cfg(feature = "audit-sqlite")]
+use crate::facets::audit::{Outcome, Severity};
+
+// Order of the entries a query returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuditOrder {
+    #[default]
+    OldestFirst,
+    NewestFirst,
+}
+
+// Which entries AuditSink::query and AuditFacet::query return. Pages are
+// counted back from the most recent match: `offset` skips that many of the
+// newest matches and `limit` keeps at most that many of the rest, so
+// `.limit(20)`, `.offset(20).limit(20)`, ... walk back through the trail.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditQuery {
+    pub operation: Option<String>,
+    pub since: Option<Timestamp>,
+    // Exclusive
+    pub until: Option<Timestamp>,
+    // Text the details must contain, ignoring ASCII case
+    pub search: Option<String>,
+    pub correlation_id: Option<String>,
+    pub offset: usize,
+    // Keep only the most recent entries
+    pub limit: Option<usize>,
+    pub order: AuditOrder,
+}
+
+impl AuditQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn operation(mut self, operation: &str) -> Self {
+        self.operation = Some(operation.to_string());
+        self
+    }
+
+    pub fn since(mut self, since: Timestamp) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn until(mut self, until: Timestamp) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn search(mut self, text: &str) -> Self {
+        self.search = Some(text.to_string());
+        self
+    }
+
+    // Entries of one correlated operation
+    pub fn correlation_id(mut self, id: &str) -> Self {
+        self.correlation_id = Some(id.to_string());
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn newest_first(mut self) -> Self {
+        self.order = AuditOrder::NewestFirst;
+        self
+    }
+
+    // Whether `entry` passes the filters; offset and limit are not applied
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.operation.as_ref().is_none_or(|operation| *operation == entry.operation)
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+            && self.search.as_ref().is_none_or(|text| entry.details.to_ascii_lowercase().contains(&text.to_ascii_lowercase()))
+            && self.correlation_id.as_ref().is_none_or(|id| entry.correlation_id.as_ref() == Some(id))
+    }
+
+    // The page of matching entries of `entries` (given oldest first)
+    pub fn select<'a>(&self, entries: impl IntoIterator<Item = &'a AuditEntry>) -> Vec<AuditEntry> {
+        self.select_refs(entries).into_iter().cloned().collect()
+    }
+
+    pub(crate) fn select_refs<'a>(&self, entries: impl IntoIterator<Item = &'a AuditEntry>) -> Vec<&'a AuditEntry> {
+        let mut selected: Vec<&AuditEntry> = entries.into_iter().filter(|entry| self.matches(entry)).collect();
+        selected.truncate(selected.len().saturating_sub(self.offset));
+        if let Some(limit) = self.limit {
+            selected.drain(..selected.len().saturating_sub(limit));
+        }
+        if self.order == AuditOrder::NewestFirst {
+            selected.reverse();
+        }
+        selected
+    }
+}
+
+// Durable home for an audit trail. AuditFacet writes every entry through
+// to its sink, so the trail survives restarts.
+pub trait AuditSink: Send + Sync {
+    fn append(&self, entry: &AuditEntry) -> Result<(), FacetError>;
+
+    // Matching entries, oldest first
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, FacetError>;
+
+    // Wait until every appended entry is stored
+    fn flush(&self) -> Result<(), FacetError> {
+        Ok(())
+    }
+}
+
+fn storage_error(error: impl fmt::Display) -> FacetError {
+    FacetError::Storage(error.to_string())
+}
+
+// Keeps entries in memory, e.g. for tests or short-lived processes
+#[derive(Default)]
+pub struct MemorySink {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditSink for MemorySink {
+    fn append(&self, entry: &AuditEntry) -> Result<(), FacetError> {
+        self.entries.lock().push(entry.clone());
+        Ok(())
+    }
+
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, FacetError> {
+        Ok(query.select(self.entries.lock().iter()))
+    }
+}
+
+// Appends one JSON object per line to a file
+#[cfg(feature = "audit-jsonl")]
+pub struct JsonLinesSink {
+    path: std::path::PathBuf,
+    file: Mutex<std::fs::File>,
+}
+
+#[cfg(feature = "audit-jsonl")]
+impl JsonLinesSink {
+    // Open `path` for appending, creating it if needed
+    pub fn open(path: impl Into<std::path::PathBuf>) -> Result<Self, FacetError> {
+        let path = path.into();
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&path).map_err(storage_error)?;
+        Ok(Self { path, file: Mutex::new(file) })
+    }
+}
+
+#[cfg(feature = "audit-jsonl")]
+impl AuditSink for JsonLinesSink {
+    fn append(&self, entry: &AuditEntry) -> Result<(), FacetError> {
+        use std::io::Write;
+
+        let mut line = serde_json::to_string(entry).map_err(storage_error)?;
+        line.push('\n');
+        self.file.lock().write_all(line.as_bytes()).map_err(storage_error)
+    }
+
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, FacetError> {
+        let contents = std::fs::read_to_string(&self.path).map_err(storage_error)?;
+        let entries = contents.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(storage_error))
+            .collect::<Result<Vec<AuditEntry>, _>>()?;
+        Ok(query.select(&entries))
+    }
+
+    fn flush(&self) -> Result<(), FacetError> {
+        self.file.lock().sync_data().map_err(storage_error)
+    }
+}
+
+// Stores entries in an `audit_entries` table of a SQLite database
+#[cfg(feature = "audit-sqlite")]
+pub struct SqliteSink {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "audit-sqlite")]
+impl SqliteSink {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, FacetError> {
+        Self::with_connection(rusqlite::Connection::open(path).map_err(storage_error)?)
+    }
+
+    pub fn in_memory() -> Result<Self, FacetError> {
+        Self::with_connection(rusqlite::Connection::open_in_memory().map_err(storage_error)?)
+    }
+
+    fn with_connection(connection: rusqlite::Connection) -> Result<Self, FacetError> {
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS audit_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp_nanos INTEGER NOT NULL,
+                operation TEXT NOT NULL,
+                details TEXT NOT NULL
+            )",
+            (),
+        ).map_err(storage_error)?;
+
+        // Columns added since the first release; older databases get them
+        // with defaults matching plain entries
+        let existing = connection.prepare("SELECT name FROM pragma_table_info('audit_entries')")
+            .and_then(|mut statement| statement.query_map((), |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>())
+            .map_err(storage_error)?;
+        for (column, definition) in STRUCTURED_COLUMNS {
+            if !existing.iter().any(|name| name == column) {
+                connection.execute(&format!("ALTER TABLE audit_entries ADD COLUMN {} {}", column, definition), ()).map_err(storage_error)?;
+            }
+        }
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+}
+
+#[cfg(feature = "audit-sqlite")]
+const STRUCTURED_COLUMNS: [(&str, &str); 5] = [
+    ("actor", "TEXT"),
+    ("severity", "TEXT NOT NULL DEFAULT 'info'"),
+    // NULL for successful operations
+    ("failure", "TEXT"),
+    // JSON object
+    ("attributes", "TEXT NOT NULL DEFAULT '{}'"),
+    ("correlation_id", "TEXT"),
+];
+
+#[cfg(feature = "audit-sqlite")]
+fn nanos(timestamp: Timestamp) -> i64 {
+    i64::try_from(timestamp.duration_since_epoch().as_nanos()).unwrap_or(i64::MAX)
+}
+
+#[cfg(feature = "audit-sqlite")]
+impl AuditSink for SqliteSink {
+    fn append(&self, entry: &AuditEntry) -> Result<(), FacetError> {
+        let failure = match &entry.outcome {
+            Outcome::Success => None,
+            Outcome::Failure(reason) => Some(reason),
+        };
+        let attributes = serde_json::to_string(&entry.attributes).map_err(storage_error)?;
+        self.connection.lock().execute(
+            "INSERT INTO audit_entries (timestamp_nanos, operation, details, actor, severity, failure, attributes, correlation_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            (
+                nanos(entry.timestamp),
+                &entry.operation,
+                &entry.details,
+                &entry.actor,
+                entry.severity.as_str(),
+                failure,
+                attributes,
+                &entry.correlation_id,
+            ),
+        ).map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, FacetError> {
+        let connection = self.connection.lock();
+        let mut statement = connection.prepare(
+            "SELECT timestamp_nanos, operation, details, actor, severity, failure, attributes, correlation_id FROM audit_entries
+             WHERE (?1 IS NULL OR operation = ?1) AND (?2 IS NULL OR timestamp_nanos >= ?2)
+               AND (?3 IS NULL OR timestamp_nanos < ?3) AND (?4 IS NULL OR instr(lower(details), lower(?4)) > 0)
+               AND (?5 IS NULL OR correlation_id = ?5)
+             ORDER BY id DESC LIMIT ?6 OFFSET ?7",
+        ).map_err(storage_error)?;
+
+        let limit = query.limit.map_or(-1, |limit| i64::try_from(limit).unwrap_or(i64::MAX));
+        let offset = i64::try_from(query.offset).unwrap_or(i64::MAX);
+        let parameters = (
+            &query.operation,
+            query.since.map(nanos),
+            query.until.map(nanos),
+            &query.search,
+            &query.correlation_id,
+            limit,
+            offset,
+        );
+        let rows = statement.query_map(parameters, |row| {
+            let nanos: i64 = row.get(0)?;
+            let severity: String = row.get(4)?;
+            let failure: Option<String> = row.get(5)?;
+            let attributes: String = row.get(6)?;
+            Ok(AuditEntry {
+                timestamp: Timestamp::from_duration_since_epoch(std::time::Duration::from_nanos(nanos.max(0) as u64)),
+                operation: row.get(1)?,
+                details: row.get(2)?,
+                actor: row.get(3)?,
+                severity: Severity::parse(&severity).unwrap_or_default(),
+                outcome: failure.map_or(Outcome::Success, Outcome::Failure),
+                attributes: serde_json::from_str(&attributes).unwrap_or_default(),
+                correlation_id: row.get(7)?,
+            })
+        }).map_err(storage_error)?;
+
+        let mut entries = rows.collect::<Result<Vec<_>, _>>().map_err(storage_error)?;
+        if query.order == AuditOrder::OldestFirst {
+            entries.reverse();
+        }
+        Ok(entries)
+    }
+}
+
+enum Command {
+    Append(AuditEntry),
+    Flush(Sender<Result<(), FacetError>>),
+}
+
+// Hands entries to a background thread that writes them to `inner`, so
+// logging never waits on the file or database. Write errors are reported by
+// the next flush; query flushes first so it sees every appended entry.
+pub struct AsyncSink {
+    inner: Arc<dyn AuditSink>,
+    commands: Option<Sender<Command>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl AsyncSink {
+    pub fn new(inner: Arc<dyn AuditSink>) -> Self {
+        let (commands, received) = mpsc::channel();
+        let sink = Arc::clone(&inner);
+        let writer = std::thread::spawn(move || {
+            let mut failed = None;
+            for command in received {
+                match command {
+                    Command::Append(entry) => {
+                        if let Err(e) = sink.append(&entry) {
+                            failed.get_or_insert(e);
+                        }
+                    }
+                    Command::Flush(reply) => {
+                        let result = match failed.take() {
+                            Some(e) => Err(e),
+                            None => sink.flush(),
+                        };
+                        let _ = reply.send(result);
+                    }
+                }
+            }
+        });
+        Self { inner, commands: Some(commands), writer: Some(writer) }
+    }
+
+    fn send(&self, command: Command) -> Result<(), FacetError> {
+        self.commands.as_ref()
+            .and_then(|commands| commands.send(command).ok())
+            .ok_or_else(|| storage_error("audit writer stopped"))
+    }
+}
+
+impl AuditSink for AsyncSink {
+    fn append(&self, entry: &AuditEntry) -> Result<(), FacetError> {
+        self.send(Command::Append(entry.clone()))
+    }
+
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, FacetError> {
+        self.flush()?;
+        self.inner.query(query)
+    }
+
+    fn flush(&self) -> Result<(), FacetError> {
+        let (reply, result) = mpsc::channel();
+        self.send(Command::Flush(reply))?;
+        result.recv().map_err(|_| storage_error("audit writer stopped"))?
+    }
+}
+
+// Pending entries are written before the sink goes away
+impl Drop for AsyncSink {
+    fn drop(&mut self) {
+        self.commands.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(millis: u64, operation: &str) -> AuditEntry {
+        let entry = AuditEntry::new(operation, &format!("{} at {}", operation, millis)).attribute("seq", millis);
+        let entry = if operation == "withdraw" { entry.actor("teller").failed("Insufficient funds") } else { entry };
+        AuditEntry { timestamp: Timestamp::from_millis(millis), ..entry }
+    }
+
+    // Shared checks every backend must pass
+    fn check_sink(sink: &dyn AuditSink) {
+        for (millis, operation) in [(1, "deposit"), (2, "withdraw"), (3, "deposit"), (4, "deposit")] {
+            sink.append(&entry(millis, operation)).unwrap();
+        }
+        let transfer = entry(5, "transfer").correlation_id("transfer-1");
+        sink.append(&transfer).unwrap();
+        sink.flush().unwrap();
+
+        let deposits = sink.query(&AuditQuery::new().operation("deposit").limit(2)).unwrap();
+        assert_eq!(deposits, [entry(3, "deposit"), entry(4, "deposit")]);
+        let recent = sink.query(&AuditQuery::new().since(Timestamp::from_millis(2))).unwrap();
+        assert_eq!(recent.len(), 4);
+        assert_eq!(recent[0], entry(2, "withdraw"));
+
+        let page = AuditQuery::new().until(Timestamp::from_millis(4)).search("DEPOSIT").offset(1).limit(1).newest_first();
+        assert_eq!(sink.query(&page).unwrap(), [entry(1, "deposit")]);
+        let newest = sink.query(&AuditQuery::new().limit(2).newest_first()).unwrap();
+        assert_eq!(newest, [transfer.clone(), entry(4, "deposit")]);
+        assert_eq!(sink.query(&AuditQuery::new().correlation_id("transfer-1")).unwrap(), [transfer]);
+    }
+
+    #[test]
+    fn test_sinks_append_and_query() {
+        check_sink(&MemorySink::new());
+        check_sink(&AsyncSink::new(Arc::new(MemorySink::new())));
+
+        #[cfg(feature = "audit-sqlite")]
+        check_sink(&SqliteSink::in_memory().unwrap());
+
+        #[cfg(feature = "audit-jsonl")]
+        {
+            let path = std::env::temp_dir().join(format!("audit-{}.jsonl", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            check_sink(&JsonLinesSink::open(&path).unwrap());
+            // Reopening keeps what was written before
+            assert_eq!(JsonLinesSink::open(&path).unwrap().query(&AuditQuery::new()).unwrap().len(), 5);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    // Fails every append, to check error reporting through AsyncSink
+    struct Unavailable;
+
+    impl AuditSink for Unavailable {
+        fn append(&self, _entry: &AuditEntry) -> Result<(), FacetError> {
+            Err(storage_error("disk full"))
+        }
+
+        fn query(&self, _query: &AuditQuery) -> Result<Vec<AuditEntry>, FacetError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_async_sink_reports_write_errors_on_flush() {
+        let sink = AsyncSink::new(Arc::new(Unavailable));
+        sink.append(&entry(1, "deposit")).unwrap();
+        assert_eq!(sink.flush(), Err(FacetError::Storage("disk full".to_string())));
+        assert_eq!(sink.flush(), Ok(()));
+    }
+
+    #[test]
+    fn test_audit_trail_survives_restart() {
+        use crate::facets::AuditFacet;
+
+        let sink: Arc<dyn AuditSink> = Arc::new(MemorySink::new());
+        let mut audit = AuditFacet::new().sink(Arc::new(AsyncSink::new(Arc::clone(&sink))));
+        audit.log_operation("deposit", "100");
+        audit.log_operation("withdraw", "40");
+        audit.flush().unwrap();
+        drop(audit);
+
+        let restarted = AuditFacet::from_sink(sink).unwrap();
+        assert_eq!(restarted.get_audit_trail().len(), 2);
+        assert_eq!(restarted.get_audit_trail()[1].operation, "withdraw");
+    }
+}
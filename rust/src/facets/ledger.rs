@@ -0,0 +1,158 @@
+use std::fmt::Write as _;
+use std::ops::{Bound, RangeBounds};
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Timestamp;
+use crate::error::FacetError;
+use crate::money::{Currency, Money};
+
+// One deposit or withdrawal booked by AccountFacet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LedgerEntry {
+    // Sequential per account, starting at 1
+    pub id: u64,
+    pub timestamp: Timestamp,
+    // Positive for deposits, negative for withdrawals
+    pub amount: Money,
+    // Balance of the amount's currency after the entry
+    pub balance: Money,
+    pub memo: String,
+}
+
+// Ledger entries of one currency over a period, with the balances either
+// side of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub account_number: String,
+    pub currency: Currency,
+    pub opening_balance: Money,
+    pub closing_balance: Money,
+    pub total_deposits: Money,
+    // Sum of withdrawals as a positive amount
+    pub total_withdrawals: Money,
+    pub entries: Vec<LedgerEntry>,
+}
+
+impl Statement {
+    // `entries` is the whole ledger in booking order
+    pub(crate) fn build(
+        account_number: &str,
+        currency: Currency,
+        entries: &[LedgerEntry],
+        period: impl RangeBounds<Timestamp>,
+    ) -> Result<Self, FacetError> {
+        let entries = entries.iter().filter(|entry| entry.amount.currency() == currency);
+        // Replayed entries may sit out of time order, so look past them
+        let opening_balance = entries.clone()
+            .filter(|entry| before(&period, entry.timestamp))
+            .last()
+            .map_or(Money::zero(currency), |entry| entry.balance);
+
+        let mut statement = Statement {
+            account_number: account_number.to_string(),
+            currency,
+            opening_balance,
+            closing_balance: opening_balance,
+            total_deposits: Money::zero(currency),
+            total_withdrawals: Money::zero(currency),
+            entries: entries.filter(|entry| period.contains(&entry.timestamp)).cloned().collect(),
+        };
+        for entry in &statement.entries {
+            if entry.amount.is_positive() {
+                statement.total_deposits = statement.total_deposits.checked_add(entry.amount)?;
+            } else {
+                statement.total_withdrawals = statement.total_withdrawals.checked_sub(entry.amount)?;
+            }
+            statement.closing_balance = entry.balance;
+        }
+        Ok(statement)
+    }
+
+    // One row per entry: id,timestamp,currency,amount,balance,memo
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("id,timestamp,currency,amount,balance,memo\n");
+        for entry in &self.entries {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                entry.id,
+                entry.timestamp,
+                entry.amount.currency(),
+                entry.amount.decimal(),
+                entry.balance.decimal(),
+                csv_field(&entry.memo),
+            );
+        }
+        csv
+    }
+}
+
+// Whether `timestamp` falls before the start of `period`
+fn before(period: &impl RangeBounds<Timestamp>, timestamp: Timestamp) -> bool {
+    match period.start_bound() {
+        Bound::Included(start) => timestamp < *start,
+        Bound::Excluded(start) => timestamp <= *start,
+        Bound::Unbounded => false,
+    }
+}
+
+// Quote fields containing separators, quotes or line breaks (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::clock::ManualClock;
+    use crate::{AccountFacet, Currency, Money};
+
+    use super::*;
+
+    #[test]
+    fn test_statement_for_period() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let mut account = AccountFacet::new("ACC001").clock(clock.clone());
+
+        account.deposit_with_memo(Money::usd(100), "opening").unwrap();
+        clock.advance(Duration::from_secs(10));
+        account.deposit(Money::usd(50)).unwrap();
+        account.withdraw_with_memo(Money::usd(30), "rent").unwrap();
+        clock.advance(Duration::from_secs(10));
+        account.deposit(Money::usd(5)).unwrap();
+
+        let statement = account.statement(Timestamp::from_millis(10_000)..Timestamp::from_millis(20_000)).unwrap();
+        assert_eq!(statement.opening_balance, Money::usd(100));
+        assert_eq!(statement.closing_balance, Money::usd(120));
+        assert_eq!(statement.total_deposits, Money::usd(50));
+        assert_eq!(statement.total_withdrawals, Money::usd(30));
+        assert_eq!(statement.entries.iter().map(|entry| entry.id).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(statement.entries[1].amount, Money::usd(-30));
+
+        let everything = account.statement(..).unwrap();
+        assert_eq!(everything.opening_balance, Money::zero(Currency::USD));
+        assert_eq!(everything.closing_balance, account.get_balance());
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let mut account = AccountFacet::new("ACC001").clock(clock);
+        account.deposit_with_memo(Money::from_minor(1_050, Currency::USD), "salary, March").unwrap();
+        account.withdraw_with_memo(Money::usd(2), "say \"hi\"").unwrap();
+
+        assert_eq!(account.statement(..).unwrap().to_csv(), concat!(
+            "id,timestamp,currency,amount,balance,memo\n",
+            "1,1970-01-01T00:00:00.000Z,USD,10.50,10.50,\"salary, March\"\n",
+            "2,1970-01-01T00:00:00.000Z,USD,-2.00,8.50,\"say \"\"hi\"\"\"\n",
+        ));
+    }
+}
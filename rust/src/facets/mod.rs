@@ -0,0 +1,43 @@
+// Built-in example facets
+pub mod account;
+pub mod account_policy;
+pub mod audit;
+pub mod audit_sink;
+pub mod ledger;
+pub mod notification;
+pub mod permission;
+pub mod policy;
+#[cfg(any(feature = "policy-toml", feature = "policy-yaml"))]
+pub mod policy_config;
+pub mod rate_limiter;
+pub mod state_machine;
+
+pub use self::account::{AccountFacet, BalanceChanged, ForeignCurrency};
+pub use self::account_policy::{AccountPolicy, StandardPolicy};
+pub use self::audit::{AuditEntry, AuditFacet, AuditInterceptor, Auditable, Outcome, Severity};
+#[cfg(feature = "audit-jsonl")]
+pub use self::audit_sink::JsonLinesSink;
+#[cfg(feature = "audit-sqlite")]
+pub use self::audit_sink::SqliteSink;
+pub use self::audit_sink::{AsyncSink, AuditOrder, AuditQuery, AuditSink, MemorySink};
+pub use self::ledger::{LedgerEntry, Statement};
+#[cfg(feature = "notify-stdout")]
+pub use self::notification::StdoutChannel;
+#[cfg(feature = "notify-webhook")]
+pub use self::notification::WebhookChannel;
+pub use self::notification::{DeliveryFailure, MemoryChannel, Notification, NotificationChannel, NotificationFacet, RetryPolicy};
+pub use self::permission::{Authorizer, PermissionFacet, PermissionGranted};
+pub use self::policy::{Decision, Effect, Policy, Role, Rule, RuleSource};
+#[cfg(any(feature = "policy-toml", feature = "policy-yaml"))]
+pub use self::policy_config::ConfigFormat;
+pub use self::rate_limiter::{RateLimitInterceptor, RateLimiterFacet};
+pub use self::state_machine::{StateChange, StateMachineFacet};
+
+facet_accessors! {
+    // Named accessors for the built-in facets, e.g. `employee.account()?`
+    pub trait BuiltinFacetAccess {
+        account, account_ref => AccountFacet;
+        permissions, permissions_ref => PermissionFacet;
+        audit, audit_ref => AuditFacet;
+    }
+}
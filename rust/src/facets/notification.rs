@@ -0,0 +1,373 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::error::FacetError;
+use crate::event::FacetEvent;
+use crate::facets::{BalanceChanged, PermissionGranted};
+use crate::money::Money;
+use crate::Facet;
+
+// Rendered notification handed to the channels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Notification {
+    // Rule that produced it, e.g. "low_balance"
+    pub kind: String,
+    pub message: String,
+    pub timestamp: Timestamp,
+}
+
+// Where notifications are delivered. A failed delivery is retried per the
+// facet's RetryPolicy.
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn deliver(&self, notification: &Notification) -> Result<(), FacetError>;
+}
+
+// Keeps delivered notifications in memory, e.g. for tests; clones share
+// the same list
+#[derive(Debug, Clone, Default)]
+pub struct MemoryChannel {
+    delivered: Arc<Mutex<Vec<Notification>>>,
+}
+
+impl MemoryChannel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.delivered.lock().clone()
+    }
+}
+
+impl NotificationChannel for MemoryChannel {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn deliver(&self, notification: &Notification) -> Result<(), FacetError> {
+        self.delivered.lock().push(notification.clone());
+        Ok(())
+    }
+}
+
+// Prints each notification as one line on standard output
+#[cfg(feature = "notify-stdout")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutChannel;
+
+#[cfg(feature = "notify-stdout")]
+impl NotificationChannel for StdoutChannel {
+    fn name(&self) -> &str {
+        "stdout"
+    }
+
+    fn deliver(&self, notification: &Notification) -> Result<(), FacetError> {
+        println!("[{}] {}: {}", notification.timestamp, notification.kind, notification.message);
+        Ok(())
+    }
+}
+
+// POSTs each notification as JSON to a URL; non-2xx responses and
+// transport errors fail the delivery
+#[cfg(feature = "notify-webhook")]
+pub struct WebhookChannel {
+    url: String,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "notify-webhook")]
+impl WebhookChannel {
+    pub fn new(url: &str) -> Self {
+        Self::with_timeout(url, Duration::from_secs(10))
+    }
+
+    pub fn with_timeout(url: &str, timeout: Duration) -> Self {
+        Self { url: url.to_string(), agent: ureq::AgentBuilder::new().timeout(timeout).build() }
+    }
+}
+
+#[cfg(feature = "notify-webhook")]
+impl NotificationChannel for WebhookChannel {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn deliver(&self, notification: &Notification) -> Result<(), FacetError> {
+        self.agent.post(&self.url)
+            .send_json(notification)
+            .map(|_| ())
+            .map_err(|e| FacetError::Other(format!("Webhook {} failed: {}", self.url, e)))
+    }
+}
+
+// How often a failed delivery is attempted again, waiting `backoff` after
+// the first failure and `multiplier` times longer after each further one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Duration,
+    pub multiplier: u32,
+}
+
+impl RetryPolicy {
+    // Deliver once, without retrying
+    pub fn none() -> Self {
+        Self { max_attempts: 1, backoff: Duration::ZERO, multiplier: 1 }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, backoff: Duration::from_millis(100), multiplier: 2 }
+    }
+}
+
+// Notification that could not be delivered after every attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryFailure {
+    pub channel: String,
+    pub notification: Notification,
+    pub attempts: u32,
+    pub error: FacetError,
+}
+
+type Trigger = Arc<dyn Fn(&dyn FacetEvent) -> Option<Vec<(&'static str, String)>> + Send + Sync>;
+
+struct NotificationRule {
+    kind: String,
+    template: String,
+    trigger: Trigger,
+}
+
+// Turns facet events emitted on the object into notifications: each rule
+// picks events of one type, returns the template variables for those it
+// fires on, and the rendered message goes to every channel. Delivery runs
+// while the object emits the event, so slow channels slow down emit.
+#[derive(Facet)]
+#[facet(name = "notifications", description = "Notifications rendered from facet events and sent to delivery channels", tags("notification"), on_event = "Self::dispatch_event")]
+pub struct NotificationFacet {
+    rules: Vec<NotificationRule>,
+    channels: Vec<Arc<dyn NotificationChannel>>,
+    retry: RetryPolicy,
+    clock: Arc<dyn Clock>,
+    delivered: u64,
+    failures: Vec<DeliveryFailure>,
+}
+
+impl Default for NotificationFacet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NotificationFacet {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { rules: Vec::new(), channels: Vec::new(), retry: RetryPolicy::default(), clock, delivered: 0, failures: Vec::new() }
+    }
+
+    pub fn channel(mut self, channel: impl NotificationChannel + 'static) -> Self {
+        self.channels.push(Arc::new(channel));
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    // Notify with `template` whenever `trigger` returns variables for an
+    // event of type E. `{name}` in the template is replaced by the
+    // variable `name`.
+    pub fn on<E: FacetEvent>(
+        mut self,
+        kind: &str,
+        template: &str,
+        trigger: impl Fn(&E) -> Option<Vec<(&'static str, String)>> + Send + Sync + 'static,
+    ) -> Self {
+        let trigger: Trigger = Arc::new(move |event: &dyn FacetEvent| event.downcast_ref::<E>().and_then(&trigger));
+        self.rules.push(NotificationRule { kind: kind.to_string(), template: template.to_string(), trigger });
+        self
+    }
+
+    // "low_balance" when a balance change leaves less than `threshold`
+    pub fn low_balance(self, threshold: Money) -> Self {
+        self.on::<BalanceChanged>("low_balance", "Balance of {account} is {balance}, below {threshold}", move |event| {
+            let below = event.balance.currency() == threshold.currency() && event.balance < threshold;
+            below.then(|| vec![
+                ("account", event.account_number.clone()),
+                ("balance", event.balance.to_string()),
+                ("threshold", threshold.to_string()),
+            ])
+        })
+    }
+
+    // "permission_escalation" whenever a permission is granted
+    pub fn permission_escalation(self) -> Self {
+        self.on::<PermissionGranted>("permission_escalation", "Role {role} was granted '{permission}'", |event| {
+            Some(vec![("role", event.role.clone()), ("permission", event.permission.clone())])
+        })
+    }
+
+    // Render and deliver a notification outside of any rule
+    pub fn notify(&mut self, kind: &str, message: &str) {
+        let notification = Notification { kind: kind.to_string(), message: message.to_string(), timestamp: self.clock.now() };
+        self.dispatch(&notification);
+    }
+
+    // Deliveries that succeeded, counting each channel separately
+    pub fn delivered_count(&self) -> u64 {
+        self.delivered
+    }
+
+    pub fn failures(&self) -> &[DeliveryFailure] {
+        &self.failures
+    }
+
+    fn dispatch_event(&mut self, event: &dyn FacetEvent) {
+        let fired: Vec<Notification> = self.rules.iter()
+            .filter_map(|rule| {
+                let variables = (rule.trigger)(event)?;
+                Some(Notification { kind: rule.kind.clone(), message: render(&rule.template, &variables), timestamp: self.clock.now() })
+            })
+            .collect();
+        for notification in &fired {
+            self.dispatch(notification);
+        }
+    }
+
+    fn dispatch(&mut self, notification: &Notification) {
+        for channel in &self.channels {
+            match deliver_with_retry(channel.as_ref(), notification, &self.retry) {
+                Ok(()) => self.delivered += 1,
+                Err((attempts, error)) => self.failures.push(DeliveryFailure {
+                    channel: channel.name().to_string(),
+                    notification: notification.clone(),
+                    attempts,
+                    error,
+                }),
+            }
+        }
+    }
+}
+
+fn deliver_with_retry(
+    channel: &dyn NotificationChannel,
+    notification: &Notification,
+    retry: &RetryPolicy,
+) -> Result<(), (u32, FacetError)> {
+    let mut backoff = retry.backoff;
+    let mut attempt = 1;
+    loop {
+        match channel.deliver(notification) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= retry.max_attempts => return Err((attempt, e)),
+            Err(_) => {
+                std::thread::sleep(backoff);
+                backoff = backoff.saturating_mul(retry.multiplier);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+// Replace each `{name}` with its variable; unknown names are left as is
+fn render(template: &str, variables: &[(&'static str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else { break };
+        rendered.push_str(&rest[..start]);
+        let name = &rest[start + 1..end];
+        match variables.iter().find(|(variable, _)| *variable == name) {
+            Some((_, value)) => rendered.push_str(value),
+            None => {
+                let _ = write!(rendered, "{{{}}}", name);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{CommandBus, FacetedObject, ObjectRegistry, PermissionFacet};
+
+    // Fails the first `failures` deliveries
+    struct Flaky {
+        failures: u32,
+        attempts: Arc<AtomicU32>,
+    }
+
+    impl NotificationChannel for Flaky {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn deliver(&self, _notification: &Notification) -> Result<(), FacetError> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(FacetError::Storage("unavailable".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_events_render_and_deliver() {
+        let channel = MemoryChannel::new();
+        let object = FacetedObject::new(());
+        object.attach_facet(PermissionFacet::new("employee")).unwrap();
+        object.attach_facet(NotificationFacet::new().channel(channel.clone()).low_balance(Money::usd(50)).permission_escalation()).unwrap();
+
+        object.emit(&BalanceChanged { account_number: "ACC001".to_string(), previous: Money::usd(100), balance: Money::usd(80) }).unwrap();
+        object.emit(&BalanceChanged { account_number: "ACC001".to_string(), previous: Money::usd(80), balance: Money::usd(20) }).unwrap();
+
+        let bus = CommandBus::new(Arc::new(ObjectRegistry::new()));
+        bus.register_builtin_commands().unwrap();
+        object.with_facet_mut::<PermissionFacet, _>(|permissions| permissions.grant_permission("write")).unwrap();
+        bus.execute(&object, "grant", json!({ "permission": "delete" })).unwrap();
+
+        let messages: Vec<(String, String)> = channel.notifications().into_iter().map(|n| (n.kind, n.message)).collect();
+        assert_eq!(messages, [
+            ("low_balance".to_string(), "Balance of ACC001 is 20.00 USD, below 50.00 USD".to_string()),
+            ("permission_escalation".to_string(), "Role employee was granted 'delete'".to_string()),
+        ]);
+        assert_eq!(render("{a} and {b}", &[("a", "1".to_string())]), "1 and {b}");
+    }
+
+    #[test]
+    fn test_failed_delivery_is_retried() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let retry = RetryPolicy { max_attempts: 3, backoff: Duration::from_millis(1), multiplier: 2 };
+        let mut notifications = NotificationFacet::new()
+            .channel(Flaky { failures: 2, attempts: Arc::clone(&attempts) })
+            .with_retry(retry);
+
+        notifications.notify("test", "delivered on the third attempt");
+        assert_eq!((notifications.delivered_count(), attempts.load(Ordering::SeqCst)), (1, 3));
+
+        notifications.retry = RetryPolicy::none();
+        attempts.store(0, Ordering::SeqCst);
+        notifications.notify("test", "given up");
+        let failure = &notifications.failures()[0];
+        assert_eq!((failure.channel.as_str(), failure.attempts, failure.notification.message.as_str()), ("flaky", 1, "given up"));
+    }
+}
@@ -0,0 +1,304 @@
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Facet;
+use crate::checkpoint::{captured, SnapshotFacet};
+use crate::clone::CloneFacet;
+use crate::error::FacetError;
+use crate::event::FacetEvent;
+use crate::facets::policy::{Decision, Policy, Rule};
+use crate::reflect::{FieldInfo, FieldKind, FieldValue, ReflectFacet};
+use crate::snapshot::FacetMigration;
+use crate::summary::{FacetSummary, Summarizable};
+use crate::transaction::TransactionalFacet;
+
+// Permission facet for access control: a role in a policy's hierarchy plus
+// grants and denials made on this facet
+#[derive(Debug, Clone, Facet, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[facet(name = "permissions", description = "Role-based permissions with per-object overrides", tags("security"), summarize, reflect, serialize, version = 2, traits(Authorizer, CloneFacet, SnapshotFacet))]
+pub struct PermissionFacet {
+    role: String,
+    overrides: Vec<Rule>,
+    // Policies are configuration, so restored facets use the built-in one
+    #[serde(skip, default = "builtin_policy")]
+    policy: Arc<Policy>,
+}
+
+fn builtin_policy() -> Arc<Policy> {
+    Arc::new(Policy::builtin())
+}
+
+impl PermissionFacet {
+    // Role in the built-in policy: "admin", "manager" or "employee"
+    pub fn new(role: &str) -> Self {
+        Self::with_policy(role, builtin_policy())
+    }
+
+    pub fn with_policy(role: &str, policy: Arc<Policy>) -> Self {
+        Self {
+            role: role.to_string(),
+            overrides: Vec::new(),
+            policy,
+        }
+    }
+
+    // Whether `action` may be performed on `resource`, and which rule decided
+    pub fn check(&self, action: &str, resource: &str) -> Decision {
+        self.policy.check(&self.role, &self.overrides, action, resource)
+    }
+
+    // Whether `permission` is granted on any resource ("*")
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.check(permission, "*").is_allowed()
+    }
+
+    // Grant `permission` on every resource, replacing an earlier revoke
+    pub fn grant_permission(&mut self, permission: &str) {
+        self.set_override(Rule::allow(permission, "*"));
+    }
+
+    // Deny `permission` on every resource, whatever the role grants
+    pub fn revoke_permission(&mut self, permission: &str) {
+        self.set_override(Rule::deny(permission, "*"));
+    }
+
+    // Add an override, replacing any earlier one for the same action and
+    // resource
+    pub fn set_override(&mut self, rule: Rule) {
+        self.overrides.retain(|existing| existing.action != rule.action || existing.resource != rule.resource);
+        self.overrides.push(rule);
+    }
+
+    pub fn get_overrides(&self) -> &[Rule] {
+        &self.overrides
+    }
+
+    pub fn get_role(&self) -> &str {
+        &self.role
+    }
+
+    pub fn get_policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    // Non-wildcard actions named by the role's lineage or the overrides
+    fn named_actions(&self) -> BTreeSet<&str> {
+        self.policy.lineage(&self.role).into_iter()
+            .flat_map(|role| role.rules.iter())
+            .chain(&self.overrides)
+            .map(|rule| rule.action.as_str())
+            .filter(|action| !action.ends_with('*'))
+            .collect()
+    }
+}
+
+// Version 1 stored the role's permissions flattened into boolean flags;
+// each flag becomes a grant or denial override
+pub(crate) struct FlagsToOverrides;
+
+impl FacetMigration for FlagsToOverrides {
+    fn source_version(&self) -> u32 {
+        1
+    }
+
+    fn migrate(&self, state: Value) -> Result<Value, FacetError> {
+        let role = state["role"].as_str()
+            .ok_or_else(|| FacetError::Invalid("missing role".to_string()))?;
+        let flags = state["permissions"].as_object()
+            .ok_or_else(|| FacetError::Invalid("missing permissions".to_string()))?;
+
+        let mut overrides: Vec<Rule> = flags.iter()
+            .map(|(permission, granted)| match granted.as_bool() {
+                Some(true) => Rule::allow(permission, "*"),
+                _ => Rule::deny(permission, "*"),
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.action.cmp(&b.action));
+
+        Ok(serde_json::json!({ "role": role, "overrides": overrides }))
+    }
+}
+
+// Permission checks operations rely on, so another access-control facet can
+// stand in for PermissionFacet
+pub trait Authorizer {
+    fn has_permission(&self, permission: &str) -> bool;
+}
+
+// Published by the "grant" command once a permission was granted
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionGranted {
+    pub role: String,
+    pub permission: String,
+}
+
+impl FacetEvent for PermissionGranted {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Authorizer for PermissionFacet {
+    fn has_permission(&self, permission: &str) -> bool {
+        PermissionFacet::has_permission(self, permission)
+    }
+}
+
+impl TransactionalFacet for PermissionFacet {
+    type Savepoint = Vec<Rule>;
+
+    fn savepoint(&self) -> Self::Savepoint {
+        self.overrides.clone()
+    }
+
+    fn rollback(&mut self, overrides: Self::Savepoint) {
+        self.overrides = overrides;
+    }
+}
+
+impl SnapshotFacet for PermissionFacet {
+    fn capture(&self) -> Box<dyn Any + Send + Sync> {
+        Box::new(self.overrides.clone())
+    }
+
+    fn restore(&mut self, state: &(dyn Any + Send + Sync)) -> Result<(), FacetError> {
+        self.overrides = captured::<Vec<Rule>>(state)?.clone();
+        Ok(())
+    }
+}
+
+// The role is fixed; every action the role or the overrides name is a
+// boolean field, and setting one grants or revokes it
+impl ReflectFacet for PermissionFacet {
+    fn fields(&self) -> Vec<FieldInfo> {
+        let mut fields = vec![FieldInfo::read_only("role", FieldKind::Text)];
+        fields.extend(self.named_actions().into_iter().map(|name| FieldInfo::writable(name, FieldKind::Bool)));
+        fields
+    }
+
+    fn get_field(&self, name: &str) -> Option<FieldValue> {
+        match name {
+            "role" => Some(FieldValue::Text(self.role.clone())),
+            _ if self.named_actions().contains(name) => Some(FieldValue::Bool(self.has_permission(name))),
+            _ => None,
+        }
+    }
+
+    fn set_field(&mut self, name: &str, value: FieldValue) -> Result<(), FacetError> {
+        match (name, value) {
+            ("role", _) => Err(FacetError::ReadOnlyField { facet: "permissions".to_string(), field: "role".to_string() }),
+            (_, FieldValue::Bool(true)) => {
+                self.grant_permission(name);
+                Ok(())
+            }
+            (_, FieldValue::Bool(false)) => {
+                self.revoke_permission(name);
+                Ok(())
+            }
+            (_, value) => Err(FacetError::FieldTypeMismatch {
+                facet: "permissions".to_string(),
+                field: name.to_string(),
+                expected: FieldKind::Bool,
+                found: value.kind(),
+            }),
+        }
+    }
+}
+
+impl Summarizable for PermissionFacet {
+    fn summarize(&self) -> FacetSummary {
+        FacetSummary::new("Permissions")
+            .field("Role", &self.role)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{Employee, FacetedObject};
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn test_permission_checking() {
+        let employee = Employee::new("Test User", "TEST001", "Engineering");
+        let employee_obj = FacetedObject::new(employee);
+
+        employee_obj.attach_facet(PermissionFacet::new("employee")).unwrap();
+
+        let has_financial = employee_obj.with_facet::<PermissionFacet, bool>(|permissions| {
+            permissions.has_permission("financial_operations")
+        }).unwrap();
+
+        assert_eq!(has_financial, false);
+
+        let has_read = employee_obj.with_facet::<PermissionFacet, bool>(|permissions| {
+            permissions.has_permission("read")
+        }).unwrap();
+
+        assert_eq!(has_read, true);
+    }
+
+    #[test]
+    fn test_reflected_fields() {
+        let employee_obj = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee_obj.attach_facet(PermissionFacet::new("employee")).unwrap();
+        employee_obj.attach_facet(crate::AccountFacet::new("ACC001")).unwrap();
+
+        employee_obj.set_field("permissions", "write", FieldValue::Bool(true)).unwrap();
+        assert!(employee_obj.set_field("permissions", "role", FieldValue::Text("admin".to_string())).is_err());
+        assert!(matches!(
+            employee_obj.set_field("account", "balance", FieldValue::Number(1e6)),
+            Err(FacetError::ReadOnlyField { .. })
+        ));
+        assert_eq!(employee_obj.get_field("account", "account_number").unwrap().to_string(), "ACC001");
+
+        let reflected = employee_obj.reflect().unwrap();
+        assert_eq!(reflected.iter().map(|facet| facet.name).collect::<Vec<_>>(), ["permissions", "account"]);
+        assert_eq!(reflected[0].get("write"), Some(&FieldValue::Bool(true)));
+        assert_eq!(reflected[0].fields[0].0, FieldInfo::read_only("role", FieldKind::Text));
+    }
+
+    #[test]
+    fn test_policy_decisions() {
+        let mut permissions = PermissionFacet::new("admin");
+        assert_eq!(
+            permissions.check("read", "ACC001").explanation(),
+            "allowed by 'allow read on *' of role employee",
+        );
+
+        permissions.revoke_permission("financial_operations");
+        assert_eq!(
+            permissions.check("financial_operations", "*").explanation(),
+            "denied by override 'deny financial_operations on *'",
+        );
+        permissions.grant_permission("financial_operations");
+        assert!(permissions.has_permission("financial_operations"));
+        assert_eq!(permissions.get_overrides().len(), 1);
+    }
+
+    #[test]
+    fn test_version_one_snapshots_migrate() {
+        use crate::{FacetRegistry, FacetedSnapshot};
+
+        let snapshot = FacetedSnapshot::from_json(r#"{
+            "core": {"name": "Test User", "id": "TEST001", "department": "Engineering"},
+            "facets": [{"name": "permissions", "state": {
+                "role": "employee",
+                "permissions": {"read": true, "write": true, "delete": false}
+            }}]
+        }"#).unwrap();
+
+        let restored = FacetedObject::restore::<Employee>(&snapshot, &FacetRegistry::builtin()).unwrap();
+        restored.with_facet::<PermissionFacet, _>(|permissions| {
+            assert_eq!(permissions.get_role(), "employee");
+            assert!(permissions.has_permission("write"));
+            assert!(!permissions.has_permission("delete"));
+        }).unwrap();
+        assert_eq!(restored.snapshot::<Employee>().unwrap().facets[0].version, 2);
+    }
+}
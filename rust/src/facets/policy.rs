@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+// Allows or denies an action on a resource. Both patterns are either exact,
+// "*" for anything, or a prefix ending in '*' such as "account:*".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Rule {
+    pub effect: Effect,
+    pub action: String,
+    pub resource: String,
+}
+
+impl Rule {
+    pub fn allow(action: &str, resource: &str) -> Self {
+        Self { effect: Effect::Allow, action: action.to_string(), resource: resource.to_string() }
+    }
+
+    pub fn deny(action: &str, resource: &str) -> Self {
+        Self { effect: Effect::Deny, action: action.to_string(), resource: resource.to_string() }
+    }
+
+    pub fn matches(&self, action: &str, resource: &str) -> bool {
+        pattern_matches(&self.action, action) && pattern_matches(&self.resource, resource)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let effect = match self.effect {
+            Effect::Allow => "allow",
+            Effect::Deny => "deny",
+        };
+        write!(f, "{} {} on {}", effect, self.action, self.resource)
+    }
+}
+
+fn pattern_matches(pattern: &str, value: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => pattern == value,
+    }
+}
+
+// Named set of rules, plus the roles whose rules it inherits
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Role {
+    pub name: String,
+    #[serde(default)]
+    pub inherits: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl Role {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Self::default() }
+    }
+
+    pub fn inherits(mut self, role: &str) -> Self {
+        self.inherits.push(role.to_string());
+        self
+    }
+
+    pub fn allow(mut self, action: &str, resource: &str) -> Self {
+        self.rules.push(Rule::allow(action, resource));
+        self
+    }
+
+    pub fn deny(mut self, action: &str, resource: &str) -> Self {
+        self.rules.push(Rule::deny(action, resource));
+        self
+    }
+}
+
+// Where the rule behind a decision was defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSource {
+    Role(String),
+    // Granted or revoked on the PermissionFacet itself
+    Override,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    pub allowed: bool,
+    // None when no rule matched and the action was denied by default
+    pub matched: Option<(Rule, RuleSource)>,
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        self.allowed
+    }
+
+    // Which rule decided, e.g. "allowed by 'allow financial_operations on *'
+    // of role manager"
+    pub fn explanation(&self) -> String {
+        let verdict = if self.allowed { "allowed" } else { "denied" };
+        match &self.matched {
+            Some((rule, RuleSource::Role(role))) => format!("{} by '{}' of role {}", verdict, rule, role),
+            Some((rule, RuleSource::Override)) => format!("{} by override '{}'", verdict, rule),
+            None => format!("{}: no rule matched", verdict),
+        }
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.explanation())
+    }
+}
+
+// Role hierarchy shared by PermissionFacets
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Policy {
+    roles: BTreeMap<String, Role>,
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // admin ⊃ manager ⊃ employee: employees read, managers also write and
+    // run financial operations, admins also delete
+    pub fn builtin() -> Self {
+        Self::new()
+            .role(Role::new("employee").allow("read", "*"))
+            .role(Role::new("manager").inherits("employee").allow("write", "*").allow("financial_operations", "*"))
+            .role(Role::new("admin").inherits("manager").allow("delete", "*"))
+    }
+
+    pub fn role(mut self, role: Role) -> Self {
+        self.roles.insert(role.name.clone(), role);
+        self
+    }
+
+    pub fn get_role(&self, name: &str) -> Option<&Role> {
+        self.roles.get(name)
+    }
+
+    // `role` followed by everything it inherits, nearest first; unknown
+    // roles and cycles are skipped
+    pub fn lineage(&self, role: &str) -> Vec<&Role> {
+        let mut lineage: Vec<&Role> = Vec::new();
+        let mut pending = vec![role];
+        while !pending.is_empty() {
+            let name = pending.remove(0);
+            if lineage.iter().any(|seen| seen.name == name) {
+                continue;
+            }
+            if let Some(role) = self.roles.get(name) {
+                pending.extend(role.inherits.iter().map(String::as_str));
+                lineage.push(role);
+            }
+        }
+        lineage
+    }
+
+    // Evaluate `overrides` and then the rules of `role` and its ancestors.
+    // Any matching deny wins over every grant; without a matching rule the
+    // action is denied.
+    pub fn check(&self, role: &str, overrides: &[Rule], action: &str, resource: &str) -> Decision {
+        let candidates = overrides.iter()
+            .map(|rule| (rule, RuleSource::Override))
+            .chain(self.lineage(role).into_iter().flat_map(|role| {
+                role.rules.iter().map(|rule| (rule, RuleSource::Role(role.name.clone())))
+            }));
+
+        let mut allowed = None;
+        for (rule, source) in candidates.filter(|(rule, _)| rule.matches(action, resource)) {
+            match rule.effect {
+                Effect::Deny => return Decision { allowed: false, matched: Some((rule.clone(), source)) },
+                Effect::Allow => {
+                    allowed.get_or_insert((rule.clone(), source));
+                }
+            }
+        }
+        Decision { allowed: allowed.is_some(), matched: allowed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inherited_and_wildcard_rules() {
+        let policy = Policy::builtin()
+            .role(Role::new("teller").inherits("employee").allow("account:*", "*").deny("account:close", "*"));
+
+        let decision = policy.check("admin", &[], "financial_operations", "*");
+        assert!(decision.is_allowed());
+        assert_eq!(decision.explanation(), "allowed by 'allow financial_operations on *' of role manager");
+
+        assert!(policy.check("teller", &[], "account:deposit", "ACC001").is_allowed());
+        assert!(policy.check("teller", &[], "read", "ACC001").is_allowed());
+        assert_eq!(
+            policy.check("teller", &[], "account:close", "ACC001").explanation(),
+            "denied by 'deny account:close on *' of role teller",
+        );
+        assert_eq!(policy.check("employee", &[], "write", "*").explanation(), "denied: no rule matched");
+    }
+
+    #[test]
+    fn test_deny_overrides_grants() {
+        let policy = Policy::builtin().role(Role::new("loop").inherits("loop").allow("read", "*"));
+        let overrides = [Rule::allow("account:*", "*"), Rule::deny("account:withdraw", "ACC002")];
+
+        assert!(policy.check("employee", &overrides, "account:withdraw", "ACC001").is_allowed());
+        let decision = policy.check("admin", &overrides, "account:withdraw", "ACC002");
+        assert!(!decision.is_allowed());
+        assert_eq!(decision.matched.unwrap().1, RuleSource::Override);
+
+        assert!(policy.check("loop", &[], "read", "*").is_allowed());
+        assert!(!policy.check("unknown", &[], "read", "*").is_allowed());
+    }
+}
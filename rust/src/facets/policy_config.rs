@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::error::FacetError;
+use crate::facets::permission::PermissionFacet;
+use crate::facets::policy::{Policy, Role, Rule};
+
+// Roles defined in a configuration file, e.g. in TOML:
+//
+//     [roles.employee]
+//     permissions = ["read"]
+//
+//     [roles.manager]
+//     inherits = ["employee"]
+//     permissions = ["write", "financial_operations"]
+//     deny = ["account:close"]
+//     rules = [{ effect = "allow", action = "account:close", resource = "ACC-TEMP*" }]
+//
+// or the same structure in YAML. `permissions` and `deny` apply to every
+// resource; `rules` can narrow them to some.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyConfig {
+    roles: BTreeMap<String, RoleConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RoleConfig {
+    #[serde(default)]
+    inherits: Vec<String>,
+    #[serde(default)]
+    permissions: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+    #[serde(default)]
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[cfg(feature = "policy-toml")]
+    Toml,
+    #[cfg(feature = "policy-yaml")]
+    Yaml,
+}
+
+impl ConfigFormat {
+    // By extension: .toml, .yaml or .yml
+    pub fn from_path(path: &Path) -> Result<Self, FacetError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "policy-toml")]
+            Some("toml") => Ok(ConfigFormat::Toml),
+            #[cfg(feature = "policy-yaml")]
+            Some("yaml" | "yml") => Ok(ConfigFormat::Yaml),
+            _ => Err(config_error(&path.display().to_string(), "unsupported configuration format")),
+        }
+    }
+}
+
+fn config_error(entry: &str, message: impl ToString) -> FacetError {
+    FacetError::InvalidConfig { entry: entry.to_string(), message: message.to_string() }
+}
+
+#[cfg(feature = "policy-toml")]
+fn toml_error(text: &str, error: toml::de::Error) -> FacetError {
+    let Some(offset) = error.span().map(|span| span.start) else {
+        return config_error("document", error.message());
+    };
+    let line_start = text[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+    let line = text[..offset].matches('\n').count() + 1;
+    config_error(&format!("line {}, column {}", line, offset - line_start + 1), error.message())
+}
+
+#[cfg(feature = "policy-yaml")]
+fn yaml_error(error: serde_yaml::Error) -> FacetError {
+    match error.location() {
+        Some(location) => config_error(&format!("line {}, column {}", location.line(), location.column()), error),
+        None => config_error("document", error),
+    }
+}
+
+fn check_pattern(entry: &str, pattern: &str) -> Result<(), FacetError> {
+    if pattern.is_empty() {
+        return Err(config_error(entry, "empty permission"));
+    }
+    if pattern.find('*').is_some_and(|star| star + 1 != pattern.len()) {
+        return Err(config_error(entry, format!("'*' may only end a pattern, got '{}'", pattern)));
+    }
+    Ok(())
+}
+
+// Inheritance path from `start` back to itself, if there is one
+fn cycle<'a>(roles: &'a BTreeMap<String, RoleConfig>, start: &'a str) -> Option<Vec<&'a str>> {
+    fn walk<'a>(roles: &'a BTreeMap<String, RoleConfig>, path: &mut Vec<&'a str>, visited: &mut BTreeSet<&'a str>) -> bool {
+        let current = path[path.len() - 1];
+        for parent in roles.get(current).into_iter().flat_map(|role| &role.inherits) {
+            if parent == path[0] {
+                path.push(parent);
+                return true;
+            }
+            if visited.insert(parent) {
+                path.push(parent);
+                if walk(roles, path, visited) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+
+    let mut path = vec![start];
+    walk(roles, &mut path, &mut BTreeSet::new()).then_some(path)
+}
+
+impl PolicyConfig {
+    fn validate(&self) -> Result<(), FacetError> {
+        if self.roles.is_empty() {
+            return Err(config_error("roles", "no roles defined"));
+        }
+        for (name, role) in &self.roles {
+            for (index, parent) in role.inherits.iter().enumerate() {
+                if !self.roles.contains_key(parent) {
+                    return Err(config_error(&format!("roles.{}.inherits[{}]", name, index), format!("unknown role '{}'", parent)));
+                }
+            }
+            for (index, permission) in role.permissions.iter().enumerate() {
+                check_pattern(&format!("roles.{}.permissions[{}]", name, index), permission)?;
+            }
+            for (index, permission) in role.deny.iter().enumerate() {
+                check_pattern(&format!("roles.{}.deny[{}]", name, index), permission)?;
+            }
+            for (index, rule) in role.rules.iter().enumerate() {
+                let entry = format!("roles.{}.rules[{}]", name, index);
+                check_pattern(&entry, &rule.action)?;
+                check_pattern(&entry, &rule.resource)?;
+            }
+            if let Some(path) = cycle(&self.roles, name) {
+                return Err(config_error(&format!("roles.{}.inherits", name), format!("inheritance cycle {}", path.join(" -> "))));
+            }
+        }
+        Ok(())
+    }
+
+    fn into_policy(self) -> Policy {
+        self.roles.into_iter().fold(Policy::new(), |policy, (name, config)| {
+            let mut role = config.inherits.iter().fold(Role::new(&name), |role, parent| role.inherits(parent));
+            role = config.permissions.iter().fold(role, |role, permission| role.allow(permission, "*"));
+            role = config.deny.iter().fold(role, |role, permission| role.deny(permission, "*"));
+            role.rules.extend(config.rules);
+            policy.role(role)
+        })
+    }
+}
+
+impl Policy {
+    // Roles defined in `text`; fails on syntax errors, unknown fields,
+    // unknown inherited roles, malformed patterns and inheritance cycles
+    pub fn from_config_str(text: &str, format: ConfigFormat) -> Result<Self, FacetError> {
+        let config: PolicyConfig = match format {
+            #[cfg(feature = "policy-toml")]
+            ConfigFormat::Toml => toml::from_str(text).map_err(|e| toml_error(text, e))?,
+            #[cfg(feature = "policy-yaml")]
+            ConfigFormat::Yaml => serde_yaml::from_str(text).map_err(yaml_error)?,
+        };
+        config.validate()?;
+        Ok(config.into_policy())
+    }
+
+    // Roles defined in the file at `path`, in the format its extension names
+    pub fn from_config(path: impl AsRef<Path>) -> Result<Self, FacetError> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)?;
+        let text = std::fs::read_to_string(path).map_err(|e| config_error(&path.display().to_string(), e))?;
+        Self::from_config_str(&text, format)
+    }
+}
+
+impl PermissionFacet {
+    // Permissions of `role` in the policy defined in the file at `path`
+    pub fn from_config(role: &str, path: impl AsRef<Path>) -> Result<Self, FacetError> {
+        Self::for_role(role, Policy::from_config(path)?)
+    }
+
+    pub fn from_str(role: &str, config: &str, format: ConfigFormat) -> Result<Self, FacetError> {
+        Self::for_role(role, Policy::from_config_str(config, format)?)
+    }
+
+    fn for_role(role: &str, policy: Policy) -> Result<Self, FacetError> {
+        if policy.get_role(role).is_none() {
+            return Err(config_error(&format!("roles.{}", role), "role not defined"));
+        }
+        Ok(Self::with_policy(role, Arc::new(policy)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_from_config() {
+        #[cfg(feature = "policy-toml")]
+        let (text, format) = (r#"
+            [roles.employee]
+            permissions = ["read"]
+
+            [roles.manager]
+            inherits = ["employee"]
+            permissions = ["write", "account:*"]
+            deny = ["account:close"]
+            rules = [{ effect = "allow", action = "account:close", resource = "TEMP*" }]
+        "#, ConfigFormat::Toml);
+        #[cfg(not(feature = "policy-toml"))]
+        let (text, format) = ("
+            roles:
+              employee:
+                permissions: [read]
+              manager:
+                inherits: [employee]
+                permissions: [write, 'account:*']
+                deny: ['account:close']
+                rules:
+                  - { effect: allow, action: 'account:close', resource: 'TEMP*' }
+        ", ConfigFormat::Yaml);
+
+        let manager = PermissionFacet::from_str("manager", text, format).unwrap();
+        assert!(manager.has_permission("read") && manager.has_permission("account:deposit"));
+        assert!(!manager.has_permission("account:close"));
+        assert!(PermissionFacet::from_str("employee", text, format).unwrap().has_permission("read"));
+        assert_eq!(
+            PermissionFacet::from_str("admin", text, format).unwrap_err().to_string(),
+            "Invalid configuration at roles.admin: role not defined",
+        );
+    }
+
+    #[test]
+    fn test_errors_point_at_entry() {
+        #[cfg(feature = "policy-toml")]
+        {
+            let unknown = Policy::from_config_str("[roles.manager]\ninherits = [\"staff\"]\n", ConfigFormat::Toml);
+            assert_eq!(unknown, Err(config_error("roles.manager.inherits[0]", "unknown role 'staff'")));
+            let cycle = Policy::from_config_str("[roles.a]\ninherits = [\"b\"]\n[roles.b]\ninherits = [\"a\"]\n", ConfigFormat::Toml);
+            assert_eq!(cycle, Err(config_error("roles.a.inherits", "inheritance cycle a -> b -> a")));
+            let typo = Policy::from_config_str("[roles.employee]\npermisions = [\"read\"]\n", ConfigFormat::Toml).unwrap_err();
+            assert!(matches!(typo, FacetError::InvalidConfig { entry, .. } if entry == "line 2, column 1"));
+        }
+        #[cfg(feature = "policy-yaml")]
+        {
+            let pattern = Policy::from_config_str("roles:\n  employee:\n    permissions: [read, 'a*b']\n", ConfigFormat::Yaml);
+            assert_eq!(pattern, Err(config_error("roles.employee.permissions[1]", "'*' may only end a pattern, got 'a*b'")));
+            let syntax = Policy::from_config_str("roles:\n  employee: [\n", ConfigFormat::Yaml).unwrap_err();
+            assert!(matches!(syntax, FacetError::InvalidConfig { entry, .. } if entry.starts_with("line ")));
+        }
+        assert!(matches!(Policy::from_config("roles.ini"), Err(FacetError::InvalidConfig { .. })));
+    }
+}
@@ -0,0 +1,195 @@
+use std::any::TypeId;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::interceptor::{FacetAccess, FacetInterceptor};
+use crate::summary::{FacetSummary, Summarizable};
+use crate::{Facet, FacetError, FacetedObject};
+
+// Token bucket throttling operations on one object: holds up to `burst`
+// tokens, refilled at `rate` tokens per `per`, and every operation takes
+// one. Attach it together with a RateLimitInterceptor to throttle facet
+// writes, or call try_acquire from operations directly.
+#[derive(Debug, Clone, Facet)]
+#[facet(name = "rate_limiter", description = "Token bucket limiting how often operations may run", tags("throttling"), summarize)]
+pub struct RateLimiterFacet {
+    rate: u32,
+    per: Duration,
+    burst: u32,
+    tokens: f64,
+    refilled_at: Timestamp,
+    clock: Arc<dyn Clock>,
+}
+
+impl RateLimiterFacet {
+    // `rate` operations per `per`, with a burst of as many; starts full
+    pub fn new(rate: u32, per: Duration) -> Self {
+        Self::with_clock(rate, per, Arc::new(SystemClock))
+    }
+
+    pub fn per_minute(rate: u32) -> Self {
+        Self::new(rate, Duration::from_secs(60))
+    }
+
+    pub fn with_clock(rate: u32, per: Duration, clock: Arc<dyn Clock>) -> Self {
+        let refilled_at = clock.now();
+        Self { rate, per, burst: rate, tokens: f64::from(rate), refilled_at, clock }
+    }
+
+    // Allow up to `burst` operations back to back; the bucket starts full
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self.tokens = f64::from(burst);
+        self
+    }
+
+    // Take a token for `operation`, or fail with RateLimited saying how
+    // long until one is available
+    pub fn try_acquire(&mut self, operation: &str) -> Result<(), FacetError> {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(FacetError::RateLimited { operation: operation.to_string(), retry_after: self.retry_after() })
+    }
+
+    // Whole tokens available now
+    pub fn available(&mut self) -> u32 {
+        self.refill();
+        self.tokens as u32
+    }
+
+    fn token_interval(&self) -> Duration {
+        if self.rate == 0 {
+            Duration::MAX
+        } else {
+            self.per / self.rate
+        }
+    }
+
+    // Saturates at Duration::MAX, e.g. for a rate of 0 that never refills
+    fn retry_after(&self) -> Duration {
+        let missing = (1.0 - self.tokens).clamp(0.0, 1.0);
+        Duration::try_from_secs_f64(self.token_interval().as_secs_f64() * missing).unwrap_or(Duration::MAX)
+    }
+
+    fn refill(&mut self) {
+        let now = self.clock.now();
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        if self.rate > 0 && !self.per.is_zero() {
+            let earned = elapsed.as_secs_f64() * f64::from(self.rate) / self.per.as_secs_f64();
+            self.tokens = (self.tokens + earned).min(f64::from(self.burst));
+        }
+        self.refilled_at = now;
+    }
+}
+
+impl Summarizable for RateLimiterFacet {
+    fn summarize(&self) -> FacetSummary {
+        FacetSummary::new("Rate Limiter")
+            .field("Rate", format!("{} per {:?}", self.rate, self.per))
+            .field("Burst", self.burst)
+            .field("Tokens", self.tokens as u32)
+    }
+}
+
+// Takes a token from the object's RateLimiterFacet for every mutable
+// access to the limited facet types, e.g. AccountFacet to throttle
+// financial operations. Objects without a RateLimiterFacet are not limited.
+#[derive(Debug, Default)]
+pub struct RateLimitInterceptor {
+    limited: Vec<TypeId>,
+}
+
+impl RateLimitInterceptor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Limit writes to facets of type F
+    pub fn limit<F: Facet + 'static>(mut self) -> Self {
+        self.limited.push(TypeId::of::<F>());
+        self
+    }
+}
+
+impl FacetInterceptor for RateLimitInterceptor {
+    fn name(&self) -> &str {
+        "rate_limit"
+    }
+
+    fn before(&self, object: &FacetedObject, access: &FacetAccess<'_>) -> Result<(), FacetError> {
+        if !access.mutable || !self.limited.contains(&access.type_id) {
+            return Ok(());
+        }
+        let facet = access.type_name.rsplit("::").next().unwrap_or(access.type_name);
+        match object.with_facet_mut::<RateLimiterFacet, _>(|limiter| limiter.try_acquire(facet)) {
+            Ok(acquired) => acquired,
+            Err(FacetError::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, ManualClock, Money};
+
+    fn limiter(rate: u32, per: Duration) -> (RateLimiterFacet, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        (RateLimiterFacet::with_clock(rate, per, clock.clone()), clock)
+    }
+
+    #[test]
+    fn test_token_bucket_refills_at_rate() {
+        let (limiter, clock) = limiter(6, Duration::from_secs(60));
+        let mut limiter = limiter.with_burst(2);
+        limiter.try_acquire("deposit").unwrap();
+        limiter.try_acquire("deposit").unwrap();
+
+        let limited = limiter.try_acquire("deposit");
+        assert!(matches!(limited, Err(FacetError::RateLimited { retry_after, .. }) if retry_after == Duration::from_secs(10)));
+        clock.advance(Duration::from_secs(4));
+        assert!(matches!(limiter.try_acquire("deposit"), Err(FacetError::RateLimited { retry_after, .. }) if retry_after == Duration::from_secs(6)));
+
+        // Never more than the burst, however long it was idle
+        clock.advance(Duration::from_secs(600));
+        assert_eq!(limiter.available(), 2);
+    }
+
+    #[test]
+    fn test_zero_rate_never_refills() {
+        let (limiter, clock) = limiter(0, Duration::from_secs(60));
+        let mut limiter = limiter.with_burst(1);
+        limiter.try_acquire("deposit").unwrap();
+        clock.advance(Duration::from_secs(3600));
+
+        let limited = limiter.try_acquire("deposit");
+        assert!(matches!(limited, Err(FacetError::RateLimited { retry_after, .. }) if retry_after == Duration::MAX));
+        assert!(matches!(RateLimiterFacet::new(0, Duration::from_secs(1)).try_acquire("deposit"), Err(FacetError::RateLimited { .. })));
+    }
+
+    #[test]
+    fn test_interceptor_limits_financial_operations() {
+        let (limiter, clock) = limiter(2, Duration::from_secs(60));
+        let object = FacetedObject::new(());
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        object.attach_facet(limiter).unwrap();
+        object.add_interceptor(RateLimitInterceptor::new().limit::<AccountFacet>()).unwrap();
+
+        let deposit = || object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10)));
+        deposit().unwrap().unwrap();
+        deposit().unwrap().unwrap();
+        assert!(matches!(deposit(), Err(FacetError::RateLimited { operation, .. }) if operation == "AccountFacet"));
+
+        // Reads are not limited
+        assert_eq!(object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(20));
+        clock.advance(Duration::from_secs(30));
+        deposit().unwrap().unwrap();
+        assert!(deposit().is_err());
+    }
+}
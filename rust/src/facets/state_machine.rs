@@ -0,0 +1,204 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::facets::Auditable;
+use crate::metadata::FacetMetadata;
+use crate::{Facet, FacetError, FacetedObject};
+
+// Decides whether a transition may happen on the object, e.g. by checking
+// its PermissionFacet; the error is returned from FacetedObject::transition
+type TransitionGuard = Arc<dyn Fn(&FacetedObject) -> Result<(), FacetError> + Send + Sync>;
+
+struct Transition<S, E> {
+    from: S,
+    event: E,
+    to: S,
+    guard: Option<TransitionGuard>,
+}
+
+// One transition taken
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateChange<S, E> {
+    pub from: S,
+    pub event: E,
+    pub to: S,
+    pub timestamp: Timestamp,
+}
+
+// Workflow state with the transitions between states declared up front,
+// e.g. employee onboarding. Events are fired with FacetedObject::transition,
+// which checks the transition's guard and writes each change to the
+// object's audit trail.
+pub struct StateMachineFacet<S, E> {
+    state: S,
+    transitions: Vec<Transition<S, E>>,
+    history: Vec<StateChange<S, E>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<S, E> StateMachineFacet<S, E>
+where
+    S: Clone + PartialEq + Debug + Send + Sync + 'static,
+    E: Clone + PartialEq + Debug + Send + Sync + 'static,
+{
+    pub fn new(initial: S) -> Self {
+        Self::with_clock(initial, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(initial: S, clock: Arc<dyn Clock>) -> Self {
+        Self { state: initial, transitions: Vec::new(), history: Vec::new(), clock }
+    }
+
+    // Allow `event` to move the machine from `from` to `to`
+    pub fn transition(self, from: S, event: E, to: S) -> Self {
+        self.add(from, event, to, None)
+    }
+
+    // As transition, but only while `guard` allows it
+    pub fn guarded_transition(
+        self,
+        from: S,
+        event: E,
+        to: S,
+        guard: impl Fn(&FacetedObject) -> Result<(), FacetError> + Send + Sync + 'static,
+    ) -> Self {
+        self.add(from, event, to, Some(Arc::new(guard)))
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    // Transitions taken so far, oldest first
+    pub fn history(&self) -> &[StateChange<S, E>] {
+        &self.history
+    }
+
+    // Events with a transition out of the current state, guarded or not
+    pub fn available_events(&self) -> Vec<&E> {
+        self.transitions.iter()
+            .filter(|transition| transition.from == self.state)
+            .map(|transition| &transition.event)
+            .collect()
+    }
+
+    fn add(mut self, from: S, event: E, to: S, guard: Option<TransitionGuard>) -> Self {
+        self.transitions.retain(|transition| transition.from != from || transition.event != event);
+        self.transitions.push(Transition { from, event, to, guard });
+        self
+    }
+
+    fn find(&self, event: &E) -> Result<&Transition<S, E>, FacetError> {
+        self.transitions.iter()
+            .find(|transition| transition.from == self.state && transition.event == *event)
+            .ok_or_else(|| FacetError::InvalidTransition { from: format!("{:?}", self.state), event: format!("{:?}", event) })
+    }
+}
+
+impl<S, E> Facet for StateMachineFacet<S, E>
+where
+    S: Clone + PartialEq + Debug + Send + Sync + 'static,
+    E: Clone + PartialEq + Debug + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn facet_name(&self) -> &'static str {
+        "state_machine"
+    }
+
+    fn metadata(&self) -> FacetMetadata {
+        FacetMetadata::of(self).description("Workflow state with declared transitions").tags(&["workflow"])
+    }
+}
+
+impl FacetedObject {
+    // Fire `event` on the object's StateMachineFacet<S, E> and return the
+    // new state. Fails with InvalidTransition if the current state has no
+    // transition for the event, or with the guard's error. The guard runs
+    // without the state machine locked, so it may read any facet; if the
+    // state changes meanwhile the event is checked again from the new state.
+    pub fn transition<S, E>(&self, event: E) -> Result<S, FacetError>
+    where
+        S: Clone + PartialEq + Debug + Send + Sync + 'static,
+        E: Clone + PartialEq + Debug + Send + Sync + 'static,
+    {
+        loop {
+            let (from, guard) = self.with_facet::<StateMachineFacet<S, E>, _>(|machine| {
+                machine.find(&event).map(|transition| (machine.state.clone(), transition.guard.clone()))
+            })??;
+            if let Some(guard) = guard {
+                guard(self)?;
+            }
+
+            let change = self.with_facet_mut::<StateMachineFacet<S, E>, _>(|machine| {
+                if machine.state != from {
+                    return None;
+                }
+                let to = machine.find(&event).ok()?.to.clone();
+                let change = StateChange { from: from.clone(), event: event.clone(), to: to.clone(), timestamp: machine.clock.now() };
+                machine.history.push(change.clone());
+                machine.state = to;
+                Some(change)
+            })?;
+
+            if let Some(change) = change {
+                let details = format!("{:?} -> {:?} on {:?}", change.from, change.to, change.event);
+                let _ = self.with_facet_as_mut::<dyn Auditable, ()>(|audit| audit.log_operation("state_transition", &details));
+                return Ok(change.to);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::employee::{Onboarding, OnboardingEvent};
+    use crate::{AuditFacet, Employee, PermissionFacet};
+
+    fn employee(role: &str) -> FacetedObject {
+        FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with(PermissionFacet::new(role))
+            .with(AuditFacet::new())
+            .with(Employee::onboarding())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_onboarding_workflow() {
+        let employee = employee("manager");
+        assert_eq!(employee.transition::<Onboarding, _>(OnboardingEvent::Hire).unwrap(), Onboarding::Hired);
+        assert_eq!(employee.transition::<Onboarding, _>(OnboardingEvent::Activate).unwrap(), Onboarding::Active);
+
+        let illegal = employee.transition::<Onboarding, _>(OnboardingEvent::Hire);
+        assert_eq!(illegal, Err(FacetError::InvalidTransition { from: "Active".to_string(), event: "Hire".to_string() }));
+
+        let history = employee.with_facet::<StateMachineFacet<Onboarding, OnboardingEvent>, _>(|machine| {
+            (machine.history().len(), machine.available_events().into_iter().cloned().collect::<Vec<_>>())
+        }).unwrap();
+        assert_eq!(history, (2, vec![OnboardingEvent::Terminate]));
+        let trail = employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().iter().map(|entry| entry.details.clone()).collect::<Vec<_>>()).unwrap();
+        assert_eq!(trail, ["Applied -> Hired on Hire", "Hired -> Active on Activate"]);
+    }
+
+    #[test]
+    fn test_guard_consults_permissions() {
+        let employee = employee("manager");
+        employee.transition::<Onboarding, _>(OnboardingEvent::Hire).unwrap();
+        employee.transition::<Onboarding, _>(OnboardingEvent::Activate).unwrap();
+
+        let denied = employee.transition::<Onboarding, _>(OnboardingEvent::Terminate);
+        assert!(matches!(denied, Err(FacetError::PermissionDenied { .. })));
+        employee.with_facet_mut::<PermissionFacet, _>(|permissions| permissions.grant_permission("delete")).unwrap();
+        assert_eq!(employee.transition::<Onboarding, _>(OnboardingEvent::Terminate).unwrap(), Onboarding::Terminated);
+    }
+}
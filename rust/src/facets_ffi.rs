@@ -0,0 +1,226 @@
+// C ABI for embedding in C, C++ or Python hosts. Objects are opaque
+// handles, facet state and command parameters and results cross the
+// boundary as JSON, so hosts never see a Rust type. Facets come from the
+// built-in FacetRegistry and operations from the CommandBus built-in
+// commands. The declarations are in include/facets.h.
+//
+// Failing calls return null or -1 and leave a message for
+// facets_last_error on the calling thread. Panics are caught at the
+// boundary and reported the same way.
+
+// Safety requirements are given on each function as `//` comments
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::command::CommandBus;
+use crate::registry::{FacetRegistry, ObjectRegistry};
+use crate::{FacetError, FacetedObject};
+
+// Id of the handle's object in its own ObjectRegistry
+const OBJECT_ID: &str = "object";
+
+// Opaque to C: a faceted object with a JSON core, the registry its facets
+// are built from and the commands that can be invoked on it
+pub struct FacetedObjectHandle {
+    bus: CommandBus,
+    registry: FacetRegistry,
+}
+
+impl FacetedObjectHandle {
+    fn new(core: Value) -> Result<Self, FacetError> {
+        let objects = Arc::new(ObjectRegistry::new());
+        objects.insert(OBJECT_ID, FacetedObject::new(core))?;
+        let bus = CommandBus::new(objects);
+        bus.register_builtin_commands()?;
+        Ok(Self { bus, registry: FacetRegistry::builtin() })
+    }
+
+    fn object(&self) -> Result<Arc<FacetedObject>, FacetError> {
+        self.bus.objects().get(OBJECT_ID).ok_or_else(|| FacetError::Other("Object has been freed".to_string()))
+    }
+
+    fn attach_json(&self, name: &str, state: Value) -> Result<(), FacetError> {
+        let facet = self.registry.deserialize(name, state)?;
+        self.object()?.attach_facets_ordered(vec![facet])
+    }
+
+    fn invoke(&self, command: &str, params: Value) -> Result<Value, FacetError> {
+        self.bus.dispatch(OBJECT_ID, command, params)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+// Run `call` with panics caught, recording any error; `failed` is returned
+// in that case
+fn guarded<T>(failed: T, call: impl FnOnce() -> Result<T, FacetError>) -> T {
+    match catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(error)) => {
+            set_last_error(error.to_string());
+            failed
+        }
+        Err(_) => {
+            set_last_error("Panic inside facets library".to_string());
+            failed
+        }
+    }
+}
+
+unsafe fn text<'a>(pointer: *const c_char, argument: &str) -> Result<&'a str, FacetError> {
+    if pointer.is_null() {
+        return Err(FacetError::Invalid(format!("'{}' is null", argument)));
+    }
+    CStr::from_ptr(pointer).to_str().map_err(|_| FacetError::Invalid(format!("'{}' is not UTF-8", argument)))
+}
+
+unsafe fn json(pointer: *const c_char, argument: &str) -> Result<Value, FacetError> {
+    serde_json::from_str(text(pointer, argument)?)
+        .map_err(|e| FacetError::Invalid(format!("'{}' is not valid JSON: {}", argument, e)))
+}
+
+unsafe fn handle<'a>(pointer: *const FacetedObjectHandle) -> Result<&'a FacetedObjectHandle, FacetError> {
+    pointer.as_ref().ok_or_else(|| FacetError::Invalid("'object' is null".to_string()))
+}
+
+// New object whose core is `core_json`; null on failure. Release it with
+// faceted_object_free.
+// Safety: `core_json` is null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn faceted_object_new(core_json: *const c_char) -> *mut FacetedObjectHandle {
+    guarded(ptr::null_mut(), || {
+        let handle = FacetedObjectHandle::new(json(core_json, "core_json")?)?;
+        Ok(Box::into_raw(Box::new(handle)))
+    })
+}
+
+// Attach the facet registered as `name` (e.g. "account"), restored from
+// `state_json`; 0 on success, -1 on failure.
+// Safety: `object` is null or a live handle from faceted_object_new; the
+// strings are null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn facet_attach_json(
+    object: *const FacetedObjectHandle,
+    name: *const c_char,
+    state_json: *const c_char,
+) -> c_int {
+    guarded(-1, || {
+        handle(object)?.attach_json(text(name, "name")?, json(state_json, "state_json")?)?;
+        Ok(0)
+    })
+}
+
+// Invoke the registered command `command` (e.g. "deposit") with
+// `params_json`, returning its result as JSON; null on failure. Release
+// the result with facets_string_free.
+// Safety: as facet_attach_json.
+#[no_mangle]
+pub unsafe extern "C" fn facet_invoke(
+    object: *const FacetedObjectHandle,
+    command: *const c_char,
+    params_json: *const c_char,
+) -> *mut c_char {
+    guarded(ptr::null_mut(), || {
+        let result = handle(object)?.invoke(text(command, "command")?, json(params_json, "params_json")?)?;
+        CString::new(result.to_string()).map(CString::into_raw).map_err(|e| FacetError::Other(e.to_string()))
+    })
+}
+
+// Safety: `object` is null or a handle from faceted_object_new that has
+// not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn faceted_object_free(object: *mut FacetedObjectHandle) {
+    if !object.is_null() {
+        drop(Box::from_raw(object));
+    }
+}
+
+// Safety: `string` is null or was returned by facet_invoke and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn facets_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+// Message of the last failure on this thread, or null. Owned by the
+// library and valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn facets_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(text: &str) -> CString {
+        CString::new(text).unwrap()
+    }
+
+    unsafe fn last_error() -> String {
+        CStr::from_ptr(facets_last_error()).to_string_lossy().into_owned()
+    }
+
+    unsafe fn invoke(object: *const FacetedObjectHandle, command: &str, params: &str) -> Option<Value> {
+        let result = facet_invoke(object, c(command).as_ptr(), c(params).as_ptr());
+        if result.is_null() {
+            return None;
+        }
+        let value = serde_json::from_str(CStr::from_ptr(result).to_str().unwrap()).unwrap();
+        facets_string_free(result);
+        Some(value)
+    }
+
+    #[test]
+    fn test_attach_and_invoke_through_c_abi() {
+        unsafe {
+            let object = faceted_object_new(c(r#"{"name": "Test User", "id": "TEST001"}"#).as_ptr());
+            assert!(!object.is_null());
+
+            let account = r#"{"account_number": "ACC001", "currency": "USD", "balances": {"USD": 0}}"#;
+            assert_eq!(facet_attach_json(object, c("account").as_ptr(), c(account).as_ptr()), 0, "{}", last_error());
+            let permissions = r#"{"role": "manager", "overrides": []}"#;
+            assert_eq!(facet_attach_json(object, c("permissions").as_ptr(), c(permissions).as_ptr()), 0, "{}", last_error());
+
+            invoke(object, "deposit", r#"{"amount": 12.5}"#).unwrap();
+            let balance = invoke(object, "balance", "{}").unwrap();
+            assert_eq!(balance["balance"]["minor"], 1250);
+
+            faceted_object_free(object);
+        }
+    }
+
+    #[test]
+    fn test_failures_are_reported_not_raised() {
+        unsafe {
+            assert!(faceted_object_new(ptr::null()).is_null());
+            assert_eq!(last_error(), "'core_json' is null");
+            assert!(faceted_object_new(c("{").as_ptr()).is_null());
+
+            let object = faceted_object_new(c("{}").as_ptr());
+            assert_eq!(facet_attach_json(object, c("unknown").as_ptr(), c("{}").as_ptr()), -1);
+            assert!(invoke(object, "balance", "{}").is_none());
+            assert!(last_error().contains("read"), "{}", last_error());
+            assert!(invoke(ptr::null(), "balance", "{}").is_none());
+            assert_eq!(last_error(), "'object' is null");
+
+            faceted_object_free(object);
+            faceted_object_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::clock::{Clock, Timestamp};
+use crate::core::FacetUsage;
+use crate::registry::ObjectRegistry;
+use crate::FacetError;
+
+// Why a facet was, or in a dry run would be, detached
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectReason {
+    // Facet::expires_at is in the past
+    Expired(Timestamp),
+    // Not accessed for at least this long
+    Idle(Duration),
+    // A facet it depends on is missing or being collected
+    Orphaned,
+}
+
+impl fmt::Display for CollectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollectReason::Expired(at) => write!(f, "expired at {}", at),
+            CollectReason::Idle(idle) => write!(f, "idle for {}s", idle.as_secs()),
+            CollectReason::Orphaned => write!(f, "orphaned"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectedFacet {
+    pub object_id: String,
+    pub facet: &'static str,
+    pub reason: CollectReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub objects_scanned: usize,
+    pub collected: Vec<CollectedFacet>,
+}
+
+impl fmt::Display for GcReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run { "would collect" } else { "collected" };
+        writeln!(f, "GC {} {} facet(s) across {} object(s)", verb, self.collected.len(), self.objects_scanned)?;
+        for collected in &self.collected {
+            writeln!(f, "  {} {}: {}", collected.object_id, collected.facet, collected.reason)?;
+        }
+        Ok(())
+    }
+}
+
+// What a sweep collects. Idle collection is off unless a window is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcPolicy {
+    pub collect_expired: bool,
+    pub idle_after: Option<Duration>,
+    pub collect_orphans: bool,
+}
+
+impl Default for GcPolicy {
+    fn default() -> Self {
+        Self {
+            collect_expired: true,
+            idle_after: None,
+            collect_orphans: true,
+        }
+    }
+}
+
+impl GcPolicy {
+    pub fn idle_after(mut self, window: Duration) -> Self {
+        self.idle_after = Some(window);
+        self
+    }
+}
+
+// Object id, facet type and instance name
+type InstanceKey = (String, TypeId, String);
+
+// Sweeps registry objects and detaches facets the policy marks as garbage.
+// Idleness is measured between sweeps from each facet's access count, so a
+// facet is only ever idle after the collector has seen it at least twice.
+pub struct FacetCollector {
+    policy: GcPolicy,
+    clock: Arc<dyn Clock>,
+    // Access count per facet instance and when it last changed
+    usage: Mutex<HashMap<InstanceKey, (u64, Timestamp)>>,
+}
+
+impl FacetCollector {
+    pub fn new(policy: GcPolicy, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            policy,
+            clock,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Report what `collect` would detach without changing any object
+    pub fn dry_run(&self, registry: &ObjectRegistry) -> Result<GcReport, FacetError> {
+        self.sweep(registry, true)
+    }
+
+    pub fn collect(&self, registry: &ObjectRegistry) -> Result<GcReport, FacetError> {
+        self.sweep(registry, false)
+    }
+
+    fn sweep(&self, registry: &ObjectRegistry, dry_run: bool) -> Result<GcReport, FacetError> {
+        let now = self.clock.now();
+        let mut usage = self.usage.lock();
+        let mut report = GcReport { dry_run, ..GcReport::default() };
+
+        let ids = registry.ids();
+        usage.retain(|(object_id, _, _), _| ids.contains(object_id));
+
+        for object_id in ids {
+            let Some(object) = registry.get(&object_id) else { continue };
+            report.objects_scanned += 1;
+
+            let facets = object.facet_usage()?;
+            let mut garbage: Vec<(&FacetUsage, CollectReason)> = facets.iter()
+                .filter_map(|facet| {
+                    let key = (object_id.clone(), facet.type_id, facet.name.clone());
+                    self.reason(facet, usage.get(&key), now).map(|reason| (facet, reason))
+                })
+                .collect();
+
+            // Record usage after judging idleness against the previous sweep
+            for facet in &facets {
+                let key = (object_id.clone(), facet.type_id, facet.name.clone());
+                match usage.get(&key) {
+                    Some((accesses, _)) if *accesses == facet.accesses => {}
+                    _ => {
+                        usage.insert(key, (facet.accesses, now));
+                    }
+                }
+            }
+
+            if self.policy.collect_orphans {
+                Self::add_orphans(&facets, &mut garbage);
+            }
+
+            for (facet, reason) in garbage {
+                if !dry_run {
+                    object.detach_instance(facet.type_id, &facet.name)?;
+                    usage.remove(&(object_id.clone(), facet.type_id, facet.name.clone()));
+                }
+                report.collected.push(CollectedFacet {
+                    object_id: object_id.clone(),
+                    facet: facet.type_name,
+                    reason,
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    fn reason(&self, facet: &FacetUsage, seen: Option<&(u64, Timestamp)>, now: Timestamp) -> Option<CollectReason> {
+        if self.policy.collect_expired {
+            if let Some(expires_at) = facet.expires_at.filter(|expires_at| *expires_at <= now) {
+                return Some(CollectReason::Expired(expires_at));
+            }
+        }
+
+        let window = self.policy.idle_after?;
+        let (accesses, since) = seen?;
+        let idle = now.saturating_duration_since(*since);
+        (*accesses == facet.accesses && idle >= window).then_some(CollectReason::Idle(idle))
+    }
+
+    // Repeatedly mark facets whose dependencies are gone, since collecting
+    // one facet can orphan another. A dependency is met by any remaining
+    // instance of its type.
+    fn add_orphans<'a>(facets: &'a [FacetUsage], garbage: &mut Vec<(&'a FacetUsage, CollectReason)>) {
+        loop {
+            let remaining: Vec<&FacetUsage> = facets.iter()
+                .filter(|facet| !garbage.iter().any(|(collected, _)| std::ptr::eq(*collected, *facet)))
+                .collect();
+            let remaining_types: HashSet<TypeId> = remaining.iter().map(|facet| facet.type_id).collect();
+
+            let orphans: Vec<&FacetUsage> = remaining.into_iter()
+                .filter(|facet| facet.dependencies.iter().any(|dependency| !remaining_types.contains(dependency)))
+                .collect();
+            if orphans.is_empty() {
+                return;
+            }
+            garbage.extend(orphans.into_iter().map(|facet| (facet, CollectReason::Orphaned)));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use std::any::Any;
+    use crate::clock::ManualClock;
+    use crate::{AccountFacet, AuditFacet, Employee, Facet, FacetedObject, PermissionFacet};
+
+    // Session token valid until a fixed time
+    struct Session(Timestamp);
+
+    impl Facet for Session {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn expires_at(&self) -> Option<Timestamp> {
+            Some(self.0)
+        }
+    }
+
+    // Cached view that is meaningless without its session
+    struct SessionCache;
+
+    impl Facet for SessionCache {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn dependencies(&self) -> Vec<TypeId> {
+            vec![TypeId::of::<Session>()]
+        }
+    }
+
+    fn registry() -> ObjectRegistry {
+        let registry = ObjectRegistry::new();
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(Session(Timestamp::from_millis(10_000))).unwrap();
+        employee.attach_facet(SessionCache).unwrap();
+        registry.insert("TEST001", employee).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_dry_run_then_collect_expired_and_orphans() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let collector = FacetCollector::new(GcPolicy::default(), clock.clone());
+        let registry = registry();
+        assert!(collector.collect(&registry).unwrap().collected.is_empty());
+
+        clock.advance(Duration::from_secs(10));
+        let report = collector.dry_run(&registry).unwrap();
+        let reasons: Vec<_> = report.collected.iter().map(|collected| collected.reason.clone()).collect();
+        assert_eq!(reasons, [CollectReason::Expired(Timestamp::from_millis(10_000)), CollectReason::Orphaned]);
+        assert!(report.to_string().starts_with("GC would collect 2 facet(s) across 1 object(s)\n"));
+
+        let employee = registry.get("TEST001").unwrap();
+        assert!(employee.has_facet::<SessionCache>());
+
+        assert_eq!(collector.collect(&registry).unwrap().collected.len(), 2);
+        assert!(!employee.has_facet::<Session>() && !employee.has_facet::<SessionCache>());
+        assert!(employee.has_facet::<AccountFacet>());
+    }
+
+    #[test]
+    fn test_idle_facets_collected_after_window() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let policy = GcPolicy { collect_expired: false, ..GcPolicy::default() }.idle_after(Duration::from_secs(60));
+        let collector = FacetCollector::new(policy, clock.clone());
+        let registry = ObjectRegistry::new();
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("employee")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        let employee = registry.insert("TEST001", employee).unwrap();
+
+        collector.collect(&registry).unwrap();
+        clock.advance(Duration::from_secs(30));
+        employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+        assert!(collector.collect(&registry).unwrap().collected.is_empty());
+
+        // Permissions and audit were never touched; the account was used 30s ago
+        clock.advance(Duration::from_secs(30));
+        let collected: Vec<_> = collector.collect(&registry).unwrap()
+            .collected.into_iter()
+            .map(|collected| collected.facet)
+            .collect();
+        assert_eq!(collected.len(), 2);
+        assert!(collected.iter().all(|facet| !facet.ends_with("AccountFacet")));
+        assert!(employee.has_facet::<AccountFacet>());
+    }
+}
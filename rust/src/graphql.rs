@@ -0,0 +1,257 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_graphql::{Context, EmptySubscription, Json, Object, Schema, SimpleObject};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::command::CommandBus;
+use crate::{Facet, FacetSummary, FacetedObject};
+#[cfg(feature = "examples")]
+use crate::{AccountFacet, AuditFacet, Employee, PermissionFacet};
+
+type FacetReader = Box<dyn Fn(&FacetedObject) -> Option<Value> + Send + Sync>;
+
+// Which facets (and core type) are visible through GraphQL, by name.
+// Only serde-serializable types can be exposed.
+#[derive(Default)]
+pub struct FacetExposure {
+    facets: Vec<(String, FacetReader)>,
+    core: Option<FacetReader>,
+}
+
+impl FacetExposure {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Expose the built-in example facets and the Employee core
+    #[cfg(feature = "examples")]
+    pub fn with_builtin_facets() -> Self {
+        Self::new()
+            .expose_core::<Employee>()
+            .expose::<AccountFacet>("account")
+            .expose::<PermissionFacet>("permissions")
+            .expose::<AuditFacet>("audit")
+    }
+
+    pub fn expose<F: Facet + Serialize + 'static>(mut self, name: &str) -> Self {
+        self.facets.push((name.to_string(), Box::new(|object: &FacetedObject| {
+            object.with_facet::<F, _>(|facet| serde_json::to_value(facet).ok())
+                .ok()
+                .flatten()
+        })));
+        self
+    }
+
+    pub fn expose_core<T: Any + Serialize>(mut self) -> Self {
+        self.core = Some(Box::new(|object: &FacetedObject| {
+            object.get_core::<T>().and_then(|core| serde_json::to_value(&*core).ok())
+        }));
+        self
+    }
+
+    fn read(&self, object: &FacetedObject, name: &str) -> Option<Value> {
+        self.facets.iter()
+            .find(|(exposed, _)| exposed == name)
+            .and_then(|(_, reader)| reader(object))
+    }
+}
+
+pub type FacetSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+// Build a schema serving the bus's object registry and registered commands
+pub fn build_schema(bus: Arc<CommandBus>, exposure: FacetExposure) -> FacetSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(bus)
+        .data(exposure)
+        .finish()
+}
+
+// Keep only the requested top-level fields of a facet's JSON data
+fn project(value: Value, fields: &Option<Vec<String>>) -> Value {
+    match (value, fields) {
+        (Value::Object(map), Some(fields)) => Value::Object(
+            map.into_iter()
+                .filter(|(key, _)| fields.contains(key))
+                .collect(),
+        ),
+        (value, _) => value,
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn objects(&self, ctx: &Context<'_>) -> Vec<ObjectNode> {
+        let bus = ctx.data_unchecked::<Arc<CommandBus>>();
+        bus.objects().ids()
+            .into_iter()
+            .filter_map(|id| bus.objects().get(&id).map(|object| ObjectNode { id, object }))
+            .collect()
+    }
+
+    async fn object(&self, ctx: &Context<'_>, id: String) -> Option<ObjectNode> {
+        let bus = ctx.data_unchecked::<Arc<CommandBus>>();
+        bus.objects().get(&id).map(|object| ObjectNode { id, object })
+    }
+
+    async fn commands(&self, ctx: &Context<'_>) -> Vec<CommandNode> {
+        let bus = ctx.data_unchecked::<Arc<CommandBus>>();
+        bus.command_names()
+            .into_iter()
+            .filter_map(|name| {
+                let spec = bus.spec(&name)?;
+                Some(CommandNode {
+                    permission: spec.permission().map(str::to_string),
+                    params: spec.params().iter()
+                        .map(|param| ParamNode {
+                            name: param.name.clone(),
+                            param_type: format!("{:?}", param.param_type),
+                            required: param.required,
+                        })
+                        .collect(),
+                    name,
+                })
+            })
+            .collect()
+    }
+}
+
+pub struct ObjectNode {
+    id: String,
+    object: Arc<FacetedObject>,
+}
+
+#[Object]
+impl ObjectNode {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn core(&self, ctx: &Context<'_>) -> Option<Json<Value>> {
+        let exposure = ctx.data_unchecked::<FacetExposure>();
+        exposure.core.as_ref()
+            .and_then(|reader| reader(&self.object))
+            .map(Json)
+    }
+
+    // All exposed facets attached to this object
+    async fn facets(&self, ctx: &Context<'_>, fields: Option<Vec<String>>) -> Vec<FacetNode> {
+        let exposure = ctx.data_unchecked::<FacetExposure>();
+        exposure.facets.iter()
+            .filter_map(|(name, reader)| {
+                reader(&self.object).map(|data| FacetNode {
+                    name: name.clone(),
+                    data: Json(project(data, &fields)),
+                })
+            })
+            .collect()
+    }
+
+    // Summaries of every attached Summarizable facet, exposed or not
+    async fn summaries(&self) -> async_graphql::Result<Vec<Json<FacetSummary>>> {
+        let summaries = self.object.summaries().map_err(|e| async_graphql::Error::new(e.to_string()))?;
+        Ok(summaries.into_iter().map(Json).collect())
+    }
+
+    // A single facet's data, optionally narrowed to the given fields
+    async fn facet(&self, ctx: &Context<'_>, name: String, fields: Option<Vec<String>>) -> Option<Json<Value>> {
+        let exposure = ctx.data_unchecked::<FacetExposure>();
+        exposure.read(&self.object, &name)
+            .map(|data| Json(project(data, &fields)))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct FacetNode {
+    name: String,
+    data: Json<Value>,
+}
+
+#[derive(SimpleObject)]
+pub struct CommandNode {
+    name: String,
+    permission: Option<String>,
+    params: Vec<ParamNode>,
+}
+
+#[derive(SimpleObject)]
+pub struct ParamNode {
+    name: String,
+    param_type: String,
+    required: bool,
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    // Run a registered command through the bus
+    async fn dispatch(
+        &self,
+        ctx: &Context<'_>,
+        object_id: String,
+        command: String,
+        params: Option<Json<Value>>,
+        idempotency_key: Option<String>,
+    ) -> async_graphql::Result<Json<Value>> {
+        let bus = ctx.data_unchecked::<Arc<CommandBus>>();
+        let params = params.map(|Json(params)| params).unwrap_or(Value::Null);
+
+        let result = match idempotency_key {
+            Some(key) => bus.dispatch_idempotent(&object_id, &command, params, &key),
+            None => bus.dispatch(&object_id, &command, params),
+        };
+        result.map(Json).map_err(|e| async_graphql::Error::new(e.to_string()))
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::registry::ObjectRegistry;
+    use serde_json::json;
+
+    fn schema() -> FacetSchema {
+        let objects = Arc::new(ObjectRegistry::new());
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        objects.insert("TEST001", employee).unwrap();
+
+        let bus = Arc::new(CommandBus::new(objects));
+        bus.register_builtin_commands().unwrap();
+        build_schema(bus, FacetExposure::with_builtin_facets())
+    }
+
+    #[tokio::test]
+    async fn test_query_facet_fields() {
+        let schema = schema();
+        let response = schema.execute(
+            r#"{ object(id: "TEST001") { core account: facet(name: "account", fields: ["balances"]) summaries } }"#,
+        ).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["object"]["account"], json!({ "balances": { "USD": 0 } }));
+        assert_eq!(data["object"]["summaries"][1]["title"], json!("Permissions"));
+        assert_eq!(data["object"]["core"]["name"], json!("Test User"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_mutation() {
+        let schema = schema();
+        let response = schema.execute(
+            r#"mutation { dispatch(objectId: "TEST001", command: "deposit", params: { amount: 250.0 }) }"#,
+        ).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(response.data.into_json().unwrap()["dispatch"], json!({ "balance": { "minor": 25_000, "currency": "USD" } }));
+
+        let response = schema.execute(
+            r#"mutation { dispatch(objectId: "TEST001", command: "withdraw", params: { amount: 1000.0 }) }"#,
+        ).await;
+        assert_eq!(response.errors.len(), 1);
+    }
+}
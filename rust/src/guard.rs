@@ -0,0 +1,241 @@
+use std::any::{type_name, TypeId};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+use crate::interceptor::{FacetAccess, HookScope};
+use crate::sync::RwLock;
+#[cfg(feature = "builtin-facets")]
+use crate::facets::Authorizer;
+
+// What a guarded facet access needs the actor to be allowed to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Capability {
+    Read,
+    Write,
+    Financial,
+    Delete,
+    Custom(String),
+}
+
+impl Capability {
+    // Permission granting the capability, as checked by PermissionFacet
+    pub fn permission(&self) -> &str {
+        match self {
+            Capability::Read => "read",
+            Capability::Write => "write",
+            Capability::Financial => "financial_operations",
+            Capability::Delete => "delete",
+            Capability::Custom(permission) => permission,
+        }
+    }
+}
+
+// Decides whether `actor` may use a capability on facets of `target`.
+// Facet accesses made by a policy are neither guarded nor intercepted.
+pub trait AccessPolicy: Send + Sync {
+    fn allows(&self, actor: &FacetedObject, target: &FacetedObject, capability: &Capability) -> bool;
+}
+
+// Allows what the actor's Authorizer facet (usually its PermissionFacet)
+// grants; actors without one are denied
+#[cfg(feature = "builtin-facets")]
+pub struct PermissionPolicy;
+
+#[cfg(feature = "builtin-facets")]
+impl AccessPolicy for PermissionPolicy {
+    fn allows(&self, actor: &FacetedObject, _target: &FacetedObject, capability: &Capability) -> bool {
+        actor.with_facet_as::<dyn Authorizer, _>(|authorizer| authorizer.has_permission(capability.permission()))
+            .unwrap_or(false)
+    }
+}
+
+// Without the built-in facets nothing grants a capability until a policy
+// is set
+#[cfg(not(feature = "builtin-facets"))]
+struct DenyAll;
+
+#[cfg(not(feature = "builtin-facets"))]
+impl AccessPolicy for DenyAll {
+    fn allows(&self, _actor: &FacetedObject, _target: &FacetedObject, _capability: &Capability) -> bool {
+        false
+    }
+}
+
+// Facet types of an object that opted into guarding, and the policy
+// guarded accesses are checked against
+pub(crate) struct Guards {
+    policy: RwLock<Arc<dyn AccessPolicy>>,
+    guarded: RwLock<BTreeSet<TypeId>>,
+}
+
+impl Default for Guards {
+    fn default() -> Self {
+        #[cfg(feature = "builtin-facets")]
+        let policy: Arc<dyn AccessPolicy> = Arc::new(PermissionPolicy);
+        #[cfg(not(feature = "builtin-facets"))]
+        let policy: Arc<dyn AccessPolicy> = Arc::new(DenyAll);
+        Self { policy: RwLock::new(policy), guarded: RwLock::new(BTreeSet::new()) }
+    }
+}
+
+impl Guards {
+    pub(crate) fn copy_from(&self, other: &Guards) {
+        *self.policy.write() = Arc::clone(&*other.policy.read());
+        *self.guarded.write() = other.guarded.read().clone();
+    }
+}
+
+thread_local! {
+    // Object and facet type of the guarded access running on this thread
+    static AUTHORIZED: Cell<Option<(usize, TypeId)>> = const { Cell::new(None) };
+}
+
+// Marks a guarded access as authorized until dropped
+struct AuthorizedScope(Option<(usize, TypeId)>);
+
+impl AuthorizedScope {
+    fn enter(object: &FacetedObject, type_id: TypeId) -> Self {
+        let key = (object as *const FacetedObject as usize, type_id);
+        Self(AUTHORIZED.with(|authorized| authorized.replace(Some(key))))
+    }
+}
+
+impl Drop for AuthorizedScope {
+    fn drop(&mut self) {
+        AUTHORIZED.with(|authorized| authorized.set(self.0));
+    }
+}
+
+impl FacetedObject {
+    // Require guarded access to facets of type F: plain accessors such as
+    // with_facet fail with PermissionDenied for F, and with_facet_guarded
+    // checks the access policy first. Other facet types stay unguarded.
+    pub fn guard_facet<F: Facet + 'static>(&self) -> Result<(), FacetError> {
+        self.guards.guarded.write().insert(TypeId::of::<F>());
+        Ok(())
+    }
+
+    pub fn is_guarded<F: Facet + 'static>(&self) -> bool {
+        self.guards.guarded.read().contains(&TypeId::of::<F>())
+    }
+
+    // Replace the policy guarded accesses are checked against, by default
+    // PermissionPolicy
+    pub fn set_access_policy(&self, policy: impl AccessPolicy + 'static) -> Result<(), FacetError> {
+        *self.guards.policy.write() = Arc::new(policy);
+        Ok(())
+    }
+
+    // Fail with PermissionDenied unless the access policy lets `actor` use
+    // `capability` on this object; `operation` names the attempt in the error
+    pub fn authorize(&self, actor: &FacetedObject, capability: &Capability, operation: &str) -> Result<(), FacetError> {
+        let policy = Arc::clone(&*self.guards.policy.read());
+        let allowed = {
+            let _scope = HookScope::enter();
+            policy.allows(actor, self, capability)
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(FacetError::PermissionDenied { operation: operation.to_string(), permission: capability.permission().to_string() })
+        }
+    }
+
+    // with_facet, once the access policy allowed `actor` to use `capability`
+    pub fn with_facet_guarded<F: Facet + 'static, R>(
+        &self,
+        capability: Capability,
+        actor: &FacetedObject,
+        operation: impl FnOnce(&F) -> R,
+    ) -> Result<R, FacetError> {
+        self.authorize(actor, &capability, type_name::<F>())?;
+        let _scope = AuthorizedScope::enter(self, TypeId::of::<F>());
+        self.with_facet(operation)
+    }
+
+    pub fn with_facet_mut_guarded<F: Facet + 'static, R>(
+        &self,
+        capability: Capability,
+        actor: &FacetedObject,
+        operation: impl FnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        self.authorize(actor, &capability, type_name::<F>())?;
+        let _scope = AuthorizedScope::enter(self, TypeId::of::<F>());
+        self.with_facet_mut(operation)
+    }
+
+    // Reject plain access to a guarded facet type
+    pub(crate) fn check_guard(&self, access: &FacetAccess<'_>) -> Result<(), FacetError> {
+        if !self.guards.guarded.read().contains(&access.type_id) {
+            return Ok(());
+        }
+        let key = (self as *const FacetedObject as usize, access.type_id);
+        if AUTHORIZED.with(|authorized| authorized.get() == Some(key)) {
+            return Ok(());
+        }
+        Err(FacetError::PermissionDenied { operation: access.type_name.to_string(), permission: "guarded access".to_string() })
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet};
+
+    fn employee(role: &str) -> FacetedObject {
+        FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .preset(&Employee::standard_preset())
+            .with(PermissionFacet::new(role))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_guarded_facets_require_capability() {
+        let employee = employee("employee");
+        let manager = FacetedObject::new(());
+        manager.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.guard_facet::<AccountFacet>().unwrap();
+        assert!(employee.is_guarded::<AccountFacet>());
+
+        // Plain access is refused once the type is guarded; other facets are not affected
+        assert!(matches!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()), Err(FacetError::PermissionDenied { .. })));
+        assert!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).is_ok());
+
+        let ran = core::cell::Cell::new(false);
+        let denied = employee.with_facet_mut_guarded::<AccountFacet, _>(Capability::Financial, &employee, |account| {
+            ran.set(true);
+            account.deposit(Money::usd(10))
+        });
+        assert!(matches!(denied, Err(FacetError::PermissionDenied { permission, .. }) if permission == "financial_operations"));
+        assert!(!ran.get());
+
+        employee.with_facet_mut_guarded::<AccountFacet, _>(Capability::Financial, &manager, |account| account.deposit(Money::usd(10)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(employee.with_facet_guarded::<AccountFacet, _>(Capability::Read, &employee, |account| account.get_balance()).unwrap(), Money::usd(10));
+    }
+
+    struct SelfService;
+
+    impl AccessPolicy for SelfService {
+        fn allows(&self, actor: &FacetedObject, target: &FacetedObject, capability: &Capability) -> bool {
+            core::ptr::eq(actor, target) && *capability != Capability::Delete
+        }
+    }
+
+    #[test]
+    fn test_pluggable_policy() {
+        let employee = employee("employee");
+        let other = FacetedObject::new(());
+        other.attach_facet(PermissionFacet::new("admin")).unwrap();
+        employee.set_access_policy(SelfService).unwrap();
+
+        assert!(employee.with_facet_mut_guarded::<AccountFacet, _>(Capability::Financial, &employee, |account| account.deposit(Money::usd(5))).is_ok());
+        assert!(employee.with_facet_guarded::<AccountFacet, _>(Capability::Read, &other, |account| account.get_balance()).is_err());
+        assert!(employee.authorize(&employee, &Capability::Delete, "close_account").is_err());
+    }
+}
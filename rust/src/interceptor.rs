@@ -0,0 +1,285 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::{type_name, TypeId};
+
+use crate::core::{Facet, FacetedObject};
+#[cfg(feature = "std")]
+use crate::correlation::CorrelationScope;
+use crate::error::FacetError;
+
+// Facet access being intercepted
+#[derive(Debug, Clone, Copy)]
+pub struct FacetAccess<'a> {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    // Instance name, DEFAULT_INSTANCE for plain with_facet calls
+    pub instance: &'a str,
+    pub mutable: bool,
+}
+
+impl<'a> FacetAccess<'a> {
+    pub(crate) fn of<F: Facet>(instance: &'a str, mutable: bool) -> Self {
+        Self { type_id: TypeId::of::<F>(), type_name: type_name::<F>(), instance, mutable }
+    }
+
+    pub fn is<F: Facet>(&self) -> bool {
+        self.type_id == TypeId::of::<F>()
+    }
+}
+
+// Cross-cutting behavior wrapped around every with_facet / with_facet_mut
+// call on an object. `before` runs in registration order ahead of the
+// access and can reject it; `after` runs in reverse order once the facet
+// lock is released, for every interceptor whose `before` succeeded.
+// Facet accesses made from inside a hook are not intercepted again (with
+// `std`), so an interceptor may read and write facets of the object.
+pub trait FacetInterceptor: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn before(&self, _object: &FacetedObject, _access: &FacetAccess<'_>) -> Result<(), FacetError> {
+        Ok(())
+    }
+
+    fn after(&self, _object: &FacetedObject, _access: &FacetAccess<'_>, _outcome: Result<(), &FacetError>) {}
+}
+
+pub(crate) type Interceptors = Vec<Arc<dyn FacetInterceptor>>;
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static IN_HOOK: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+// Marks the current thread as running interceptor hooks
+#[cfg(feature = "std")]
+pub(crate) struct HookScope;
+
+#[cfg(feature = "std")]
+impl HookScope {
+    pub(crate) fn enter() -> Option<Self> {
+        IN_HOOK.with(|in_hook| !in_hook.replace(true)).then_some(HookScope)
+    }
+
+    fn active() -> bool {
+        IN_HOOK.with(|in_hook| in_hook.get())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for HookScope {
+    fn drop(&mut self) {
+        IN_HOOK.with(|in_hook| in_hook.set(false));
+    }
+}
+
+#[cfg(not(feature = "std"))]
+struct HookScope;
+
+#[cfg(not(feature = "std"))]
+impl HookScope {
+    fn enter() -> Option<Self> {
+        Some(HookScope)
+    }
+}
+
+// Interceptors whose `before` passed for one access, waiting for the outcome
+pub(crate) struct Interception<'a> {
+    object: &'a FacetedObject,
+    access: FacetAccess<'a>,
+    entered: Interceptors,
+    // Entries the hooks, or anything the access triggers, audit share an id
+    #[cfg(feature = "std")]
+    _correlation: Option<CorrelationScope>,
+}
+
+impl Interception<'_> {
+    pub(crate) fn finish<R>(self, result: Result<R, FacetError>) -> Result<R, FacetError> {
+        if !self.entered.is_empty() {
+            let _scope = HookScope::enter();
+            for interceptor in self.entered.iter().rev() {
+                interceptor.after(self.object, &self.access, result.as_ref().map(|_| ()));
+            }
+        }
+        result
+    }
+}
+
+impl FacetedObject {
+    // Run the `before` hooks for `access`. Must be called without any facet
+    // lock held.
+    pub(crate) fn intercept<'a>(&'a self, access: FacetAccess<'a>) -> Result<Interception<'a>, FacetError> {
+        // Guards apply to callers, not to hooks and access policies
+        #[cfg(feature = "std")]
+        if !HookScope::active() {
+            self.check_guard(&access)?;
+        }
+        let mut interception = Interception {
+            object: self,
+            access,
+            entered: Vec::new(),
+            #[cfg(feature = "std")]
+            _correlation: None,
+        };
+        let interceptors: Interceptors = self.interceptors.read().clone();
+        if interceptors.is_empty() {
+            return Ok(interception);
+        }
+        #[cfg(feature = "std")]
+        {
+            interception._correlation = Some(CorrelationScope::enter());
+        }
+        let rejected = {
+            let Some(_scope) = HookScope::enter() else {
+                return Ok(interception);
+            };
+            interceptors.into_iter().try_for_each(|interceptor| {
+                interceptor.before(self, &interception.access)?;
+                interception.entered.push(interceptor);
+                Ok(())
+            })
+        };
+
+        match rejected {
+            Ok(()) => Ok(interception),
+            Err(e) => interception.finish(Err(e)),
+        }
+    }
+
+    // Append an interceptor; it runs after those already registered
+    pub fn add_interceptor(&self, interceptor: impl FacetInterceptor + 'static) -> Result<(), FacetError> {
+        self.interceptors.write().push(Arc::new(interceptor));
+        Ok(())
+    }
+
+    pub fn insert_interceptor_before(
+        &self,
+        existing: &str,
+        interceptor: impl FacetInterceptor + 'static,
+    ) -> Result<(), FacetError> {
+        let mut interceptors = self.interceptors.write();
+        let index = interceptor_position(&interceptors, existing)?;
+        interceptors.insert(index, Arc::new(interceptor));
+        Ok(())
+    }
+
+    pub fn remove_interceptor(&self, name: &str) -> Result<(), FacetError> {
+        let mut interceptors = self.interceptors.write();
+        let index = interceptor_position(&interceptors, name)?;
+        interceptors.remove(index);
+        Ok(())
+    }
+
+    // Put the interceptors in the given order; every one must be named
+    pub fn reorder_interceptors(&self, names: &[&str]) -> Result<(), FacetError> {
+        let mut interceptors = self.interceptors.write();
+        if names.len() != interceptors.len() {
+            return Err(FacetError::Invalid(format!("Expected {} interceptor names, got {}", interceptors.len(), names.len())));
+        }
+
+        let mut reordered = Vec::with_capacity(interceptors.len());
+        for name in names {
+            let index = interceptor_position(&interceptors, name)?;
+            reordered.push(interceptors.remove(index));
+        }
+        *interceptors = reordered;
+        Ok(())
+    }
+
+    pub fn interceptor_names(&self) -> Vec<String> {
+        self.interceptors.read().iter().map(|interceptor| interceptor.name().to_string()).collect()
+    }
+}
+
+fn interceptor_position(interceptors: &Interceptors, name: &str) -> Result<usize, FacetError> {
+    interceptors.iter()
+        .position(|interceptor| interceptor.name() == name)
+        .ok_or_else(|| FacetError::Invalid(format!("Interceptor '{}' not registered", name)))
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{AccountFacet, Employee, Money, PermissionFacet};
+
+    // Records hook calls so ordering can be checked
+    struct Trace {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FacetInterceptor for Trace {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn before(&self, _object: &FacetedObject, access: &FacetAccess<'_>) -> Result<(), FacetError> {
+            self.calls.lock().unwrap().push(format!("{} before mutable={}", self.name, access.mutable));
+            Ok(())
+        }
+
+        fn after(&self, _object: &FacetedObject, _access: &FacetAccess<'_>, outcome: Result<(), &FacetError>) {
+            self.calls.lock().unwrap().push(format!("{} after ok={}", self.name, outcome.is_ok()));
+        }
+    }
+
+    // Only lets accounts be written by objects with a PermissionFacet
+    struct RequirePermissions;
+
+    impl FacetInterceptor for RequirePermissions {
+        fn name(&self) -> &str {
+            "require_permissions"
+        }
+
+        fn before(&self, object: &FacetedObject, access: &FacetAccess<'_>) -> Result<(), FacetError> {
+            if access.mutable && access.is::<AccountFacet>() && !object.has_facet::<PermissionFacet>() {
+                return Err(FacetError::PermissionDenied {
+                    operation: "account_write".to_string(),
+                    permission: "financial_operations".to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_interceptors_wrap_access_in_order() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        employee.add_interceptor(Trace { name: "outer", calls: Arc::clone(&calls) }).unwrap();
+        employee.insert_interceptor_before("outer", Trace { name: "first", calls: Arc::clone(&calls) }).unwrap();
+        employee.reorder_interceptors(&["outer", "first"]).unwrap();
+        assert_eq!(employee.interceptor_names(), ["outer", "first"]);
+
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+        assert!(employee.with_facet::<PermissionFacet, _>(|_| ()).is_err());
+        assert_eq!(*calls.lock().unwrap(), [
+            "outer before mutable=true", "first before mutable=true", "first after ok=true", "outer after ok=true",
+            "outer before mutable=false", "first before mutable=false", "first after ok=false", "outer after ok=false",
+        ]);
+
+        employee.remove_interceptor("first").unwrap();
+        assert!(employee.remove_interceptor("first").is_err());
+        assert_eq!(employee.interceptor_names(), ["outer"]);
+    }
+
+    #[test]
+    fn test_interceptor_can_reject_access() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.add_interceptor(RequirePermissions).unwrap();
+
+        let denied = employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10)));
+        assert!(matches!(denied, Err(FacetError::PermissionDenied { .. })));
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(0));
+
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(10));
+    }
+}
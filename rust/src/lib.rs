@@ -0,0 +1,166 @@
+// Dynamic facets: runtime composition of behavior by attaching facets to a
+// core object. The core model (Facet, FacetedObject) only needs `alloc`;
+// everything else requires the default `std` feature. The example facets
+// and the Employee domain sit behind the `builtin-facets` and `examples`
+// features so minimal users only compile the composition engine.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+// Lets #[derive(Facet)] refer to ::dynamic_entities inside this crate too
+extern crate self as dynamic_entities;
+
+#[macro_use]
+mod accessors;
+#[cfg(feature = "std")]
+pub mod admission;
+#[cfg(feature = "actor")]
+pub mod actor;
+#[cfg(feature = "async")]
+pub mod async_access;
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod cast;
+pub mod checkpoint;
+pub mod clock;
+pub mod clone;
+#[cfg(feature = "std")]
+pub mod command;
+pub mod core;
+#[cfg(feature = "std")]
+pub mod correlation;
+pub mod derived;
+#[cfg(feature = "std")]
+pub mod diff;
+pub mod error;
+pub mod event;
+pub mod exchange;
+#[cfg(feature = "examples")]
+pub mod employee;
+#[cfg(feature = "builtin-facets")]
+pub mod facets;
+#[cfg(feature = "ffi")]
+pub mod facets_ffi;
+#[cfg(feature = "std")]
+pub mod gc;
+#[cfg(feature = "std")]
+pub mod guard;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod interceptor;
+pub mod locks;
+pub mod metadata;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod money;
+pub mod observe;
+#[cfg(feature = "examples")]
+pub mod operations;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod pipeline;
+#[cfg(feature = "std")]
+pub mod recording;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "replication")]
+pub mod replication;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod reflect;
+pub mod report;
+#[cfg(feature = "builtin-facets")]
+pub mod runner;
+#[cfg(feature = "std")]
+pub mod snapshot;
+pub mod shared;
+pub mod summary;
+mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tracing")]
+pub mod trace;
+pub mod transaction;
+pub mod ttl;
+pub mod typed;
+#[cfg(feature = "validation")]
+pub mod validation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod world;
+
+#[cfg(feature = "std")]
+pub use crate::admission::WriteLimits;
+pub use crate::builder::{FacetPreset, FacetedObjectBuilder};
+#[cfg(feature = "std")]
+pub use crate::cache::CacheFacet;
+pub use crate::cast::TraitCaster;
+pub use crate::checkpoint::{Checkpoint, SnapshotFacet};
+pub use crate::clock::{Clock, ManualClock, Timestamp};
+pub use crate::clone::CloneFacet;
+#[cfg(feature = "std")]
+pub use crate::correlation::CorrelationScope;
+#[cfg(feature = "std")]
+pub use crate::clock::SystemClock;
+pub use crate::core::{
+    CoreRef, Facet, FacetContext, DEFAULT_INSTANCE, FacetRef, FacetRefMut, FacetVisitor, FacetedObject,
+};
+#[cfg(feature = "derive")]
+pub use dynamic_entities_derive::Facet;
+pub use crate::derived::{Derived, DerivedFacet};
+#[cfg(feature = "std")]
+pub use crate::diff::{FacetChanges, FacetDiff, FieldChange};
+pub use crate::error::FacetError;
+#[cfg(feature = "std")]
+pub use crate::guard::{AccessPolicy, Capability};
+#[cfg(feature = "builtin-facets")]
+pub use crate::guard::PermissionPolicy;
+pub use crate::event::FacetEvent;
+#[cfg(feature = "std")]
+pub use crate::event::DomainEvent;
+pub use crate::locks::{FacetLockRequest, FacetLocks, LockedFacets};
+pub use crate::exchange::{ExchangeRate, ExchangeRateProvider, StaticRates};
+pub use crate::interceptor::{FacetAccess, FacetInterceptor};
+pub use crate::metadata::{FacetDescription, FacetMetadata};
+#[cfg(feature = "metrics")]
+pub use crate::metrics::FacetMetrics;
+pub use crate::money::{Currency, Money};
+pub use crate::observe::Subscription;
+pub use crate::reflect::{FieldInfo, FieldKind, FieldValue, ReflectFacet, ReflectedFacet};
+pub use crate::report::{
+    HtmlFormatter, MarkdownFormatter, PlainTextFormatter, Report, ReportFormatter, ReportRenderer,
+};
+pub use crate::shared::{SharedFacetedObject, WeakFacetedObject};
+pub use crate::summary::{FacetSummary, Summarizable};
+#[cfg(feature = "tracing")]
+pub use crate::trace::LoggingInterceptor;
+pub use crate::transaction::{Transaction, TransactionalFacet};
+pub use crate::ttl::ExpiredFacet;
+pub use crate::typed::Faceted;
+#[cfg(feature = "validation")]
+pub use crate::validation::{RuleSet, Target, ValidationFacet, ValidationReport, Violation};
+pub use crate::world::{EntityId, FacetQuery, FacetWorld};
+
+#[cfg(feature = "std")]
+pub use crate::command::{CommandBus, CommandSpec, ParamType, Params};
+#[cfg(feature = "examples")]
+pub use crate::employee::Employee;
+#[cfg(feature = "builtin-facets")]
+pub use crate::facets::{
+    AccountFacet, AccountPolicy, AuditEntry, AuditFacet, AuditInterceptor, Auditable, Authorizer, BalanceChanged, BuiltinFacetAccess,
+    ForeignCurrency, LedgerEntry, NotificationFacet, PermissionFacet, PermissionGranted, RateLimitInterceptor, RateLimiterFacet,
+    StandardPolicy, StateChange, StateMachineFacet, Statement,
+};
+#[cfg(feature = "examples")]
+pub use crate::operations::{EmployeeOperations, FinancialEmployee, FinancialOperation};
+#[cfg(feature = "std")]
+pub use crate::pipeline::{OperationContext, Pipeline, Stage};
+#[cfg(feature = "std")]
+pub use crate::registry::{FacetRegistry, ObjectRegistry};
+#[cfg(feature = "builtin-facets")]
+pub use crate::runner::{FacetOperation, OperationRunner};
+#[cfg(feature = "std")]
+pub use crate::snapshot::{FacetMigration, FacetedSnapshot, SerializableFacet, SerializedFacet};
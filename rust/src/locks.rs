@@ -0,0 +1,294 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::vec::Vec;
+use core::any::{type_name, TypeId};
+use core::marker::PhantomData;
+
+use crate::core::{downcast_mut, downcast_ref, Facet, FacetRef, FacetRefMut, FacetSlot, FacetedObject, Permit, DEFAULT_INSTANCE};
+use crate::error::FacetError;
+use crate::interceptor::{FacetAccess, Interception};
+use crate::sync::{FacetReadGuard, FacetWriteGuard};
+
+// One facet FacetedObject::lock_facets should lock, shared or exclusive
+#[derive(Debug, Clone, Copy)]
+pub struct FacetLockRequest {
+    pub(crate) type_id: TypeId,
+    pub(crate) type_name: &'static str,
+    mutable: bool,
+}
+
+impl FacetLockRequest {
+    pub fn read<F: Facet + 'static>() -> Self {
+        Self { type_id: TypeId::of::<F>(), type_name: type_name::<F>(), mutable: false }
+    }
+
+    pub fn write<F: Facet + 'static>() -> Self {
+        Self { type_id: TypeId::of::<F>(), type_name: type_name::<F>(), mutable: true }
+    }
+}
+
+enum Locked {
+    Read(FacetReadGuard<FacetSlot>),
+    Write(FacetWriteGuard<FacetSlot>),
+}
+
+// Facets locked together by FacetedObject::lock_facets. Take the guards
+// with next_ref and next_mut, in the order the facets were requested, or
+// look them up by type through by_type. The interceptors' `after` hooks run
+// when it is dropped, so drop the guards taken from it first.
+pub struct FacetLocks<'a> {
+    object: &'a FacetedObject,
+    locked: VecDeque<(TypeId, Locked)>,
+    // Handed to the first write guard taken
+    permit: Permit<'a>,
+    interceptions: Vec<Interception<'a>>,
+}
+
+impl<'a> FacetLocks<'a> {
+    pub fn next_ref<F: Facet + 'static>(&mut self) -> Result<FacetRef<'a, F>, FacetError> {
+        match self.locked.pop_front() {
+            Some((_, Locked::Read(slot))) => {
+                downcast_ref::<F>(&slot)?;
+                Ok(FacetRef { slot, _facet: PhantomData })
+            }
+            _ => Err(FacetError::DowncastFailed { type_name: type_name::<F>() }),
+        }
+    }
+
+    pub fn next_mut<F: Facet + 'static>(&mut self) -> Result<FacetRefMut<'a, F>, FacetError> {
+        match self.locked.pop_front() {
+            Some((_, Locked::Write(mut slot))) => {
+                downcast_mut::<F>(&mut slot)?;
+                Ok(FacetRefMut { slot: Some(slot), object: self.object, _permit: core::mem::take(&mut self.permit), _facet: PhantomData })
+            }
+            _ => Err(FacetError::DowncastFailed { type_name: type_name::<F>() }),
+        }
+    }
+
+    // The facets not yet taken, looked up by type
+    pub fn by_type(mut self) -> LockedFacets<'a> {
+        LockedFacets {
+            object: self.object,
+            locked: core::mem::take(&mut self.locked).into(),
+            _permit: core::mem::take(&mut self.permit),
+            interceptions: core::mem::take(&mut self.interceptions),
+        }
+    }
+}
+
+impl Drop for FacetLocks<'_> {
+    fn drop(&mut self) {
+        self.locked.clear();
+        finish_all(core::mem::take(&mut self.interceptions), Ok(()));
+    }
+}
+
+// Facets locked together, looked up by type. Mutation observers of the
+// mutably locked facets, then the interceptors' `after` hooks, run when it
+// is dropped.
+pub struct LockedFacets<'a> {
+    object: &'a FacetedObject,
+    locked: Vec<(TypeId, Locked)>,
+    _permit: Permit<'a>,
+    interceptions: Vec<Interception<'a>>,
+}
+
+impl LockedFacets<'_> {
+    pub fn get<F: Facet + 'static>(&self) -> Result<&F, FacetError> {
+        match self.locked.iter().find(|(type_id, _)| *type_id == TypeId::of::<F>()) {
+            Some((_, Locked::Read(slot))) => downcast_ref::<F>(slot),
+            Some((_, Locked::Write(slot))) => downcast_ref::<F>(slot),
+            None => Err(FacetError::NotLocked { facet: type_name::<F>(), write: false }),
+        }
+    }
+
+    pub fn get_mut<F: Facet + 'static>(&mut self) -> Result<&mut F, FacetError> {
+        match self.locked.iter_mut().find(|(type_id, _)| *type_id == TypeId::of::<F>()) {
+            Some((_, Locked::Write(slot))) => downcast_mut::<F>(slot),
+            Some((_, Locked::Read(_))) | None => Err(FacetError::NotLocked { facet: type_name::<F>(), write: true }),
+        }
+    }
+}
+
+impl Drop for LockedFacets<'_> {
+    fn drop(&mut self) {
+        let written: Vec<TypeId> = self.locked.iter()
+            .filter(|(_, locked)| matches!(locked, Locked::Write(_)))
+            .map(|(type_id, _)| *type_id)
+            .collect();
+        self.locked.clear();
+        for type_id in written {
+            self.object.notify_mutation(type_id);
+        }
+        finish_all(core::mem::take(&mut self.interceptions), Ok(()));
+    }
+}
+
+// Run the `after` hooks of several accesses, the last one started first
+fn finish_all(interceptions: Vec<Interception<'_>>, result: Result<(), &FacetError>) {
+    for interception in interceptions.into_iter().rev() {
+        let _ = interception.finish(result.map_err(FacetError::clone));
+    }
+}
+
+impl FacetedObject {
+    // Lock the default instances of several facets at once. Locks are
+    // always taken in the same (TypeId) order whatever the request order,
+    // so two callers locking overlapping sets cannot deadlock each other.
+    // Each facet counts as one access for the interceptors and guards, as
+    // with with_facet / with_facet_mut. Usually called through with_facets!.
+    pub fn lock_facets(&self, requests: &[FacetLockRequest]) -> Result<FacetLocks<'_>, FacetError> {
+        let mut order: Vec<usize> = (0..requests.len()).collect();
+        order.sort_by_key(|&index| requests[index].type_id);
+        if let Some(pair) = order.windows(2).find(|pair| requests[pair[0]].type_id == requests[pair[1]].type_id) {
+            return Err(FacetError::Invalid(format!("{} requested more than once", requests[pair[0]].type_name)));
+        }
+
+        // Interceptors run before any facet is locked, since their hooks
+        // may access the same facets
+        let mut interceptions = Vec::with_capacity(requests.len());
+        let mut cells = Vec::with_capacity(requests.len());
+        let prepared = requests.iter()
+            .try_for_each(|request| {
+                interceptions.push(self.intercept(FacetAccess {
+                    type_id: request.type_id,
+                    type_name: request.type_name,
+                    instance: DEFAULT_INSTANCE,
+                    mutable: request.mutable,
+                })?);
+                cells.push(self.cell_of(request.type_id, request.type_name, DEFAULT_INSTANCE, request.mutable)?);
+                Ok(())
+            })
+            .and_then(|()| if requests.iter().any(|request| request.mutable) { self.admit_write() } else { Ok(Permit::default()) });
+        let permit = match prepared {
+            Ok(permit) => permit,
+            Err(e) => {
+                finish_all(interceptions, Err(&e));
+                return Err(e);
+            }
+        };
+
+        let mut locked: Vec<Option<(TypeId, Locked)>> = requests.iter().map(|_| None).collect();
+        for index in order {
+            locked[index] = Some((requests[index].type_id, if requests[index].mutable {
+                Locked::Write(cells[index].write_arc())
+            } else {
+                Locked::Read(cells[index].read_arc())
+            }));
+        }
+        Ok(FacetLocks { object: self, locked: locked.into_iter().flatten().collect(), permit, interceptions })
+    }
+}
+
+// Run a block with several facets of an object locked at once, so no other
+// accessor can interleave between them. Evaluates to Ok with the block's
+// value, or to the error locking failed with, e.g. a missing facet.
+//
+//     with_facets!(employee, (permissions: &PermissionFacet, account: &mut AccountFacet) => {
+//         if permissions.has_permission("financial_operations") {
+//             account.deposit(amount)
+//         } else { ... }
+//     })
+//
+// Mutation observers of the mutably locked facets run after the block.
+#[macro_export]
+macro_rules! with_facets {
+    ($object:expr, ($($bindings:tt)+) => $body:expr) => {
+        $crate::with_facets!(@collect $object, [] [$($bindings)+] $body)
+    };
+    (@collect $object:expr, [$($done:tt)*] [$name:ident : &mut $facet:ty $(, $($rest:tt)*)?] $body:expr) => {
+        $crate::with_facets!(@collect $object, [$($done)* ($name, $facet, write, next_mut, mut)] [$($($rest)*)?] $body)
+    };
+    (@collect $object:expr, [$($done:tt)*] [$name:ident : & $facet:ty $(, $($rest:tt)*)?] $body:expr) => {
+        $crate::with_facets!(@collect $object, [$($done)* ($name, $facet, read, next_ref, )] [$($($rest)*)?] $body)
+    };
+    (@collect $object:expr, [$(($name:ident, $facet:ty, $request:ident, $next:ident, $($mutability:tt)?))+] [] $body:expr) => {
+        (|| -> ::core::result::Result<_, $crate::error::FacetError> {
+            let mut locks = $object.lock_facets(&[$($crate::locks::FacetLockRequest::$request::<$facet>()),+])?;
+            $(
+                #[allow(unused_mut)]
+                let mut $name = locks.$next::<$facet>()?;
+                let $name: &$($mutability)? $facet = &$($mutability)? *$name;
+            )+
+            ::core::result::Result::Ok($body)
+        })()
+    };
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::locks::FacetLockRequest;
+    use crate::{AccountFacet, AuditFacet, Employee, FacetError, FacetedObject, Money, PermissionFacet, RateLimitInterceptor, RateLimiterFacet};
+
+    fn employee() -> FacetedObject {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee
+    }
+
+    #[test]
+    fn test_with_facets_locks_together() {
+        let employee = employee();
+        let balance = with_facets!(employee, (permissions: &PermissionFacet, account: &mut AccountFacet, audit: &mut AuditFacet) => {
+            assert!(permissions.has_permission("financial_operations"));
+            let balance = account.deposit(Money::usd(100)).unwrap();
+            audit.log_operation("deposit", &format!("New balance: {}", balance));
+            balance
+        });
+        assert_eq!(balance, Ok(Money::usd(100)));
+        assert_eq!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 1);
+
+        employee.detach_facet::<AuditFacet>().unwrap();
+        let missing = with_facets!(employee, (account: &AccountFacet, audit: &AuditFacet) => (account.get_balance(), audit.get_audit_trail().len()));
+        assert!(matches!(missing, Err(FacetError::NotFound { .. })));
+        assert!(with_facets!(employee, (first: &AccountFacet, second: &mut AccountFacet) => (first.get_balance(), second.get_balance())).is_err());
+    }
+
+    #[test]
+    fn test_locking_is_intercepted_and_typed() {
+        let employee = employee();
+        employee.attach_facet(RateLimiterFacet::per_minute(1)).unwrap();
+        employee.add_interceptor(RateLimitInterceptor::new().limit::<AccountFacet>()).unwrap();
+
+        let mut locked = employee.lock_facets(&[FacetLockRequest::read::<AccountFacet>()]).unwrap().by_type();
+        assert_eq!(locked.get_mut::<AccountFacet>().err(), Some(FacetError::NotLocked { facet: core::any::type_name::<AccountFacet>(), write: true }));
+        assert_eq!(locked.get::<AuditFacet>().err(), Some(FacetError::NotLocked { facet: core::any::type_name::<AuditFacet>(), write: false }));
+        drop(locked);
+
+        with_facets!(employee, (account: &mut AccountFacet) => account.deposit(Money::usd(1))).unwrap().unwrap();
+        let limited = with_facets!(employee, (account: &mut AccountFacet) => account.deposit(Money::usd(1)));
+        assert!(matches!(limited, Err(FacetError::RateLimited { .. })));
+    }
+
+    #[test]
+    fn test_opposite_orders_do_not_deadlock() {
+        let employee = Arc::new(employee());
+        let workers: Vec<_> = (0..4).map(|i| {
+            let employee = Arc::clone(&employee);
+            thread::spawn(move || {
+                for _ in 0..200 {
+                    if i % 2 == 0 {
+                        with_facets!(employee, (account: &mut AccountFacet, audit: &mut AuditFacet) => {
+                            account.deposit(Money::usd(1)).unwrap();
+                            audit.log_operation("deposit", "1.00 USD");
+                        }).unwrap();
+                    } else {
+                        with_facets!(employee, (audit: &mut AuditFacet, account: &mut AccountFacet) => {
+                            account.deposit(Money::usd(1)).unwrap();
+                            audit.log_operation("deposit", "1.00 USD");
+                        }).unwrap();
+                    }
+                }
+            })
+        }).collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(800));
+    }
+}
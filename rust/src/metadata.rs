@@ -0,0 +1,160 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+use serde::Serialize;
+
+use crate::clock::Timestamp;
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+
+// What a facet says about itself, for tooling and messages that should
+// show "account" rather than a type path or TypeId
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacetMetadata {
+    pub name: &'static str,
+    pub type_name: &'static str,
+    pub description: &'static str,
+    // Schema version of the serialized layout
+    pub version: u32,
+    // Capabilities: "summarize", "reflect" and "serialize" for the
+    // optional interfaces the facet provides, plus tags of its own
+    pub tags: Vec<&'static str>,
+}
+
+impl FacetMetadata {
+    // Metadata derived from the facet's Facet implementation, without a
+    // description or tags of its own
+    pub fn of<F: Facet + ?Sized>(facet: &F) -> Self {
+        let mut tags = Vec::new();
+        if facet.as_summarizable().is_some() {
+            tags.push("summarize");
+        }
+        if facet.as_reflect().is_some() {
+            tags.push("reflect");
+        }
+        #[cfg(feature = "std")]
+        if facet.as_serializable().is_some() {
+            tags.push("serialize");
+        }
+        Self {
+            name: facet.facet_name(),
+            type_name: facet.facet_type_name(),
+            description: "",
+            #[cfg(feature = "std")]
+            version: facet.schema_version(),
+            #[cfg(not(feature = "std"))]
+            version: 1,
+            tags,
+        }
+    }
+
+    pub fn description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    pub fn tags(mut self, tags: &[&'static str]) -> Self {
+        self.tags.extend_from_slice(tags);
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+// One attached facet instance as reported by FacetedObject::describe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacetDescription {
+    pub instance: String,
+    pub metadata: FacetMetadata,
+    // The earlier of the instance's TTL and the facet's own expiry
+    pub expires_at: Option<Timestamp>,
+    // Names of the attached facets this one depends on
+    pub depends_on: Vec<&'static str>,
+}
+
+impl FacetedObject {
+    // Every attached facet instance, in attach order, e.g. for debugging
+    // or monitoring endpoints. Expired instances are left out.
+    pub fn describe(&self) -> Result<Vec<FacetDescription>, FacetError> {
+        let mut described: Vec<(TypeId, FacetDescription, Vec<TypeId>)> = Vec::new();
+        for (type_id, instance, cell) in self.cells_in_order() {
+            let ttl = self.instance_expiry(type_id, &instance);
+            let slot = cell.read();
+            let Some(facet) = slot.as_deref() else { continue };
+            let expires_at = match (ttl, facet.expires_at()) {
+                (Some(ttl), Some(own)) => Some(ttl.min(own)),
+                (ttl, own) => ttl.or(own),
+            };
+            let description = FacetDescription { instance, metadata: facet.metadata(), expires_at, depends_on: Vec::new() };
+            described.push((type_id, description, facet.dependencies()));
+        }
+
+        let names: Vec<(TypeId, &'static str)> = described.iter()
+            .map(|(type_id, description, _)| (*type_id, description.metadata.name))
+            .collect();
+        Ok(described.into_iter()
+            .map(|(_, mut description, dependencies)| {
+                description.depends_on = dependencies.iter()
+                    .filter_map(|dependency| names.iter().find(|(type_id, _)| type_id == dependency).map(|(_, name)| *name))
+                    .collect();
+                description
+            })
+            .collect())
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, PermissionFacet};
+    use core::any::Any;
+
+    struct Ledger;
+
+    impl Facet for Ledger {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn dependencies(&self) -> Vec<TypeId> {
+            alloc::vec![TypeId::of::<AccountFacet>()]
+        }
+    }
+
+    #[test]
+    fn test_builtin_metadata() {
+        let account = AccountFacet::new("ACC001").metadata();
+        assert_eq!(account.name, "account");
+        assert_eq!(account.version, 3);
+        assert!(account.has_tag("summarize") && account.has_tag("serialize") && account.has_tag("financial"));
+        assert!(!account.description.is_empty());
+
+        let ledger = Ledger.metadata();
+        assert_eq!(ledger.name, core::any::type_name::<Ledger>());
+        assert_eq!((ledger.description, ledger.version, ledger.tags), ("", 1, Vec::new()));
+    }
+
+    #[test]
+    fn test_describe_attached_facets() {
+        let object = FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with(AccountFacet::new("ACC001"))
+            .with(PermissionFacet::new("manager"))
+            .with(Ledger)
+            .with_named("backup", AuditFacet::new())
+            .build()
+            .unwrap();
+
+        let described = object.describe().unwrap();
+        let names: Vec<(&str, &str)> = described.iter().map(|facet| (facet.instance.as_str(), facet.metadata.name)).collect();
+        assert_eq!(names, [("default", "account"), ("default", "permissions"), ("default", names[2].1), ("backup", "audit")]);
+        assert_eq!(described[2].depends_on, ["account"]);
+        assert!(described.iter().all(|facet| facet.expires_at.is_none()));
+    }
+}
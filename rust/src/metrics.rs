@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use prometheus::{exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+use crate::error::FacetError;
+
+// Prometheus metrics for facet operations of the objects it is set on
+// with FacetedObject::with_metrics, labelled by facet type (short name)
+// and operation:
+//
+//   facet_operations_total              attaches, detaches and accesses
+//   facet_operation_errors_total        those that failed
+//   facet_operation_duration_seconds    time taken, including lock waits
+//   facet_lock_wait_seconds             time spent waiting for the facet's
+//                                       lock, by access "read" or "write"
+//
+// High lock waits against short operations point at contention on one
+// facet. One FacetMetrics is usually shared by every object.
+pub struct FacetMetrics {
+    registry: Registry,
+    operations: IntCounterVec,
+    errors: IntCounterVec,
+    duration: HistogramVec,
+    lock_wait: HistogramVec,
+}
+
+fn metrics_error(e: prometheus::Error) -> FacetError {
+    FacetError::Other(format!("Metrics error: {}", e))
+}
+
+fn short_name(type_name: &'static str) -> &'static str {
+    type_name.rsplit("::").next().unwrap_or(type_name)
+}
+
+impl FacetMetrics {
+    // Metrics in a registry of their own
+    pub fn new() -> Result<Self, FacetError> {
+        Self::register(Registry::new())
+    }
+
+    // Metrics added to `registry`, e.g. one shared with the rest of the
+    // application
+    pub fn register(registry: Registry) -> Result<Self, FacetError> {
+        // 1µs to ~4s
+        let buckets = exponential_buckets(1e-6, 4.0, 12).map_err(metrics_error)?;
+        let operations = IntCounterVec::new(
+            Opts::new("facet_operations_total", "Facet operations performed"),
+            &["facet", "operation"],
+        ).map_err(metrics_error)?;
+        let errors = IntCounterVec::new(
+            Opts::new("facet_operation_errors_total", "Facet operations that failed"),
+            &["facet", "operation"],
+        ).map_err(metrics_error)?;
+        let duration = HistogramVec::new(
+            HistogramOpts::new("facet_operation_duration_seconds", "Time taken by facet operations").buckets(buckets.clone()),
+            &["facet", "operation"],
+        ).map_err(metrics_error)?;
+        let lock_wait = HistogramVec::new(
+            HistogramOpts::new("facet_lock_wait_seconds", "Time spent waiting for facet locks").buckets(buckets),
+            &["facet", "access"],
+        ).map_err(metrics_error)?;
+
+        registry.register(Box::new(operations.clone())).map_err(metrics_error)?;
+        registry.register(Box::new(errors.clone())).map_err(metrics_error)?;
+        registry.register(Box::new(duration.clone())).map_err(metrics_error)?;
+        registry.register(Box::new(lock_wait.clone())).map_err(metrics_error)?;
+        Ok(Self { registry, operations, errors, duration, lock_wait })
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    // Every metric in the registry in the Prometheus text format, e.g. for
+    // a /metrics endpoint
+    pub fn encode(&self) -> Result<String, FacetError> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer).map_err(metrics_error)?;
+        String::from_utf8(buffer).map_err(|e| FacetError::Other(e.to_string()))
+    }
+
+    pub fn operation_count(&self, facet: &str, operation: &str) -> u64 {
+        self.operations.with_label_values(&[facet, operation]).get()
+    }
+
+    pub fn error_count(&self, facet: &str, operation: &str) -> u64 {
+        self.errors.with_label_values(&[facet, operation]).get()
+    }
+
+    // Total time spent waiting for the locks of `facet` by `access`
+    pub fn lock_wait(&self, facet: &str, access: &str) -> Duration {
+        Duration::from_secs_f64(self.lock_wait.with_label_values(&[facet, access]).get_sample_sum())
+    }
+
+    pub(crate) fn observe_operation(&self, operation: &str, facet: &'static str, elapsed: Duration, ok: bool) {
+        let labels = [short_name(facet), operation];
+        self.operations.with_label_values(&labels).inc();
+        if !ok {
+            self.errors.with_label_values(&labels).inc();
+        }
+        self.duration.with_label_values(&labels).observe(elapsed.as_secs_f64());
+    }
+
+    pub(crate) fn observe_lock_wait(&self, facet: &'static str, access: &str, waited: Duration) {
+        self.lock_wait.with_label_values(&[short_name(facet), access]).observe(waited.as_secs_f64());
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use super::*;
+    use crate::{AccountFacet, FacetedObject, Money};
+
+    #[test]
+    fn test_counts_errors_and_encoding() {
+        let metrics = Arc::new(FacetMetrics::new().unwrap());
+        let object = FacetedObject::new(()).with_metrics(Arc::clone(&metrics));
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+        object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+        assert!(object.attach_facet(AccountFacet::new("ACC002")).is_err());
+
+        assert_eq!(metrics.operation_count("AccountFacet", "attach"), 2);
+        assert_eq!(metrics.error_count("AccountFacet", "attach"), 1);
+        assert_eq!(metrics.operation_count("AccountFacet", "with_facet_mut"), 1);
+        let text = metrics.encode().unwrap();
+        assert!(text.contains("facet_operations_total{facet=\"AccountFacet\",operation=\"with_facet\"} 1"));
+        assert!(text.contains("facet_lock_wait_seconds_count{access=\"write\",facet=\"AccountFacet\"} 1"));
+    }
+
+    #[test]
+    fn test_lock_wait_shows_contention() {
+        let metrics = Arc::new(FacetMetrics::new().unwrap());
+        let object = Arc::new(FacetedObject::new(()).with_metrics(Arc::clone(&metrics)));
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let locked = Arc::new(Barrier::new(2));
+        let writer = {
+            let (object, locked) = (Arc::clone(&object), Arc::clone(&locked));
+            thread::spawn(move || {
+                object.with_facet_mut::<AccountFacet, _>(|_| {
+                    locked.wait();
+                    thread::sleep(Duration::from_millis(50));
+                }).unwrap();
+            })
+        };
+        locked.wait();
+        object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+        writer.join().unwrap();
+
+        assert!(metrics.lock_wait("AccountFacet", "read") >= Duration::from_millis(30));
+        assert!(metrics.lock_wait("AccountFacet", "write") < Duration::from_millis(30));
+    }
+}
@@ -0,0 +1,260 @@
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::FacetError;
+
+// ISO 4217 currency code, e.g. "USD"
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+    pub const GBP: Currency = Currency(*b"GBP");
+    pub const JPY: Currency = Currency(*b"JPY");
+
+    // Three uppercase ASCII letters
+    pub fn new(code: &str) -> Result<Self, FacetError> {
+        match code.as_bytes() {
+            &[a, b, c] if [a, b, c].iter().all(u8::is_ascii_uppercase) => Ok(Currency([a, b, c])),
+            _ => Err(FacetError::Invalid(alloc::format!("Invalid currency code '{}'", code))),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        // Only ever built from ASCII letters
+        str::from_utf8(&self.0).unwrap_or("???")
+    }
+
+    // Digits after the decimal point, i.e. how many minor units make one
+    // major unit (100 cents per dollar, no subdivision of the yen)
+    pub fn minor_units(&self) -> u32 {
+        match &self.0 {
+            b"JPY" | b"KRW" | b"ISK" | b"CLP" | b"VND" => 0,
+            b"BHD" | b"KWD" | b"OMR" | b"JOD" | b"TND" => 3,
+            _ => 2,
+        }
+    }
+
+    fn scale(&self) -> i64 {
+        10_i64.pow(self.minor_units())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+// Serialized as the ISO 4217 code
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Currency {
+    fn schema_name() -> alloc::borrow::Cow<'static, str> {
+        "Currency".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({ "type": "string", "pattern": "^[A-Z]{3}$" })
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Currency::new(&code).map_err(D::Error::custom)
+    }
+}
+
+// Fixed-point amount: a whole number of the currency's minor units (cents
+// for USD), so sums are exact. Arithmetic is checked and fails with
+// FacetError::Overflow or FacetError::CurrencyMismatch instead of wrapping
+// or mixing currencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub const fn from_minor(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+
+    pub fn from_major(major: i64, currency: Currency) -> Result<Self, FacetError> {
+        major.checked_mul(currency.scale())
+            .map(|minor| Self::from_minor(minor, currency))
+            .ok_or(FacetError::Overflow)
+    }
+
+    // Whole dollars, mostly for tests and examples. Panics if the amount
+    // does not fit; use from_major for untrusted input.
+    pub fn usd(dollars: i64) -> Self {
+        Self::from_major(dollars, Currency::USD).expect("dollar amount out of range")
+    }
+
+    pub const fn zero(currency: Currency) -> Self {
+        Self::from_minor(0, currency)
+    }
+
+    // Convert a floating-point amount from an untyped boundary (JSON,
+    // scripts), rounding to the nearest minor unit
+    #[cfg(feature = "std")]
+    pub fn from_f64(amount: f64, currency: Currency) -> Result<Self, FacetError> {
+        if !amount.is_finite() {
+            return Err(FacetError::Invalid(alloc::format!("Amount {} is not a number", amount)));
+        }
+        let minor = (amount * currency.scale() as f64).round();
+        if minor < i64::MIN as f64 || minor >= i64::MAX as f64 {
+            return Err(FacetError::Overflow);
+        }
+        Ok(Self::from_minor(minor as i64, currency))
+    }
+
+    // Nearest floating-point value in major units, for display and for
+    // boundaries that only understand numbers
+    pub fn to_f64(&self) -> f64 {
+        self.minor as f64 / self.currency.scale() as f64
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor == 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor > 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.minor < 0
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, FacetError> {
+        self.same_currency(&other)?;
+        self.minor.checked_add(other.minor)
+            .map(|minor| Self::from_minor(minor, self.currency))
+            .ok_or(FacetError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, FacetError> {
+        self.same_currency(&other)?;
+        self.minor.checked_sub(other.minor)
+            .map(|minor| Self::from_minor(minor, self.currency))
+            .ok_or(FacetError::Overflow)
+    }
+
+    pub fn checked_neg(self) -> Result<Money, FacetError> {
+        self.minor.checked_neg()
+            .map(|minor| Self::from_minor(minor, self.currency))
+            .ok_or(FacetError::Overflow)
+    }
+
+    // The amount without its currency code, e.g. "-12.50"
+    pub fn decimal(&self) -> impl fmt::Display + '_ {
+        Decimal(self)
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), FacetError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(FacetError::CurrencyMismatch { expected: self.currency, found: other.currency })
+        }
+    }
+}
+
+// Amounts in different currencies are unordered
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.minor.cmp(&other.minor))
+    }
+}
+
+// "1234.50 USD"
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.decimal(), self.currency)
+    }
+}
+
+struct Decimal<'a>(&'a Money);
+
+impl fmt::Display for Decimal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Money { minor, currency } = *self.0;
+        let sign = if minor < 0 { "-" } else { "" };
+        let units = currency.minor_units();
+        let scale = currency.scale().unsigned_abs();
+        let minor = minor.unsigned_abs();
+        if units == 0 {
+            write!(f, "{}{}", sign, minor)
+        } else {
+            write!(f, "{}{}.{:0width$}", sign, minor / scale, minor % scale, width = units as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let balance = Money::usd(10);
+        assert_eq!(balance.checked_add(Money::from_minor(5, Currency::USD)).unwrap().minor(), 1005);
+        assert_eq!(balance.checked_sub(Money::usd(25)).unwrap().to_string(), "-15.00 USD");
+
+        assert_eq!(Money::from_minor(i64::MAX, Currency::USD).checked_add(Money::usd(1)), Err(FacetError::Overflow));
+        assert_eq!(
+            balance.checked_add(Money::from_major(10, Currency::EUR).unwrap()),
+            Err(FacetError::CurrencyMismatch { expected: Currency::USD, found: Currency::EUR }),
+        );
+        assert!(Money::usd(1) < Money::usd(2));
+        assert_eq!(Money::usd(1).partial_cmp(&Money::zero(Currency::JPY)), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_float_boundary_and_serde() {
+        // 0.1 + 0.2 drifts as f64 but not once converted to cents
+        let sum = Money::from_f64(0.1, Currency::USD).unwrap()
+            .checked_add(Money::from_f64(0.2, Currency::USD).unwrap())
+            .unwrap();
+        assert_eq!(sum, Money::from_minor(30, Currency::USD));
+        assert_eq!(Money::from_f64(1500.0, Currency::JPY).unwrap().to_string(), "1500 JPY");
+        assert!(Money::from_f64(f64::NAN, Currency::USD).is_err());
+        assert_eq!(Money::from_f64(1e30, Currency::USD), Err(FacetError::Overflow));
+
+        let json = serde_json::to_value(sum).unwrap();
+        assert_eq!(json, serde_json::json!({ "minor": 30, "currency": "USD" }));
+        assert_eq!(serde_json::from_value::<Money>(json).unwrap(), sum);
+        assert!(serde_json::from_value::<Currency>(serde_json::json!("usd")).is_err());
+    }
+}
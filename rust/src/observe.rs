@@ -0,0 +1,215 @@
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+use crate::sync::RwLock;
+
+// Called after a facet of the observed type was attached, detached or
+// mutably accessed
+pub(crate) type MutationObserver = Arc<dyn Fn(&FacetedObject) + Send + Sync>;
+
+// Copies a facet's state for change observers; the argument is the facet
+// as Any
+pub(crate) type Capture = Arc<dyn Fn(&dyn Any) -> Option<Box<dyn Any + Send + Sync>> + Send + Sync>;
+
+type ChangeObserver = Arc<dyn Fn(&dyn Any, &dyn Any) + Send + Sync>;
+
+// Called after a facet of any type was attached or detached, with the
+// facet's type
+pub(crate) type LayoutObserver = Arc<dyn Fn(&FacetedObject, TypeId) + Send + Sync>;
+
+#[derive(Clone)]
+pub(crate) enum Observer {
+    Mutation(MutationObserver),
+    Change { capture: Capture, notify: ChangeObserver },
+    // Registered under the core's TypeId but run for every facet type
+    Layout(LayoutObserver),
+}
+
+pub(crate) struct Registration {
+    id: u64,
+    type_id: TypeId,
+    observer: Observer,
+}
+
+pub(crate) type Observers = Arc<RwLock<Vec<Registration>>>;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Keeps an observer registered; dropping it unregisters the observer
+#[must_use = "the observer is unregistered when the subscription is dropped"]
+pub struct Subscription {
+    observers: Weak<RwLock<Vec<Registration>>>,
+    id: u64,
+}
+
+impl Subscription {
+    // Leave the observer registered for the lifetime of the object
+    pub fn keep(self) {
+        core::mem::forget(self);
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(observers) = self.observers.upgrade() {
+            observers.write().retain(|registration| registration.id != self.id);
+        }
+    }
+}
+
+impl FacetedObject {
+    // Call `observer` after every attach, detach or mutable access of a
+    // facet of type F, once the facet's lock has been released
+    pub fn observe<F: Facet + 'static>(
+        &self,
+        observer: impl Fn(&FacetedObject) + Send + Sync + 'static,
+    ) -> Result<Subscription, FacetError> {
+        self.register_observer(TypeId::of::<F>(), Observer::Mutation(Arc::new(observer)))
+    }
+
+    // Call `observer` with the state before and after each with_facet_mut
+    // (or with_named_facet_mut) on F. The facet is cloned on both sides of
+    // the mutation while such observers are registered.
+    pub fn observe_changes<F: Facet + Clone + 'static>(
+        &self,
+        observer: impl Fn(&F, &F) + Send + Sync + 'static,
+    ) -> Result<Subscription, FacetError> {
+        let capture: Capture = Arc::new(|facet: &dyn Any| {
+            facet.downcast_ref::<F>().map(|facet| Box::new(facet.clone()) as Box<dyn Any + Send + Sync>)
+        });
+        let notify: ChangeObserver = Arc::new(move |old: &dyn Any, new: &dyn Any| {
+            if let (Some(old), Some(new)) = (old.downcast_ref::<F>(), new.downcast_ref::<F>()) {
+                observer(old, new);
+            }
+        });
+        self.register_observer(TypeId::of::<F>(), Observer::Change { capture, notify })
+    }
+
+    // Observers that stay registered as long as the object lives, e.g. for
+    // derived facets
+    pub(crate) fn add_mutation_observer(&self, type_id: TypeId, observer: MutationObserver) -> Result<(), FacetError> {
+        self.register_observer(type_id, Observer::Mutation(observer)).map(Subscription::keep)
+    }
+
+    // Observe attaches and detaches of every facet type, e.g. to keep an
+    // index of objects by facet
+    pub(crate) fn observe_layout(&self, observer: LayoutObserver) -> Result<Subscription, FacetError> {
+        self.register_observer(TypeId::of::<FacetedObject>(), Observer::Layout(observer))
+    }
+
+    fn register_observer(&self, type_id: TypeId, observer: Observer) -> Result<Subscription, FacetError> {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.observers.write().push(Registration { id, type_id, observer });
+        Ok(Subscription { observers: Arc::downgrade(&self.observers), id })
+    }
+
+    fn observers_of(&self, type_id: TypeId) -> Vec<Observer> {
+        self.observers.read().iter()
+            .filter(|registration| registration.type_id == type_id)
+            .map(|registration| registration.observer.clone())
+            .collect()
+    }
+
+    // State capture for change observers of `type_id`, if there are any
+    pub(crate) fn change_capture(&self, type_id: TypeId) -> Option<Capture> {
+        self.observers_of(type_id).into_iter().find_map(|observer| match observer {
+            Observer::Change { capture, .. } => Some(capture),
+            Observer::Mutation(_) | Observer::Layout(_) => None,
+        })
+    }
+
+    // Run mutation observers of `type_id`; must be called without the facet
+    // lock held
+    pub(crate) fn notify_mutation(&self, type_id: TypeId) {
+        for observer in self.observers_of(type_id) {
+            if let Observer::Mutation(observer) = observer {
+                observer(self);
+            }
+        }
+    }
+
+    // Run mutation and layout observers after a facet of `type_id` was
+    // attached or detached; must be called without facet locks held
+    pub(crate) fn notify_layout(&self, type_id: TypeId) {
+        self.notify_mutation(type_id);
+        for observer in self.observers_of(TypeId::of::<FacetedObject>()) {
+            if let Observer::Layout(observer) = observer {
+                observer(self, type_id);
+            }
+        }
+    }
+
+    // Run change observers of `type_id` with states from change_capture
+    pub(crate) fn notify_change(&self, type_id: TypeId, old: &dyn Any, new: &dyn Any) {
+        for observer in self.observers_of(type_id) {
+            if let Observer::Change { notify, .. } = observer {
+                notify(old, new);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Employee, Money, PermissionFacet};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_subscription_unregisters_on_drop() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        let seen = Arc::new(AtomicU64::new(0));
+
+        let counter = Arc::clone(&seen);
+        let subscription = employee.observe::<AccountFacet>(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }).unwrap();
+
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        assert_eq!(seen.load(Ordering::Relaxed), 2);
+
+        drop(subscription);
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10))).unwrap().unwrap();
+        assert_eq!(seen.load(Ordering::Relaxed), 2);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Theme(&'static str);
+
+    impl Facet for Theme {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_change_observers_see_old_and_new_state() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(Theme("light")).unwrap();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&changes);
+        let subscription = employee.observe_changes::<Theme>(move |old, new| {
+            log.lock().unwrap().push((old.0, new.0));
+        }).unwrap();
+
+        employee.with_facet_mut::<Theme, _>(|theme| theme.0 = "dark").unwrap();
+        employee.with_facet_mut::<Theme, _>(|theme| theme.0 = "contrast").unwrap();
+        assert_eq!(*changes.lock().unwrap(), [("light", "dark"), ("dark", "contrast")]);
+
+        subscription.keep();
+        employee.with_facet_mut::<Theme, _>(|theme| theme.0 = "light").unwrap();
+        assert_eq!(changes.lock().unwrap().len(), 3);
+    }
+}
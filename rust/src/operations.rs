@@ -0,0 +1,265 @@
+use crate::core::FacetedObject;
+use crate::employee::Employee;
+use crate::error::FacetError;
+use crate::facets::{AccountFacet, BalanceChanged, PermissionFacet};
+use crate::guard::Capability;
+use crate::money::Money;
+use crate::pipeline::{Audit, Authorize, Pipeline};
+use crate::locks::{FacetLockRequest, LockedFacets};
+use crate::report::{PlainTextFormatter, Report, ReportFormatter, ReportRenderer};
+use crate::runner::FacetOperation;
+use crate::typed::Faceted;
+
+// Employee statically known to carry the facets financial operations need
+pub type FinancialEmployee = Faceted<Employee, (AccountFacet, PermissionFacet)>;
+
+// A financial operation on the employee's account, run through an
+// OperationRunner; like perform_financial_operation it needs the
+// "financial_operations" permission and leaves the account as it found it
+// if it fails
+pub struct FinancialOperation<F> {
+    operation: F,
+}
+
+impl<F> FinancialOperation<F>
+where
+    F: FnOnce(&mut AccountFacet) -> Result<Money, FacetError>,
+{
+    pub fn new(operation: F) -> Self {
+        Self { operation }
+    }
+}
+
+impl<F> FacetOperation for FinancialOperation<F>
+where
+    F: FnOnce(&mut AccountFacet) -> Result<Money, FacetError>,
+{
+    type Output = Money;
+
+    fn name(&self) -> &str {
+        "financial_operation"
+    }
+
+    fn required_facets(&self) -> Vec<FacetLockRequest> {
+        vec![FacetLockRequest::write::<AccountFacet>()]
+    }
+
+    fn permission(&self) -> Option<&str> {
+        Some("financial_operations")
+    }
+
+    fn execute(self, facets: &mut LockedFacets<'_>) -> Result<Money, FacetError> {
+        let account = facets.get_mut::<AccountFacet>()?;
+        let mut draft = account.clone();
+        let balance = (self.operation)(&mut draft)?;
+        *account = draft;
+        Ok(balance)
+    }
+}
+
+// Composite operations that work across facets. New operations are better
+// written as FacetOperations, which get the permission check and auditing
+// from OperationRunner.
+pub struct EmployeeOperations;
+
+impl EmployeeOperations {
+    // Stages wrapped around every financial operation: permission check,
+    // then auditing of the outcome (successes and failures)
+    pub fn financial_pipeline() -> Pipeline {
+        Pipeline::new("financial_operation")
+            .stage(Authorize::new("financial_operations"))
+            .stage(Audit::new().describe(|balance| format!("New balance: {}", balance)))
+    }
+
+    pub fn perform_financial_operation<F>(
+        employee_obj: &FacetedObject,
+        operation: F,
+    ) -> Result<String, FacetError>
+    where
+        F: FnOnce(&mut AccountFacet) -> Result<Money, FacetError>,
+    {
+        Self::perform_financial_operation_with(&Self::financial_pipeline(), employee_obj, operation)
+    }
+
+    // Run a financial operation through a custom pipeline, e.g. one with
+    // rate limiting or notification stages added
+    pub fn perform_financial_operation_with<F>(
+        pipeline: &Pipeline,
+        employee_obj: &FacetedObject,
+        operation: F,
+    ) -> Result<String, FacetError>
+    where
+        F: FnOnce(&mut AccountFacet) -> Result<Money, FacetError>,
+    {
+        // A failing operation leaves the account as it found it
+        let balance = pipeline.run(employee_obj, |object| {
+            object.transaction(|tx| tx.with_facet_mut::<AccountFacet, Result<Money, FacetError>>(operation)?)
+        })?;
+
+        let employee_name = employee_obj.get_core::<Employee>()
+            .map(|emp| emp.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        Ok(format!("Financial operation completed for {}. New balance: {}", employee_name, balance))
+    }
+
+    // Same as perform_financial_operation, but the account and permission
+    // facets are guaranteed by the type, so only real failures remain
+    pub fn perform_typed_financial_operation<F>(
+        employee: &FinancialEmployee,
+        operation: F,
+    ) -> Result<String, FacetError>
+    where
+        F: FnOnce(&mut AccountFacet) -> Result<Money, FacetError>,
+    {
+        // Employees act on their own accounts
+        employee.object().authorize(employee.object(), &Capability::Financial, "financial_operation")?;
+
+        let (account_number, previous) = employee.with::<AccountFacet, _, _>(|account| {
+            (account.get_account_number().to_string(), account.get_balance())
+        })?;
+
+        // Audit and any other interested facets pick the change up
+        // themselves; if that fails the balance change is rolled back
+        let balance = employee.object().transaction(|tx| {
+            let balance = tx.with_facet_mut::<AccountFacet, _>(operation)??;
+            tx.object().emit(&BalanceChanged { account_number, previous, balance })?;
+            Ok(balance)
+        })?;
+
+        Ok(format!("Financial operation completed for {}. New balance: {}", employee.core().name, balance))
+    }
+
+    // Structured report of the employee and every summarizable facet
+    pub fn employee_report(employee_obj: &FacetedObject) -> Result<Report, FacetError> {
+        let title = employee_obj.get_core::<Employee>()
+            .map(|employee| format!("Employee: {} (ID: {})", employee.name, employee.id))
+            .unwrap_or_else(|| "Employee: Unknown".to_string());
+        let mut report = Report::for_object(&title, employee_obj)?;
+
+        if let Some(employee) = employee_obj.get_core::<Employee>() {
+            report = report.field("Department", &employee.department);
+        }
+        Ok(report)
+    }
+
+    pub fn get_employee_summary(employee_obj: &FacetedObject) -> String {
+        Self::render_employee_summary(employee_obj, &ReportRenderer::new(PlainTextFormatter))
+    }
+
+    // Employee summary in any report format, e.g. Markdown or HTML
+    pub fn render_employee_summary<R: ReportFormatter>(
+        employee_obj: &FacetedObject,
+        renderer: &ReportRenderer<R>,
+    ) -> String {
+        match Self::employee_report(employee_obj) {
+            Ok(report) => renderer.render(&report),
+            Err(e) => format!("Employee summary unavailable: {}\n", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facets::{AuditFacet, AuditInterceptor};
+    use crate::pipeline::Validate;
+    use crate::report::MarkdownFormatter;
+
+    #[test]
+    fn test_typed_financial_operation() {
+        let employee = FinancialEmployee::require({
+            let object = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+            object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+            object.attach_facet(PermissionFacet::new("manager")).unwrap();
+            object
+        }).unwrap();
+
+        let result = EmployeeOperations::perform_typed_financial_operation(&employee, |account| account.deposit(Money::usd(500)));
+        assert!(result.unwrap().contains("New balance: 500.00 USD"));
+
+        let result = EmployeeOperations::perform_typed_financial_operation(&employee, |account| account.withdraw(Money::usd(900)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_financial_operation_runs_through_runner() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+
+        assert_eq!(employee.run_operation(FinancialOperation::new(|account| account.deposit(Money::usd(50)))), Ok(Money::usd(50)));
+        let failed = employee.run_operation(FinancialOperation::new(|account| {
+            account.deposit(Money::usd(10))?;
+            account.withdraw(Money::usd(500))
+        }));
+        assert!(matches!(failed, Err(FacetError::InsufficientFunds { .. })));
+        assert_eq!(employee.facet_ref::<AccountFacet>().unwrap().get_balance(), Money::usd(50));
+
+        let details: Vec<String> = employee.with_facet::<AuditFacet, _>(|audit| {
+            audit.get_audit_trail().iter().map(|entry| entry.details.clone()).collect()
+        }).unwrap();
+        assert_eq!(details, ["50.00 USD", "Failed: Insufficient funds: balance 60.00 USD, requested 500.00 USD"]);
+    }
+
+    #[test]
+    fn test_employee_summary_formats() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let text = EmployeeOperations::get_employee_summary(&employee);
+        assert!(text.starts_with("Employee: Test User (ID: TEST001)\nDepartment: Engineering\nAccount:\n"));
+
+        let markdown = EmployeeOperations::render_employee_summary(&employee, &ReportRenderer::new(MarkdownFormatter));
+        assert!(markdown.contains("## Account"));
+        assert!(markdown.contains("| Number | ACC001 |"));
+    }
+
+    #[test]
+    fn test_custom_financial_pipeline() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+
+        let pipeline = EmployeeOperations::financial_pipeline()
+            .insert_before("audit", Validate::new("core_is_employee", |ctx| {
+                ctx.object.get_core::<Employee>()
+                    .map(|_| ())
+                    .ok_or(FacetError::CoreTypeMismatch { type_name: "Employee" })
+            }))
+            .unwrap();
+        assert_eq!(pipeline.stage_names(), ["authorize", "core_is_employee", "audit"]);
+
+        let result = EmployeeOperations::perform_financial_operation_with(&pipeline, &employee, |account| account.deposit(Money::usd(50)));
+        assert!(result.unwrap().ends_with("New balance: 50.00 USD"));
+        assert!(EmployeeOperations::perform_financial_operation_with(&pipeline, &employee, |account| account.withdraw(Money::usd(80))).is_err());
+
+        let details: Vec<String> = employee.with_facet::<AuditFacet, _>(|audit| {
+            audit.get_audit_trail().iter().map(|entry| entry.details.clone()).collect()
+        }).unwrap();
+        assert_eq!(details, [
+            "New balance: 50.00 USD",
+            "Failed: Insufficient funds: balance 50.00 USD, requested 80.00 USD",
+        ]);
+    }
+
+    #[test]
+    fn test_audit_interceptor_replaces_audit_stage() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee.add_interceptor(AuditInterceptor).unwrap();
+
+        let pipeline = EmployeeOperations::financial_pipeline().remove("audit").unwrap();
+        EmployeeOperations::perform_financial_operation_with(&pipeline, &employee, |account| account.deposit(Money::usd(50))).unwrap();
+        employee.with_facet_mut::<PermissionFacet, _>(|permissions| permissions.grant_permission("payroll")).unwrap();
+
+        let details: Vec<String> = employee.with_facet::<AuditFacet, _>(|audit| {
+            audit.get_audit_trail().iter().map(|entry| entry.details.clone()).collect()
+        }).unwrap();
+        assert_eq!(details, ["AccountFacet modified", "PermissionFacet modified"]);
+    }
+}
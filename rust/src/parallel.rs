@@ -0,0 +1,104 @@
+use std::any::TypeId;
+
+use rayon::prelude::*;
+
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+use crate::world::{EntityId, FacetWorld};
+
+impl FacetWorld {
+    // Run `operation` on every instance of F across the world on the rayon
+    // thread pool, e.g. to apply monthly interest to every account. Each
+    // call only write-locks the facet it runs on, as with_facet_mut does,
+    // so objects are processed side by side; the world itself is only
+    // locked to list the objects carrying F. Results come back in
+    // insertion order, one per instance. Instances detached while the batch
+    // runs are skipped; any other access error fails the batch once every
+    // object has been visited.
+    pub fn par_for_each_facet_mut<F, R>(
+        &self,
+        operation: impl Fn(&mut F) -> R + Send + Sync,
+    ) -> Result<Vec<(EntityId, R)>, FacetError>
+    where
+        F: Facet + 'static,
+        R: Send,
+    {
+        let carriers = self.carriers(TypeId::of::<F>())?;
+        let results: Vec<Result<Vec<(EntityId, R)>, FacetError>> = carriers.par_iter()
+            .map(|(id, object)| each_instance(object, &operation).map(|results| {
+                results.into_iter().map(|result| (*id, result)).collect()
+            }))
+            .collect();
+
+        let mut flattened = Vec::new();
+        for result in results {
+            flattened.extend(result?);
+        }
+        Ok(flattened)
+    }
+}
+
+fn each_instance<F: Facet + 'static, R>(object: &FacetedObject, operation: &impl Fn(&mut F) -> R) -> Result<Vec<R>, FacetError> {
+    let mut results = Vec::new();
+    for name in object.facet_instance_names::<F>() {
+        match object.with_named_facet_mut(&name, operation) {
+            Ok(result) => results.push(result),
+            Err(FacetError::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Employee, Money, PermissionFacet};
+
+    fn account_holder(id: &str, cents: i64) -> FacetedObject {
+        let employee = FacetedObject::builder(Employee::new("Test User", id, "Engineering"))
+            .with(AccountFacet::new(&format!("ACC-{}", id)))
+            .build()
+            .unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::from_minor(cents, account.currency()))).unwrap().unwrap();
+        employee
+    }
+
+    #[test]
+    fn test_monthly_interest_across_world() {
+        let world = FacetWorld::new();
+        for n in 0..200 {
+            world.insert(account_holder(&format!("E{:03}", n), 10_000 + n)).unwrap();
+        }
+        let without_account = world.insert(FacetedObject::builder(Employee::new("Test User", "E999", "Engineering"))
+            .with(PermissionFacet::new("employee"))
+            .build()
+            .unwrap()).unwrap();
+
+        // 1% interest, rounded down to the cent
+        let credited = world.par_for_each_facet_mut::<AccountFacet, _>(|account| {
+            let interest = Money::from_minor(account.get_balance().minor() / 100, account.currency());
+            account.deposit_with_memo(interest, "interest").map(|_| interest)
+        }).unwrap();
+
+        assert_eq!(credited.len(), 200);
+        assert!(credited.iter().all(|(id, interest)| *id != without_account && interest.as_ref().unwrap().minor() >= 100));
+        assert!(credited.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        let (first, _) = credited[0];
+        assert_eq!(
+            world.get(first).unwrap().with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(),
+            Money::from_minor(10_100, crate::Currency::USD),
+        );
+    }
+
+    #[test]
+    fn test_every_named_instance_is_visited() {
+        let world = FacetWorld::new();
+        let holder = account_holder("E001", 500);
+        holder.attach_named_facet("savings", AccountFacet::new("SAV-E001")).unwrap();
+        let id = world.insert(holder).unwrap();
+
+        let numbers = world.par_for_each_facet_mut::<AccountFacet, _>(|account| account.get_account_number().to_string()).unwrap();
+        assert_eq!(numbers, [(id, "ACC-E001".to_string()), (id, "SAV-E001".to_string())]);
+    }
+}
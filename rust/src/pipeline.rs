@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::clock::{Clock, Timestamp};
+use crate::correlation::CorrelationScope;
+use crate::{FacetError, FacetedObject};
+#[cfg(feature = "builtin-facets")]
+use crate::{AuditEntry, Auditable, Authorizer};
+
+// State shared by the stages of one pipeline run
+pub struct OperationContext<'a> {
+    pub object: &'a FacetedObject,
+    pub operation: &'a str,
+    // Free-form values stages can use to pass data along
+    pub attributes: HashMap<String, String>,
+    // Set once the operation has run (or was rejected by a stage)
+    pub outcome: Option<Result<String, FacetError>>,
+}
+
+// One cross-cutting step wrapped around an operation. `before` runs in
+// pipeline order ahead of execution and can reject it; `after` runs in
+// reverse order once the outcome is known, for every stage whose `before`
+// succeeded.
+pub trait Stage: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn before(&self, _ctx: &mut OperationContext<'_>) -> Result<(), FacetError> {
+        Ok(())
+    }
+
+    fn after(&self, _ctx: &OperationContext<'_>) {}
+}
+
+// Ordered stages for a named operation
+pub struct Pipeline {
+    operation: String,
+    stages: Vec<Box<dyn Stage>>,
+}
+
+impl Pipeline {
+    pub fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            stages: Vec::new(),
+        }
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    // Append a stage
+    pub fn stage(mut self, stage: impl Stage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    // Insert a stage ahead of the stage named `existing`
+    pub fn insert_before(mut self, existing: &str, stage: impl Stage + 'static) -> Result<Self, FacetError> {
+        let index = self.position(existing)?;
+        self.stages.insert(index, Box::new(stage));
+        Ok(self)
+    }
+
+    pub fn remove(mut self, name: &str) -> Result<Self, FacetError> {
+        let index = self.position(name)?;
+        self.stages.remove(index);
+        Ok(self)
+    }
+
+    // Reorder stages to match `names`, which must list every stage once
+    pub fn reorder(mut self, names: &[&str]) -> Result<Self, FacetError> {
+        if names.len() != self.stages.len() {
+            return Err(FacetError::Invalid(format!("Expected {} stage names, got {}", self.stages.len(), names.len())));
+        }
+
+        let mut reordered = Vec::with_capacity(self.stages.len());
+        for name in names {
+            let index = self.position(name)?;
+            reordered.push(self.stages.remove(index));
+        }
+        self.stages = reordered;
+        Ok(self)
+    }
+
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    fn position(&self, name: &str) -> Result<usize, FacetError> {
+        self.stages.iter()
+            .position(|stage| stage.name() == name)
+            .ok_or_else(|| FacetError::Invalid(format!("Stage '{}' not in pipeline '{}'", name, self.operation)))
+    }
+
+    // Run the stages around `execute`. Audit entries written during the
+    // run share a correlation id, also set as the "correlation_id"
+    // attribute.
+    pub fn run<T: Display>(
+        &self,
+        object: &FacetedObject,
+        execute: impl FnOnce(&FacetedObject) -> Result<T, FacetError>,
+    ) -> Result<T, FacetError> {
+        let correlation = CorrelationScope::enter();
+        let mut ctx = OperationContext {
+            object,
+            operation: &self.operation,
+            attributes: HashMap::from([("correlation_id".to_string(), correlation.id())]),
+            outcome: None,
+        };
+
+        for (index, stage) in self.stages.iter().enumerate() {
+            if let Err(e) = stage.before(&mut ctx) {
+                ctx.outcome = Some(Err(e.clone()));
+                for entered in self.stages[..index].iter().rev() {
+                    entered.after(&ctx);
+                }
+                return Err(e);
+            }
+        }
+
+        let result = execute(object);
+        ctx.outcome = Some(match &result {
+            Ok(value) => Ok(value.to_string()),
+            Err(e) => Err(e.clone()),
+        });
+
+        for stage in self.stages.iter().rev() {
+            stage.after(&ctx);
+        }
+        result
+    }
+}
+
+// Rejects the operation unless the object's Authorizer facet (normally
+// PermissionFacet) grants `permission`
+#[cfg(feature = "builtin-facets")]
+pub struct Authorize {
+    permission: String,
+}
+
+#[cfg(feature = "builtin-facets")]
+impl Authorize {
+    pub fn new(permission: &str) -> Self {
+        Self { permission: permission.to_string() }
+    }
+}
+
+#[cfg(feature = "builtin-facets")]
+impl Stage for Authorize {
+    fn name(&self) -> &str {
+        "authorize"
+    }
+
+    fn before(&self, ctx: &mut OperationContext<'_>) -> Result<(), FacetError> {
+        let allowed = ctx.object.with_facet_as::<dyn Authorizer, bool>(|permissions| {
+            permissions.has_permission(&self.permission)
+        }).unwrap_or(false);
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(FacetError::PermissionDenied {
+                operation: ctx.operation.to_string(),
+                permission: self.permission.clone(),
+            })
+        }
+    }
+}
+
+type Validator = Box<dyn Fn(&OperationContext<'_>) -> Result<(), FacetError> + Send + Sync>;
+
+// Rejects the operation when the validator returns an error
+pub struct Validate {
+    name: String,
+    validator: Validator,
+}
+
+impl Validate {
+    pub fn new(
+        name: &str,
+        validator: impl Fn(&OperationContext<'_>) -> Result<(), FacetError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            validator: Box::new(validator),
+        }
+    }
+}
+
+impl Stage for Validate {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn before(&self, ctx: &mut OperationContext<'_>) -> Result<(), FacetError> {
+        (self.validator)(ctx)
+    }
+}
+
+// Allows at most `limit` runs of the pipeline per fixed time window,
+// across all objects it is used with
+pub struct RateLimit {
+    limit: u32,
+    window: Duration,
+    clock: Arc<dyn Clock>,
+    current: Mutex<(Timestamp, u32)>,
+}
+
+impl RateLimit {
+    pub fn new(limit: u32, window: Duration, clock: Arc<dyn Clock>) -> Self {
+        let now = clock.now();
+        Self {
+            limit,
+            window,
+            clock,
+            current: Mutex::new((now, 0)),
+        }
+    }
+}
+
+impl Stage for RateLimit {
+    fn name(&self) -> &str {
+        "rate_limit"
+    }
+
+    fn before(&self, ctx: &mut OperationContext<'_>) -> Result<(), FacetError> {
+        let now = self.clock.now();
+        let mut current = self.current.lock();
+
+        let elapsed = now.saturating_duration_since(current.0);
+        if elapsed >= self.window {
+            *current = (now, 0);
+        }
+        if current.1 >= self.limit {
+            return Err(FacetError::RateLimited { operation: ctx.operation.to_string(), retry_after: self.window - elapsed });
+        }
+        current.1 += 1;
+        Ok(())
+    }
+}
+
+type Describe = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+// Records the outcome in the object's Auditable facet, if one is attached
+#[cfg(feature = "builtin-facets")]
+pub struct Audit {
+    describe: Describe,
+}
+
+#[cfg(feature = "builtin-facets")]
+impl Audit {
+    pub fn new() -> Self {
+        Self { describe: Box::new(str::to_string) }
+    }
+
+    // Customize how a successful outcome is written to the trail
+    pub fn describe(mut self, describe: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        self.describe = Box::new(describe);
+        self
+    }
+}
+
+#[cfg(feature = "builtin-facets")]
+impl Default for Audit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "builtin-facets")]
+impl Stage for Audit {
+    fn name(&self) -> &str {
+        "audit"
+    }
+
+    fn after(&self, ctx: &OperationContext<'_>) {
+        let entry = match &ctx.outcome {
+            Some(Ok(value)) => AuditEntry::new(ctx.operation, &(self.describe)(value)),
+            Some(Err(e)) => AuditEntry::new(ctx.operation, &format!("Failed: {}", e)).failed(e),
+            None => return,
+        };
+        let _ = ctx.object.with_facet_as_mut::<dyn Auditable, ()>(|audit| audit.record(entry));
+    }
+}
+
+type Notifier = Box<dyn Fn(&OperationContext<'_>) + Send + Sync>;
+
+// Calls back once the outcome is known, e.g. to publish a notification
+pub struct Notify {
+    notifier: Notifier,
+}
+
+impl Notify {
+    pub fn new(notifier: impl Fn(&OperationContext<'_>) + Send + Sync + 'static) -> Self {
+        Self { notifier: Box::new(notifier) }
+    }
+}
+
+impl Stage for Notify {
+    fn name(&self) -> &str {
+        "notify"
+    }
+
+    fn after(&self, ctx: &OperationContext<'_>) {
+        (self.notifier)(ctx);
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet};
+
+    fn employee(role: &str) -> FacetedObject {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new(role)).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee
+    }
+
+    fn audit_details(object: &FacetedObject) -> Vec<String> {
+        object.with_facet::<AuditFacet, _>(|audit| {
+            audit.get_audit_trail().iter().map(|entry| entry.details.clone()).collect()
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_stage_order_controls_auditing_of_rejections() {
+        let deposit = |object: &FacetedObject| {
+            object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10)))?
+        };
+
+        let employee_obj = employee("employee");
+        let pipeline = Pipeline::new("deposit")
+            .stage(Authorize::new("financial_operations"))
+            .stage(Audit::new());
+        assert!(pipeline.run(&employee_obj, deposit).is_err());
+        assert!(audit_details(&employee_obj).is_empty());
+
+        // With audit first, the rejection is recorded as well
+        let pipeline = pipeline.reorder(&["audit", "authorize"]).unwrap();
+        assert!(pipeline.run(&employee_obj, deposit).is_err());
+        assert_eq!(audit_details(&employee_obj).len(), 1);
+        assert_eq!(audit_details(&employee_obj)[0], "Failed: Access denied: 'deposit' requires permission 'financial_operations'");
+    }
+
+    #[test]
+    fn test_rate_limit_validation_and_notification() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&notified);
+
+        let pipeline = Pipeline::new("deposit")
+            .stage(Authorize::new("financial_operations"))
+            .stage(RateLimit::new(2, Duration::from_secs(60), clock.clone()))
+            .stage(Notify::new(move |ctx| {
+                sink.lock().push(ctx.outcome.clone().unwrap());
+            }))
+            .insert_before("rate_limit", Validate::new("business_hours", |_| Ok(())))
+            .unwrap();
+        assert_eq!(pipeline.stage_names(), ["authorize", "business_hours", "rate_limit", "notify"]);
+
+        let employee_obj = employee("manager");
+        let deposit = |object: &FacetedObject| {
+            object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(10)))?
+        };
+        assert_eq!(pipeline.run(&employee_obj, deposit).unwrap(), Money::usd(10));
+        assert_eq!(pipeline.run(&employee_obj, deposit).unwrap(), Money::usd(20));
+        assert!(matches!(pipeline.run(&employee_obj, deposit), Err(FacetError::RateLimited { retry_after, .. }) if retry_after == Duration::from_secs(60)));
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(pipeline.run(&employee_obj, deposit).unwrap(), Money::usd(30));
+        assert_eq!(notified.lock().len(), 3);
+    }
+}
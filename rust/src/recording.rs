@@ -0,0 +1,306 @@
+// Record/replay of facet mutations. While recording, each operation run
+// through the recorder (directly or via the `Record` pipeline stage) appends
+// the serialized before/after state of every tracked facet it changed. The
+// trace can be saved and later replayed to rebuild the object as it was
+// after any step.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::pipeline::{OperationContext, Stage};
+use crate::{Facet, FacetError, FacetedObject};
+
+// One facet change. `before`/`after` are None when the facet was not attached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub seq: u64,
+    pub timestamp: Timestamp,
+    pub operation: String,
+    pub facet: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |state: &Option<Value>| state.as_ref().map_or("-".to_string(), Value::to_string);
+        write!(f, "#{} {} {} {}: {} -> {}", self.seq, self.timestamp, self.operation, self.facet, show(&self.before), show(&self.after))
+    }
+}
+
+// Tracked facet states when recording started, followed by every change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    pub initial: BTreeMap<String, Value>,
+    pub entries: Vec<TraceEntry>,
+}
+
+impl Trace {
+    // Tracked facet states after step `seq`; 0 is the initial state
+    pub fn state_at(&self, seq: u64) -> BTreeMap<String, Value> {
+        let mut state = self.initial.clone();
+        for entry in self.entries.iter().take_while(|entry| entry.seq <= seq) {
+            match &entry.after {
+                Some(after) => state.insert(entry.facet.clone(), after.clone()),
+                None => state.remove(&entry.facet),
+            };
+        }
+        state
+    }
+
+    pub fn last_seq(&self) -> u64 {
+        self.entries.last().map_or(0, |entry| entry.seq)
+    }
+}
+
+type StateReader = Box<dyn Fn(&FacetedObject) -> Option<Value> + Send + Sync>;
+type StateRestorer = Box<dyn Fn(&FacetedObject, Value) -> Result<(), FacetError> + Send + Sync>;
+
+struct TrackedFacet {
+    name: String,
+    read: StateReader,
+    restore: StateRestorer,
+}
+
+pub struct MutationRecorder {
+    facets: Vec<TrackedFacet>,
+    clock: Arc<dyn Clock>,
+    trace: Mutex<Trace>,
+}
+
+impl Default for MutationRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MutationRecorder {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            facets: Vec::new(),
+            clock,
+            trace: Mutex::new(Trace::default()),
+        }
+    }
+
+    // Record changes to facet F under `name`
+    pub fn track<F>(mut self, name: &str) -> Self
+    where
+        F: Facet + Serialize + DeserializeOwned + 'static,
+    {
+        self.facets.push(TrackedFacet {
+            name: name.to_string(),
+            read: Box::new(|object| {
+                object.with_facet::<F, _>(|facet| serde_json::to_value(facet).ok())
+                    .ok()
+                    .flatten()
+            }),
+            restore: Box::new(|object, state| {
+                let facet: F = serde_json::from_value(state)
+                    .map_err(|e| FacetError::Invalid(format!("Invalid recorded state: {}", e)))?;
+                object.attach_facet(facet)
+            }),
+        });
+        self
+    }
+
+    // Continue from a previously saved trace, e.g. to replay it offline
+    pub fn load(self, trace: Trace) -> Self {
+        *self.trace.lock() = trace;
+        self
+    }
+
+    fn snapshot(&self, object: &FacetedObject) -> BTreeMap<String, Value> {
+        self.facets.iter()
+            .filter_map(|facet| (facet.read)(object).map(|state| (facet.name.clone(), state)))
+            .collect()
+    }
+
+    // Start a fresh trace from the object's current state
+    pub fn start(&self, object: &FacetedObject) {
+        let initial = self.snapshot(object);
+        *self.trace.lock() = Trace { initial, entries: Vec::new() };
+    }
+
+    // Run `operation` against the object and record what it changed
+    pub fn capture<R>(
+        &self,
+        object: &FacetedObject,
+        operation: &str,
+        f: impl FnOnce(&FacetedObject) -> R,
+    ) -> R {
+        let before = self.snapshot(object);
+        let result = f(object);
+        self.append(operation, before, self.snapshot(object));
+        result
+    }
+
+    fn append(
+        &self,
+        operation: &str,
+        mut before: BTreeMap<String, Value>,
+        mut after: BTreeMap<String, Value>,
+    ) {
+        let timestamp = self.clock.now();
+        let mut trace = self.trace.lock();
+
+        for facet in &self.facets {
+            let (before, after) = (before.remove(&facet.name), after.remove(&facet.name));
+            if before != after {
+                let seq = trace.last_seq() + 1;
+                trace.entries.push(TraceEntry {
+                    seq,
+                    timestamp,
+                    operation: operation.to_string(),
+                    facet: facet.name.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
+    pub fn trace(&self) -> Trace {
+        self.trace.lock().clone()
+    }
+
+    // Rebuild the object as it was after step `seq` around a fresh core.
+    // Untracked facets are not part of the trace and are not restored.
+    pub fn replay_to<T: Any + Send + Sync>(&self, seq: u64, core: T) -> Result<FacetedObject, FacetError> {
+        let state = self.trace.lock().state_at(seq);
+
+        let object = FacetedObject::new(core);
+        for facet in &self.facets {
+            if let Some(value) = state.get(&facet.name) {
+                (facet.restore)(&object, value.clone())?;
+            }
+        }
+        Ok(object)
+    }
+}
+
+// Pipeline stage recording what each run of the operation changed
+pub struct Record {
+    recorder: Arc<MutationRecorder>,
+}
+
+impl Record {
+    pub fn new(recorder: Arc<MutationRecorder>) -> Self {
+        Self { recorder }
+    }
+
+    const BEFORE: &'static str = "record.before";
+}
+
+impl Stage for Record {
+    fn name(&self) -> &str {
+        "record"
+    }
+
+    fn before(&self, ctx: &mut OperationContext<'_>) -> Result<(), FacetError> {
+        let before = serde_json::to_string(&self.recorder.snapshot(ctx.object))
+            .map_err(|e| FacetError::Other(e.to_string()))?;
+        ctx.attributes.insert(Self::BEFORE.to_string(), before);
+        Ok(())
+    }
+
+    fn after(&self, ctx: &OperationContext<'_>) {
+        let before = ctx.attributes.get(Self::BEFORE)
+            .and_then(|before| serde_json::from_str(before).ok())
+            .unwrap_or_default();
+        self.recorder.append(ctx.operation, before, self.recorder.snapshot(ctx.object));
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::pipeline::{Authorize, Pipeline};
+    use crate::{AccountFacet, Employee, EmployeeOperations, Money, PermissionFacet};
+    use serde_json::json;
+
+    fn tracking_recorder() -> MutationRecorder {
+        MutationRecorder::with_clock(Arc::new(ManualClock::new(Timestamp::from_millis(0))))
+            .track::<AccountFacet>("account")
+            .track::<PermissionFacet>("permissions")
+    }
+
+    fn employee() -> FacetedObject {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        employee.attach_facet(AccountFacet::new("ACC001").clock(clock)).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee
+    }
+
+    fn balance(object: &FacetedObject) -> Money {
+        object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap()
+    }
+
+    #[test]
+    fn test_replay_to_each_step() {
+        let recorder = tracking_recorder();
+        let employee = employee();
+        recorder.start(&employee);
+
+        for amount in [Money::usd(100), Money::usd(50)] {
+            recorder.capture(&employee, "deposit", |object| {
+                object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(amount))
+            }).unwrap().unwrap();
+        }
+        recorder.capture(&employee, "read_only", balance);
+
+        let trace = recorder.trace();
+        assert_eq!(trace.last_seq(), 2);
+        assert_eq!(trace.entries[1].before.as_ref().unwrap()["balances"], json!({ "USD": 10_000 }));
+
+        // A saved trace replays the same way in a fresh recorder
+        let offline = tracking_recorder().load(serde_json::from_str(&serde_json::to_string(&trace).unwrap()).unwrap());
+        let core = || Employee::new("Test User", "TEST001", "Engineering");
+        assert_eq!(balance(&offline.replay_to(0, core()).unwrap()), Money::usd(0));
+        assert_eq!(balance(&offline.replay_to(1, core()).unwrap()), Money::usd(100));
+        assert_eq!(balance(&offline.replay_to(2, core()).unwrap()), Money::usd(150));
+    }
+
+    #[test]
+    fn test_record_stage_captures_pipeline_operations() {
+        let recorder = Arc::new(tracking_recorder());
+        let employee = employee();
+        recorder.start(&employee);
+
+        let pipeline = Pipeline::new("deposit")
+            .stage(Record::new(Arc::clone(&recorder)))
+            .stage(Authorize::new("financial_operations"));
+        EmployeeOperations::perform_financial_operation_with(&pipeline, &employee, |account| account.deposit(Money::usd(25))).unwrap();
+
+        let trace = recorder.trace();
+        assert_eq!(trace.entries.len(), 1);
+        assert_eq!(trace.entries[0].operation, "deposit");
+        assert_eq!(trace.entries[0].to_string(), format!(
+            concat!(
+                "#1 {} deposit account: ",
+                "{{\"account_number\":\"ACC001\",\"balances\":{{\"USD\":0}},\"currency\":\"USD\",",
+                "\"foreign_currency\":\"reject\",\"ledger\":[]}}",
+                " -> ",
+                "{{\"account_number\":\"ACC001\",\"balances\":{{\"USD\":2500}},\"currency\":\"USD\",",
+                "\"foreign_currency\":\"reject\",\"ledger\":[{{\"amount\":{{\"currency\":\"USD\",\"minor\":2500}},",
+                "\"balance\":{{\"currency\":\"USD\",\"minor\":2500}},\"id\":1,\"memo\":\"\",",
+                "\"timestamp\":{{\"nanos\":0,\"secs\":0}}}}]}}",
+            ),
+            Timestamp::from_millis(0),
+        ));
+    }
+}
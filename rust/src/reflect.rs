@@ -0,0 +1,124 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::core::Facet;
+use crate::error::FacetError;
+
+// Type of a reflected field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Bool,
+    Number,
+    Text,
+}
+
+// Dynamically typed field value
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl FieldValue {
+    pub fn kind(&self) -> FieldKind {
+        match self {
+            FieldValue::Bool(_) => FieldKind::Bool,
+            FieldValue::Number(_) => FieldKind::Number,
+            FieldValue::Text(_) => FieldKind::Text,
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(value) => write!(f, "{}", value),
+            FieldValue::Number(value) => write!(f, "{}", value),
+            FieldValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo {
+    pub name: String,
+    pub kind: FieldKind,
+    pub writable: bool,
+}
+
+impl FieldInfo {
+    pub fn read_only(name: &str, kind: FieldKind) -> Self {
+        Self { name: name.to_string(), kind, writable: false }
+    }
+
+    pub fn writable(name: &str, kind: FieldKind) -> Self {
+        Self { name: name.to_string(), kind, writable: true }
+    }
+}
+
+// Runtime view of a facet's data, so generic tooling can list, read and
+// edit fields without compile-time knowledge of the facet type. Facets opt
+// in by implementing this and returning Some(self) from Facet::as_reflect
+// and Facet::as_reflect_mut. Tooling addresses them by Facet::facet_name.
+pub trait ReflectFacet: Facet {
+    fn fields(&self) -> Vec<FieldInfo>;
+
+    fn get_field(&self, name: &str) -> Option<FieldValue>;
+
+    // Setters should go through the facet's own invariants (e.g. validation)
+    fn set_field(&mut self, name: &str, value: FieldValue) -> Result<(), FacetError>;
+}
+
+// Error for set_field on a field that is missing, read-only or given the
+// wrong kind of value
+pub fn check_writable(facet: &dyn ReflectFacet, name: &str, value: &FieldValue) -> Result<(), FacetError> {
+    let facet_name = facet.facet_name().to_string();
+    let field = facet.fields()
+        .into_iter()
+        .find(|field| field.name == name)
+        .ok_or_else(|| FacetError::UnknownField { facet: facet_name.clone(), field: name.to_string() })?;
+
+    if !field.writable {
+        return Err(FacetError::ReadOnlyField { facet: facet_name, field: name.to_string() });
+    }
+    if field.kind != value.kind() {
+        return Err(FacetError::FieldTypeMismatch {
+            facet: facet_name,
+            field: name.to_string(),
+            expected: field.kind,
+            found: value.kind(),
+        });
+    }
+    Ok(())
+}
+
+// All fields of one reflectable facet with their current values
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflectedFacet {
+    pub name: &'static str,
+    pub fields: Vec<(FieldInfo, FieldValue)>,
+}
+
+impl ReflectedFacet {
+    pub(crate) fn of(facet: &dyn Facet) -> Option<Self> {
+        let reflect = facet.as_reflect()?;
+        Some(Self {
+            name: reflect.facet_name(),
+            fields: reflect.fields()
+                .into_iter()
+                .filter_map(|field| {
+                    let value = reflect.get_field(&field.name)?;
+                    Some((field, value))
+                })
+                .collect(),
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.fields.iter()
+            .find(|(field, _)| field.name == name)
+            .map(|(_, value)| value)
+    }
+}
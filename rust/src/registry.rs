@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::builder::FacetPreset;
+use crate::command::CommandSpec;
+use crate::snapshot::FacetMigration;
+use crate::sync::RwLock;
+use crate::{Facet, FacetError, FacetedObject, SerializableFacet};
+
+// Registry of live faceted objects addressable by id, shared by the
+// command bus and other front ends that route requests to objects
+#[derive(Default)]
+pub struct ObjectRegistry {
+    objects: RwLock<HashMap<String, Arc<FacetedObject>>>,
+}
+
+impl ObjectRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Register an object under a unique id
+    pub fn insert(&self, id: &str, object: FacetedObject) -> Result<Arc<FacetedObject>, FacetError> {
+        let mut objects = self.objects.write();
+
+        if objects.contains_key(id) {
+            return Err(FacetError::Invalid(format!("Object '{}' already registered", id)));
+        }
+
+        let object = Arc::new(object);
+        objects.insert(id.to_string(), Arc::clone(&object));
+        Ok(object)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<FacetedObject>> {
+        self.objects.read().get(id).cloned()
+    }
+
+    pub fn remove(&self, id: &str) -> Option<Arc<FacetedObject>> {
+        self.objects.write().remove(id)
+    }
+
+    // Registered ids in sorted order
+    pub fn ids(&self) -> Vec<String> {
+        let objects = self.objects.read();
+        let mut ids: Vec<String> = objects.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+type FacetConstructor = Box<dyn Fn(Option<&str>) -> Result<Box<dyn Facet>, FacetError> + Send + Sync>;
+type FacetDeserializer = Box<dyn Fn(Value) -> Result<Box<dyn Facet>, FacetError> + Send + Sync>;
+
+// Ways to produce one registered facet type
+#[derive(Default)]
+struct FacetFactory {
+    construct: Option<FacetConstructor>,
+    deserialize: Option<FacetDeserializer>,
+    // Keyed by the version each migration upgrades from
+    migrations: BTreeMap<u32, Box<dyn FacetMigration>>,
+    // Commands that operate on the facet, by command name
+    operations: BTreeMap<String, CommandSpec>,
+    // JSON Schema of the serialized state
+    #[cfg(feature = "schema")]
+    schema: Option<Value>,
+}
+
+// Facet types addressable by name, so facets can be created from
+// configuration specs like "audit" or "permissions:manager" and rebuilt
+// from snapshots without compile-time knowledge of the types involved
+#[derive(Default)]
+pub struct FacetRegistry {
+    factories: HashMap<String, FacetFactory>,
+    presets: HashMap<String, FacetPreset>,
+}
+
+impl FacetRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Registry of the built-in facets: "account:<number>",
+    // "permissions[:<role>]" (role defaults to "employee") and "audit"
+    #[cfg(feature = "builtin-facets")]
+    pub fn builtin() -> Self {
+        use crate::facets::account::{FloatBalanceToMoney, SingleToPerCurrency};
+        use crate::facets::permission::FlagsToOverrides;
+        use crate::{AccountFacet, AuditFacet, PermissionFacet};
+
+        let registry = Self::new()
+            .register("account", |argument| {
+                let number = argument.ok_or_else(|| FacetError::Invalid("account needs an account number".to_string()))?;
+                Ok(AccountFacet::new(number))
+            })
+            .register("permissions", |argument| Ok(PermissionFacet::new(argument.unwrap_or("employee"))))
+            .register("audit", |_| Ok(AuditFacet::new()))
+            .register_serializable::<AccountFacet>("account")
+            .migration("account", FloatBalanceToMoney)
+            .migration("account", SingleToPerCurrency)
+            .register_serializable::<PermissionFacet>("permissions")
+            .migration("permissions", FlagsToOverrides)
+            .register_serializable::<AuditFacet>("audit");
+        #[cfg(feature = "schema")]
+        let registry = registry
+            .register_schema::<AccountFacet>("account")
+            .register_schema::<PermissionFacet>("permissions")
+            .register_schema::<AuditFacet>("audit");
+        crate::command::builtin_specs().into_iter()
+            .fold(registry, |registry, (facet, command, spec)| registry.operation(facet, command, spec))
+    }
+
+    // Build facets named `name` with `constructor`, which receives the part
+    // of the spec after the first ':' if there is one
+    pub fn register<F, C>(mut self, name: &str, constructor: C) -> Self
+    where
+        F: Facet + 'static,
+        C: Fn(Option<&str>) -> Result<F, FacetError> + Send + Sync + 'static,
+    {
+        self.factories.entry(name.to_string()).or_default().construct = Some(Box::new(move |argument| {
+            Ok(Box::new(constructor(argument)?))
+        }));
+        self
+    }
+
+    // Restore F from snapshot entries saved under `name`, which should match
+    // the facet's Facet::facet_name
+    pub fn register_serializable<F>(mut self, name: &str) -> Self
+    where
+        F: SerializableFacet + DeserializeOwned + 'static,
+    {
+        let facet_name = name.to_string();
+        self.factories.entry(name.to_string()).or_default().deserialize = Some(Box::new(move |state| {
+            let facet: F = serde_json::from_value(state)
+                .map_err(|e| FacetError::Invalid(format!("Invalid state for facet '{}': {}", facet_name, e)))?;
+            Ok(Box::new(facet))
+        }));
+        self
+    }
+
+    // Upgrade saved states of facet `name` from migration.source_version()
+    // to the next version
+    pub fn migration(mut self, name: &str, migration: impl FacetMigration + 'static) -> Self {
+        self.factories.entry(name.to_string()).or_default()
+            .migrations
+            .insert(migration.source_version(), Box::new(migration));
+        self
+    }
+
+    // Run the migrations of facet `name` on a state saved at `version`,
+    // one version step at a time, until none applies
+    pub fn migrate(&self, name: &str, version: u32, state: Value) -> Result<Value, FacetError> {
+        let Some(migrations) = self.factories.get(name).map(|factory| &factory.migrations) else {
+            return Ok(state);
+        };
+        let mut state = state;
+        let mut version = version;
+        while let Some(migration) = migrations.get(&version) {
+            state = migration.migrate(state)
+                .map_err(|e| FacetError::Invalid(format!("Cannot migrate facet '{}' from version {}: {}", name, version, e)))?;
+            version += 1;
+        }
+        Ok(state)
+    }
+
+    // Declare command `command`, registered on a CommandBus, as an
+    // operation of facet `name`, for tooling such as schemas
+    pub fn operation(mut self, name: &str, command: &str, spec: CommandSpec) -> Self {
+        self.factories.entry(name.to_string()).or_default().operations.insert(command.to_string(), spec);
+        self
+    }
+
+    // Operations declared for facet `name`, sorted by command name
+    pub fn operations(&self, name: &str) -> Vec<(&str, &CommandSpec)> {
+        self.factories.get(name)
+            .map(|factory| factory.operations.iter().map(|(command, spec)| (command.as_str(), spec)).collect())
+            .unwrap_or_default()
+    }
+
+    // Describe the saved state of facet `name` with F's JSON Schema
+    #[cfg(feature = "schema")]
+    pub fn register_schema<F: schemars::JsonSchema>(mut self, name: &str) -> Self {
+        self.factories.entry(name.to_string()).or_default().schema = Some(schemars::schema_for!(F).to_value());
+        self
+    }
+
+    // JSON Schema documents of every facet registered with a schema, keyed
+    // by facet name. Each lists the facet's operations under
+    // "x-operations", as parameter schemas keyed by command name.
+    #[cfg(feature = "schema")]
+    pub fn schemas(&self) -> BTreeMap<String, Value> {
+        self.factories.iter()
+            .filter_map(|(name, factory)| {
+                let mut schema = factory.schema.clone()?;
+                let operations: serde_json::Map<String, Value> = factory.operations.iter()
+                    .map(|(command, spec)| (command.clone(), spec.json_schema()))
+                    .collect();
+                schema["x-operations"] = Value::Object(operations);
+                Some((name.clone(), schema))
+            })
+            .collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    // Make `preset` available by its name, replacing any preset of that name
+    pub fn preset(mut self, preset: FacetPreset) -> Self {
+        self.presets.insert(preset.name().to_string(), preset);
+        self
+    }
+
+    pub fn get_preset(&self, name: &str) -> Result<&FacetPreset, FacetError> {
+        self.presets.get(name).ok_or_else(|| FacetError::Invalid(format!("Unknown preset '{}'", name)))
+    }
+
+    // Registered names in sorted order
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.factories.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    // Build a facet from a spec such as "permissions:manager"
+    pub fn create(&self, spec: &str) -> Result<Box<dyn Facet>, FacetError> {
+        let (name, argument) = match spec.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (spec, None),
+        };
+        let construct = self.factories.get(name)
+            .and_then(|factory| factory.construct.as_ref())
+            .ok_or_else(|| FacetError::UnknownFacet { name: name.to_string() })?;
+        construct(argument)
+    }
+
+    // Build every spec and attach the facets in dependency order; nothing
+    // is attached if any spec is invalid
+    pub fn attach_all(&self, object: &FacetedObject, specs: &[&str]) -> Result<(), FacetError> {
+        let facets = specs.iter()
+            .map(|spec| self.create(spec))
+            .collect::<Result<Vec<_>, _>>()?;
+        object.attach_facets_ordered(facets)
+    }
+
+    pub fn deserialize(&self, name: &str, state: Value) -> Result<Box<dyn Facet>, FacetError> {
+        let deserialize = self.factories.get(name)
+            .and_then(|factory| factory.deserialize.as_ref())
+            .ok_or_else(|| FacetError::UnknownFacet { name: name.to_string() })?;
+        deserialize(state)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, PermissionFacet};
+
+    #[test]
+    fn test_attach_facets_from_specs() {
+        let registry = FacetRegistry::builtin();
+        assert_eq!(registry.names(), ["account", "audit", "permissions"]);
+
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        registry.attach_all(&employee, &["audit", "permissions:manager", "account:ACC001"]).unwrap();
+
+        assert!(employee.has_facet::<AuditFacet>());
+        assert_eq!(employee.with_facet::<PermissionFacet, _>(|permissions| permissions.get_role().to_string()).unwrap(), "manager");
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_account_number().to_string()).unwrap(), "ACC001");
+    }
+
+    #[test]
+    fn test_invalid_specs_attach_nothing() {
+        let registry = FacetRegistry::builtin();
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+
+        assert_eq!(
+            registry.attach_all(&employee, &["audit", "payroll"]),
+            Err(FacetError::UnknownFacet { name: "payroll".to_string() }),
+        );
+        assert!(registry.attach_all(&employee, &["audit", "account"]).is_err());
+        assert!(!employee.has_facet::<AuditFacet>());
+    }
+
+    #[test]
+    fn test_presets_by_name() {
+        let registry = FacetRegistry::builtin().preset(Employee::standard_preset());
+
+        let employee = FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .preset(registry.get_preset("standard_employee").unwrap())
+            .build()
+            .unwrap();
+        assert!(employee.has_facet::<AccountFacet>());
+        assert!(registry.get_preset("contractor").is_err());
+    }
+
+    #[test]
+    fn test_builtin_operations() {
+        let registry = FacetRegistry::builtin();
+        let commands: Vec<&str> = registry.operations("account").into_iter().map(|(command, _)| command).collect();
+        assert_eq!(commands, ["balance", "deposit", "withdraw"]);
+        assert_eq!(registry.operations("permissions")[0].1.permission(), Some("write"));
+        assert_eq!(registry.operations("audit")[0].0, "log");
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_schemas_describe_state_and_operations() {
+        let schemas = FacetRegistry::builtin().schemas();
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), ["account", "audit", "permissions"]);
+
+        let account = &schemas["account"];
+        assert_eq!(account["type"], "object");
+        assert!(account["properties"]["balances"].is_object());
+        assert!(account["properties"].get("rates").is_none());
+        assert_eq!(account["x-operations"]["deposit"]["properties"]["amount"]["type"], "number");
+        assert_eq!(account["x-operations"]["deposit"]["required"], serde_json::json!(["amount"]));
+        assert_eq!(account["x-operations"]["deposit"]["x-permission"], "financial_operations");
+        assert_eq!(schemas["permissions"]["x-operations"]["grant"]["properties"]["permission"]["type"], "string");
+    }
+}
@@ -0,0 +1,444 @@
+// Replication of selected facets between processes. A node publishes a
+// facet's serialized state after mutating it; peers apply incoming updates
+// through the facet's ConflictResolver so every node converges on the same
+// state for the same object id.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::registry::ObjectRegistry;
+use crate::{Facet, FacetError, FacetedObject};
+
+// One facet's state as published by a node. `version` is a Lamport clock
+// kept per node, so (version, node) orders updates the same way everywhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetUpdate {
+    pub object_id: String,
+    pub facet: String,
+    pub node: String,
+    pub version: u64,
+    pub state: Value,
+}
+
+// Carries updates between nodes. Updates are never delivered back to the
+// transport that published them.
+pub trait SyncTransport: Send + Sync {
+    fn publish(&self, update: &FacetUpdate) -> Result<(), FacetError>;
+
+    // Updates received since the last call
+    fn receive(&self) -> Result<Vec<FacetUpdate>, FacetError>;
+}
+
+type Inbox = Arc<Mutex<VecDeque<FacetUpdate>>>;
+
+fn drain(inbox: &Inbox) -> Vec<FacetUpdate> {
+    inbox.lock().drain(..).collect()
+}
+
+// In-process broadcast hub; every connected transport sees the others'
+// updates. Intended for tests and single-process setups.
+#[derive(Clone, Default)]
+pub struct InMemoryHub {
+    inboxes: Arc<Mutex<Vec<Inbox>>>,
+}
+
+impl InMemoryHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connect(&self) -> InMemoryTransport {
+        let inbox = Inbox::default();
+        self.inboxes.lock().push(Arc::clone(&inbox));
+        InMemoryTransport { hub: self.clone(), inbox }
+    }
+}
+
+pub struct InMemoryTransport {
+    hub: InMemoryHub,
+    inbox: Inbox,
+}
+
+impl SyncTransport for InMemoryTransport {
+    fn publish(&self, update: &FacetUpdate) -> Result<(), FacetError> {
+        let inboxes = self.hub.inboxes.lock();
+        for inbox in inboxes.iter().filter(|inbox| !Arc::ptr_eq(inbox, &self.inbox)) {
+            inbox.lock().push_back(update.clone());
+        }
+        Ok(())
+    }
+
+    fn receive(&self) -> Result<Vec<FacetUpdate>, FacetError> {
+        Ok(drain(&self.inbox))
+    }
+}
+
+// Newline-delimited JSON over TCP. Each node listens on its own address and
+// pushes updates to every registered peer; the listener thread runs for the
+// life of the process.
+pub struct TcpTransport {
+    local_addr: SocketAddr,
+    peers: Mutex<Vec<(SocketAddr, Option<TcpStream>)>>,
+    inbox: Inbox,
+}
+
+impl TcpTransport {
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<Self, FacetError> {
+        let listener = TcpListener::bind(addr).map_err(|e| FacetError::Other(format!("Failed to bind: {}", e)))?;
+        let local_addr = listener.local_addr().map_err(|e| FacetError::Other(e.to_string()))?;
+        let inbox = Inbox::default();
+
+        let accepted = Arc::clone(&inbox);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let inbox = Arc::clone(&accepted);
+                thread::spawn(move || Self::read_updates(stream, inbox));
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            peers: Mutex::new(Vec::new()),
+            inbox,
+        })
+    }
+
+    // Queue updates from one peer connection; malformed lines are skipped
+    fn read_updates(stream: TcpStream, inbox: Inbox) {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            if let Ok(update) = serde_json::from_str::<FacetUpdate>(&line) {
+                inbox.lock().push_back(update);
+            }
+        }
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn add_peer(&self, addr: SocketAddr) {
+        self.peers.lock().push((addr, None));
+    }
+}
+
+impl SyncTransport for TcpTransport {
+    // Sends to every peer, reconnecting as needed; reports the first failure
+    // after trying them all
+    fn publish(&self, update: &FacetUpdate) -> Result<(), FacetError> {
+        let mut line = serde_json::to_string(update).map_err(|e| FacetError::Other(e.to_string()))?;
+        line.push('\n');
+
+        let mut peers = self.peers.lock();
+        let mut failure = None;
+        for (addr, stream) in peers.iter_mut() {
+            if stream.is_none() {
+                *stream = TcpStream::connect(*addr).ok();
+            }
+            let sent = match stream {
+                Some(connection) => connection.write_all(line.as_bytes()).is_ok(),
+                None => false,
+            };
+            if !sent {
+                *stream = None;
+                failure.get_or_insert_with(|| FacetError::Other(format!("Failed to send update to {}", addr)));
+            }
+        }
+        failure.map_or(Ok(()), Err)
+    }
+
+    fn receive(&self) -> Result<Vec<FacetUpdate>, FacetError> {
+        Ok(drain(&self.inbox))
+    }
+}
+
+// Outcome of a conflict between local state and an incoming update
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    KeepLocal,
+    TakeRemote,
+    // New state to apply locally and re-publish
+    Merged(Value),
+}
+
+// Decides which state wins when an update arrives for a facet that is
+// already attached locally. `local` carries the last version this node
+// published or applied for the facet.
+pub trait ConflictResolver: Send + Sync {
+    fn resolve(&self, local: &FacetUpdate, remote: &FacetUpdate) -> Resolution;
+}
+
+// Highest (version, node) wins
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LastWriterWins;
+
+impl ConflictResolver for LastWriterWins {
+    fn resolve(&self, local: &FacetUpdate, remote: &FacetUpdate) -> Resolution {
+        if (remote.version, &remote.node) > (local.version, &local.node) {
+            Resolution::TakeRemote
+        } else {
+            Resolution::KeepLocal
+        }
+    }
+}
+
+// Combines both states with a merge function, e.g. a union of granted
+// permissions. The function should be commutative so peers converge.
+pub struct MergeWith<M>(pub M);
+
+impl<M> ConflictResolver for MergeWith<M>
+where
+    M: Fn(&Value, &Value) -> Value + Send + Sync,
+{
+    fn resolve(&self, local: &FacetUpdate, remote: &FacetUpdate) -> Resolution {
+        Resolution::Merged((self.0)(&local.state, &remote.state))
+    }
+}
+
+type StateReader = Box<dyn Fn(&FacetedObject) -> Option<Value> + Send + Sync>;
+type StateWriter = Box<dyn Fn(&FacetedObject, Value) -> Result<(), FacetError> + Send + Sync>;
+
+struct ReplicatedFacet {
+    name: String,
+    read: StateReader,
+    write: StateWriter,
+    resolver: Box<dyn ConflictResolver>,
+}
+
+// Synchronizes designated facets of the objects in a registry with other
+// nodes. Local mutations are not detected automatically: call `publish`
+// after changing a replicated facet, and `sync` to apply peers' updates.
+pub struct Replicator {
+    node: String,
+    objects: Arc<ObjectRegistry>,
+    transport: Box<dyn SyncTransport>,
+    facets: Vec<ReplicatedFacet>,
+    clock: AtomicU64,
+    // Last (version, node) published or applied per (object id, facet)
+    versions: Mutex<HashMap<(String, String), (u64, String)>>,
+}
+
+impl Replicator {
+    pub fn new(node: &str, objects: Arc<ObjectRegistry>, transport: impl SyncTransport + 'static) -> Self {
+        Self {
+            node: node.to_string(),
+            objects,
+            transport: Box::new(transport),
+            facets: Vec::new(),
+            clock: AtomicU64::new(0),
+            versions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Replicate facet F under `name`, which must be the same on every node
+    pub fn replicate<F>(mut self, name: &str, resolver: impl ConflictResolver + 'static) -> Self
+    where
+        F: Facet + Serialize + DeserializeOwned + 'static,
+    {
+        self.facets.push(ReplicatedFacet {
+            name: name.to_string(),
+            read: Box::new(|object| {
+                object.with_facet::<F, _>(|facet| serde_json::to_value(facet).ok())
+                    .ok()
+                    .flatten()
+            }),
+            write: Box::new(|object, state| {
+                let facet: F = serde_json::from_value(state)
+                    .map_err(|e| FacetError::Invalid(format!("Invalid replicated state: {}", e)))?;
+                if object.has_facet::<F>() {
+                    object.with_facet_mut::<F, _>(|current| *current = facet)?;
+                } else {
+                    object.attach_facet(facet)?;
+                }
+                Ok(())
+            }),
+            resolver: Box::new(resolver),
+        });
+        self
+    }
+
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    fn facet(&self, name: &str) -> Option<&ReplicatedFacet> {
+        self.facets.iter().find(|facet| facet.name == name)
+    }
+
+    fn object(&self, object_id: &str) -> Result<Arc<FacetedObject>, FacetError> {
+        self.objects.get(object_id)
+            .ok_or_else(|| FacetError::UnknownObject { id: object_id.to_string() })
+    }
+
+    fn send(&self, object_id: &str, facet: &str, state: Value) -> Result<(), FacetError> {
+        let version = self.clock.fetch_add(1, Ordering::SeqCst) + 1;
+        self.versions.lock()
+            .insert((object_id.to_string(), facet.to_string()), (version, self.node.clone()));
+
+        self.transport.publish(&FacetUpdate {
+            object_id: object_id.to_string(),
+            facet: facet.to_string(),
+            node: self.node.clone(),
+            version,
+            state,
+        })
+    }
+
+    // Publish every replicated facet attached to the object, returning how
+    // many were sent
+    pub fn publish(&self, object_id: &str) -> Result<usize, FacetError> {
+        let object = self.object(object_id)?;
+        let mut published = 0;
+        for facet in &self.facets {
+            if let Some(state) = (facet.read)(&object) {
+                self.send(object_id, &facet.name, state)?;
+                published += 1;
+            }
+        }
+        Ok(published)
+    }
+
+    pub fn publish_facet(&self, object_id: &str, name: &str) -> Result<(), FacetError> {
+        let facet = self.facet(name)
+            .ok_or_else(|| FacetError::Invalid(format!("Facet '{}' is not replicated", name)))?;
+        let object = self.object(object_id)?;
+        let state = (facet.read)(&object)
+            .ok_or_else(|| FacetError::UnknownFacet { name: name.to_string() })?;
+        self.send(object_id, name, state)
+    }
+
+    // Apply received updates, returning how many changed local state.
+    // Updates for objects or facets this node does not host are ignored.
+    pub fn sync(&self) -> Result<usize, FacetError> {
+        let mut applied = 0;
+        for update in self.transport.receive()? {
+            self.clock.fetch_max(update.version, Ordering::SeqCst);
+            if self.apply(update)? {
+                applied += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    fn apply(&self, remote: FacetUpdate) -> Result<bool, FacetError> {
+        let (Some(facet), Some(object)) = (self.facet(&remote.facet), self.objects.get(&remote.object_id)) else {
+            return Ok(false);
+        };
+        let key = (remote.object_id.clone(), remote.facet.clone());
+
+        let resolution = match (facet.read)(&object) {
+            None => Resolution::TakeRemote,
+            Some(state) => {
+                let (version, node) = self.versions.lock()
+                    .get(&key)
+                    .cloned()
+                    .unwrap_or_else(|| (0, self.node.clone()));
+                let local = FacetUpdate { version, node, state, ..remote.clone() };
+                facet.resolver.resolve(&local, &remote)
+            }
+        };
+
+        match resolution {
+            Resolution::KeepLocal => Ok(false),
+            Resolution::TakeRemote => {
+                (facet.write)(&object, remote.state)?;
+                self.versions.lock().insert(key, (remote.version, remote.node));
+                Ok(true)
+            }
+            Resolution::Merged(state) => {
+                (facet.write)(&object, state.clone())?;
+                // Peers already hold the remote state; only a merge that
+                // differs from it needs to be sent back out
+                if state != remote.state {
+                    self.send(&remote.object_id, &remote.facet, state)?;
+                } else {
+                    self.versions.lock().insert(key, (remote.version, remote.node));
+                }
+                Ok(true)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use crate::{Employee, PermissionFacet};
+
+    fn node(name: &str, transport: impl SyncTransport + 'static) -> Replicator {
+        let objects = Arc::new(ObjectRegistry::new());
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(PermissionFacet::new("employee")).unwrap();
+        objects.insert("TEST001", employee).unwrap();
+
+        Replicator::new(name, objects, transport)
+            .replicate::<PermissionFacet>("permissions", LastWriterWins)
+    }
+
+    fn can(replicator: &Replicator, permission: &str) -> bool {
+        replicator.objects.get("TEST001").unwrap()
+            .with_facet::<PermissionFacet, _>(|permissions| permissions.has_permission(permission))
+            .unwrap()
+    }
+
+    fn grant(replicator: &Replicator, permission: &str) {
+        replicator.objects.get("TEST001").unwrap()
+            .with_facet_mut::<PermissionFacet, _>(|permissions| permissions.grant_permission(permission))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_updates_converge() {
+        let hub = InMemoryHub::new();
+        let a = node("a", hub.connect());
+        let b = node("b", hub.connect());
+
+        grant(&a, "write");
+        a.publish("TEST001").unwrap();
+        assert_eq!(b.sync().unwrap(), 1);
+        assert!(can(&b, "write"));
+
+        // Concurrent edits: both nodes settle on b's, the higher (version, node)
+        grant(&a, "delete");
+        grant(&b, "financial_operations");
+        a.publish("TEST001").unwrap();
+        b.publish("TEST001").unwrap();
+        a.sync().unwrap();
+        b.sync().unwrap();
+
+        for replicator in [&a, &b] {
+            assert!(can(replicator, "financial_operations"));
+            assert!(!can(replicator, "delete"));
+        }
+    }
+
+    #[test]
+    fn test_tcp_transport_delivers_updates() {
+        let a = TcpTransport::bind("127.0.0.1:0").unwrap();
+        let b = TcpTransport::bind("127.0.0.1:0").unwrap();
+        a.add_peer(b.local_addr());
+
+        let a = node("a", a);
+        let b = node("b", b);
+
+        grant(&a, "write");
+        a.publish_facet("TEST001", "permissions").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !can(&b, "write") && Instant::now() < deadline {
+            b.sync().unwrap();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(can(&b, "write"));
+    }
+}
@@ -0,0 +1,244 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::core::FacetedObject;
+use crate::error::FacetError;
+use crate::summary::FacetSummary;
+
+// Structured report about one object: a heading, top-level fields and one
+// section per summarizable facet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+    pub sections: Vec<FacetSummary>,
+}
+
+impl Report {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Self::default()
+        }
+    }
+
+    // Report with a section for every summarizable facet on `object`
+    pub fn for_object(title: &str, object: &FacetedObject) -> Result<Self, FacetError> {
+        Ok(Self {
+            sections: object.summaries()?,
+            ..Self::new(title)
+        })
+    }
+
+    pub fn field(mut self, label: &str, value: impl core::fmt::Display) -> Self {
+        self.fields.push((label.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn section(mut self, summary: FacetSummary) -> Self {
+        self.sections.push(summary);
+        self
+    }
+}
+
+// Output format for reports. Each method appends to `out`.
+pub trait ReportFormatter {
+    fn header(&self, report: &Report, out: &mut String);
+    fn section(&self, summary: &FacetSummary, out: &mut String);
+
+    fn footer(&self, _report: &Report, _out: &mut String) {}
+}
+
+// Console text, one `Label: value` per line with indented facet sections
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainTextFormatter;
+
+impl ReportFormatter for PlainTextFormatter {
+    fn header(&self, report: &Report, out: &mut String) {
+        if !report.title.is_empty() {
+            let _ = writeln!(out, "{}", report.title);
+        }
+        for (label, value) in &report.fields {
+            let _ = writeln!(out, "{}: {}", label, value);
+        }
+    }
+
+    fn section(&self, summary: &FacetSummary, out: &mut String) {
+        let _ = write!(out, "{}", summary);
+    }
+}
+
+// GitHub-flavored Markdown with a field table per section
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownFormatter;
+
+impl MarkdownFormatter {
+    fn escape(text: &str) -> String {
+        text.replace('|', "\\|").replace('\n', " ")
+    }
+
+    fn table(fields: &[(String, String)], out: &mut String) {
+        if fields.is_empty() {
+            return;
+        }
+        out.push_str("| Field | Value |\n| --- | --- |\n");
+        for (label, value) in fields {
+            let _ = writeln!(out, "| {} | {} |", Self::escape(label), Self::escape(value));
+        }
+        out.push('\n');
+    }
+}
+
+impl ReportFormatter for MarkdownFormatter {
+    fn header(&self, report: &Report, out: &mut String) {
+        let _ = writeln!(out, "# {}\n", Self::escape(&report.title));
+        Self::table(&report.fields, out);
+    }
+
+    fn section(&self, summary: &FacetSummary, out: &mut String) {
+        let _ = writeln!(out, "## {}\n", Self::escape(&summary.title));
+        Self::table(&summary.fields, out);
+        if !summary.items.is_empty() {
+            for item in &summary.items {
+                let _ = writeln!(out, "- {}", Self::escape(item));
+            }
+            out.push('\n');
+        }
+    }
+}
+
+// HTML fragment with a table per section, suitable for embedding in a page
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmlFormatter;
+
+impl HtmlFormatter {
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    fn table(fields: &[(String, String)], out: &mut String) {
+        if fields.is_empty() {
+            return;
+        }
+        out.push_str("<table>\n");
+        for (label, value) in fields {
+            let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", Self::escape(label), Self::escape(value));
+        }
+        out.push_str("</table>\n");
+    }
+}
+
+impl ReportFormatter for HtmlFormatter {
+    fn header(&self, report: &Report, out: &mut String) {
+        let _ = writeln!(out, "<section class=\"report\">\n<h1>{}</h1>", Self::escape(&report.title));
+        Self::table(&report.fields, out);
+    }
+
+    fn section(&self, summary: &FacetSummary, out: &mut String) {
+        let _ = writeln!(out, "<h2>{}</h2>", Self::escape(&summary.title));
+        Self::table(&summary.fields, out);
+        if !summary.items.is_empty() {
+            out.push_str("<ul>\n");
+            for item in &summary.items {
+                let _ = writeln!(out, "<li>{}</li>", Self::escape(item));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+
+    fn footer(&self, _report: &Report, out: &mut String) {
+        out.push_str("</section>\n");
+    }
+}
+
+// Custom rendering for one facet's section, replacing the formatter's
+pub type SectionTemplate = Box<dyn Fn(&FacetSummary, &mut String) + Send + Sync>;
+
+// Renders reports with a formatter, letting individual facet sections be
+// overridden by title
+pub struct ReportRenderer<F> {
+    formatter: F,
+    templates: BTreeMap<String, SectionTemplate>,
+}
+
+impl<F: ReportFormatter> ReportRenderer<F> {
+    pub fn new(formatter: F) -> Self {
+        Self {
+            formatter,
+            templates: BTreeMap::new(),
+        }
+    }
+
+    // Use `template` for sections titled `facet_title`
+    pub fn with_template(
+        mut self,
+        facet_title: &str,
+        template: impl Fn(&FacetSummary, &mut String) + Send + Sync + 'static,
+    ) -> Self {
+        self.templates.insert(facet_title.to_string(), Box::new(template));
+        self
+    }
+
+    pub fn render(&self, report: &Report) -> String {
+        let mut out = String::new();
+        self.formatter.header(report, &mut out);
+        for section in &report.sections {
+            match self.templates.get(&section.title) {
+                Some(template) => template(section, &mut out),
+                None => self.formatter.section(section, &mut out),
+            }
+        }
+        self.formatter.footer(report, &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report::new("Employee <EMP001>")
+            .field("Department", "R&D")
+            .section(FacetSummary::new("Account").field("Balance", "$10.00"))
+            .section(FacetSummary::new("Audit").field("Entries", 1).item("deposit | 10"))
+    }
+
+    #[test]
+    fn test_markdown_and_html_escape_content() {
+        let markdown = ReportRenderer::new(MarkdownFormatter).render(&report());
+        assert!(markdown.starts_with("# Employee <EMP001>\n"));
+        assert!(markdown.contains("| Balance | $10.00 |"));
+        assert!(markdown.contains("- deposit \\| 10"));
+
+        let html = ReportRenderer::new(HtmlFormatter).render(&report());
+        assert!(html.contains("<h1>Employee &lt;EMP001&gt;</h1>"));
+        assert!(html.contains("<tr><th>Department</th><td>R&amp;D</td></tr>"));
+        assert!(html.trim_end().ends_with("</section>"));
+    }
+
+    #[test]
+    fn test_section_template_override() {
+        let text = ReportRenderer::new(PlainTextFormatter)
+            .with_template("Account", |summary, out| {
+                out.push_str(&format!("{} hidden\n", summary.title));
+            })
+            .render(&report());
+
+        assert_eq!(text, "Employee <EMP001>\nDepartment: R&D\nAccount hidden\nAudit:\n  Entries: 1\n  - deposit | 10\n");
+    }
+}
@@ -0,0 +1,165 @@
+use std::any::type_name;
+use std::fmt::Display;
+
+use crate::locks::{FacetLockRequest, LockedFacets};
+use crate::pipeline::{Audit, Authorize, Pipeline, Validate};
+use crate::{FacetError, FacetedObject};
+
+// A composite operation over several facets of one object, e.g. paying a
+// salary into an employee's account. The operation declares the facets and
+// permission it needs; OperationRunner checks them, locks the facets
+// together and audits the outcome, so operations don't repeat that.
+pub trait FacetOperation {
+    type Output: Display;
+
+    // Name used in permission errors, audit entries and traces
+    fn name(&self) -> &str;
+
+    // Facets `execute` reads or mutates; all stay locked while it runs
+    fn required_facets(&self) -> Vec<FacetLockRequest>;
+
+    // Permission the object's Authorizer must grant, if any
+    fn permission(&self) -> Option<&str> {
+        None
+    }
+
+    fn execute(self, facets: &mut LockedFacets<'_>) -> Result<Self::Output, FacetError>;
+}
+
+// Runs FacetOperations: fails with MissingFacets unless every required
+// facet is attached, then enforces the permission, locks the facets and
+// executes. Locking a facet is an access like any other, so the object's
+// interceptors (rate limits, auditing) and guards apply to it. Each run is timed as a "run" of the operation type with the
+// `metrics` feature, and its outcome, rejections included, is written to
+// the object's Auditable facet.
+#[derive(Debug, Clone)]
+pub struct OperationRunner {
+    audit: bool,
+}
+
+impl Default for OperationRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OperationRunner {
+    pub fn new() -> Self {
+        Self { audit: true }
+    }
+
+    pub fn without_audit(mut self) -> Self {
+        self.audit = false;
+        self
+    }
+
+    pub fn run<O: FacetOperation>(&self, object: &FacetedObject, operation: O) -> Result<O::Output, FacetError> {
+        let name = operation.name().to_string();
+        let requests = operation.required_facets();
+        let mut pipeline = Pipeline::new(&name);
+        if self.audit {
+            pipeline = pipeline.stage(Audit::new());
+        }
+        let required = requests.clone();
+        pipeline = pipeline.stage(Validate::new("dependencies", move |ctx| {
+            let attached = ctx.object.facet_type_ids();
+            let type_names: Vec<&'static str> = required.iter()
+                .filter(|request| !attached.contains(&request.type_id))
+                .map(|request| request.type_name)
+                .collect();
+            if type_names.is_empty() {
+                Ok(())
+            } else {
+                Err(FacetError::MissingFacets { type_names })
+            }
+        }));
+        if let Some(permission) = operation.permission() {
+            pipeline = pipeline.stage(Authorize::new(permission));
+        }
+
+        object.instrumented("run", type_name::<O>(), &name, || {
+            pipeline.run(object, |object| operation.execute(&mut object.lock_facets(&requests)?.by_type()))
+        })
+    }
+}
+
+impl FacetedObject {
+    // Run `operation` with the default OperationRunner
+    pub fn run_operation<O: FacetOperation>(&self, operation: O) -> Result<O::Output, FacetError> {
+        OperationRunner::new().run(self, operation)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet, RateLimitInterceptor, RateLimiterFacet};
+
+    fn employee_as(role: &str) -> FacetedObject {
+        FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .preset(&Employee::standard_preset())
+            .with(PermissionFacet::new(role))
+            .build()
+            .unwrap()
+    }
+
+    // Pays `amount` into the account, recording the payer's role
+    struct PaySalary {
+        amount: Money,
+    }
+
+    impl FacetOperation for PaySalary {
+        type Output = String;
+
+        fn name(&self) -> &str {
+            "pay_salary"
+        }
+
+        fn required_facets(&self) -> Vec<FacetLockRequest> {
+            vec![FacetLockRequest::read::<PermissionFacet>(), FacetLockRequest::write::<AccountFacet>()]
+        }
+
+        fn permission(&self) -> Option<&str> {
+            Some("financial_operations")
+        }
+
+        fn execute(self, facets: &mut LockedFacets<'_>) -> Result<String, FacetError> {
+            let role = facets.get::<PermissionFacet>()?.get_role().to_string();
+            let balance = facets.get_mut::<AccountFacet>()?.deposit(self.amount)?;
+            Ok(format!("Paid {} as {}, new balance {}", self.amount, role, balance))
+        }
+    }
+
+    #[test]
+    fn test_runner_checks_then_executes() {
+        let employee = employee_as("manager");
+        employee.detach_facet::<AccountFacet>().unwrap();
+        let missing = employee.run_operation(PaySalary { amount: Money::usd(100) });
+        assert_eq!(missing, Err(FacetError::MissingFacets { type_names: vec![type_name::<AccountFacet>()] }));
+
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        let paid = employee.run_operation(PaySalary { amount: Money::usd(100) }).unwrap();
+        assert_eq!(paid, "Paid 100.00 USD as manager, new balance 100.00 USD");
+        let trail = employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().to_vec()).unwrap();
+        assert_eq!(trail.len(), 2);
+        assert!(trail[1].details.starts_with("Paid 100.00 USD"));
+    }
+
+    #[test]
+    fn test_runner_enforces_permission_and_interceptors() {
+        let employee = employee_as("employee");
+        let denied = OperationRunner::new().without_audit().run(&employee, PaySalary { amount: Money::usd(100) });
+        assert!(matches!(denied, Err(FacetError::PermissionDenied { ref permission, .. }) if permission == "financial_operations"));
+        assert_eq!(employee.facet_ref::<AccountFacet>().unwrap().get_balance(), Money::usd(0));
+        assert!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().is_empty()).unwrap());
+
+        // Locked facets count as accesses, so a rate limit applies too
+        let manager = employee_as("manager");
+        manager.attach_facet(RateLimiterFacet::per_minute(1)).unwrap();
+        manager.add_interceptor(RateLimitInterceptor::new().limit::<AccountFacet>()).unwrap();
+        manager.run_operation(PaySalary { amount: Money::usd(100) }).unwrap();
+        let limited = manager.run_operation(PaySalary { amount: Money::usd(100) });
+        assert!(matches!(limited, Err(FacetError::RateLimited { .. })));
+        assert_eq!(manager.facet_ref::<AccountFacet>().unwrap().get_balance(), Money::usd(100));
+    }
+}
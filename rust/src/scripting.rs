@@ -0,0 +1,304 @@
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
+use serde_json::Value;
+
+use crate::command::CommandBus;
+use crate::{AccountFacet, AuditFacet, FacetError, FacetedObject, Money, PermissionFacet};
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+// Facet errors surface in scripts as runtime errors carrying their message
+fn script_error(error: FacetError) -> Box<EvalAltResult> {
+    error.to_string().into()
+}
+
+// Handle to a registry object as seen from a script. Reads go straight to
+// the facets; anything that mutates state is routed through the CommandBus
+// so scripts get the same permission checks and auditing as other callers.
+#[derive(Clone)]
+pub struct ScriptObject {
+    id: String,
+    object: Arc<FacetedObject>,
+    bus: Arc<CommandBus>,
+}
+
+impl ScriptObject {
+    fn has_facet(&mut self, name: &str) -> ScriptResult<bool> {
+        match name {
+            "account" => Ok(self.object.has_facet::<AccountFacet>()),
+            "permissions" => Ok(self.object.has_facet::<PermissionFacet>()),
+            "audit" => Ok(self.object.has_facet::<AuditFacet>()),
+            _ => Err(format!("Unknown facet '{}'", name).into()),
+        }
+    }
+
+    fn balance(&mut self) -> ScriptResult<f64> {
+        self.object.with_facet::<AccountFacet, _>(|account| account.get_balance().to_f64()).map_err(script_error)
+    }
+
+    fn role(&mut self) -> ScriptResult<String> {
+        self.object.with_facet::<PermissionFacet, _>(|permissions| permissions.get_role().to_string()).map_err(script_error)
+    }
+
+    fn has_permission(&mut self, permission: &str) -> bool {
+        self.object.with_facet::<PermissionFacet, _>(|permissions| permissions.has_permission(permission))
+            .unwrap_or(false)
+    }
+
+    fn log(&mut self, operation: &str, details: &str) -> ScriptResult<()> {
+        let mut params = Map::new();
+        params.insert("operation".into(), operation.into());
+        params.insert("details".into(), details.into());
+        self.dispatch("log", params).map(|_| ())
+    }
+
+    fn dispatch(&mut self, command: &str, params: Map) -> ScriptResult<Dynamic> {
+        let params: Value = rhai::serde::from_dynamic(&params.into())?;
+        let result = self.bus.dispatch(&self.id, command, params).map_err(script_error)?;
+        rhai::serde::to_dynamic(result)
+    }
+
+    // Scripts only have floats, so amounts and balances cross as major units
+    fn amount_command(&mut self, command: &str, amount: f64) -> ScriptResult<f64> {
+        let mut params = Map::new();
+        params.insert("amount".into(), amount.into());
+        let result = self.dispatch(command, params)?;
+        result.try_cast::<Map>()
+            .and_then(|map| map.get("balance").and_then(|balance| rhai::serde::from_dynamic::<Money>(balance).ok()))
+            .map(|balance| balance.to_f64())
+            .ok_or_else(|| format!("Command '{}' did not return a balance", command).into())
+    }
+}
+
+// A facet of a script object, reached as a property such as `obj.account`
+#[derive(Clone)]
+pub struct ScriptFacet {
+    object: ScriptObject,
+    name: &'static str,
+}
+
+impl ScriptFacet {
+    // The object, if this is the facet the called method belongs to
+    fn on(&mut self, name: &str, method: &str) -> ScriptResult<&mut ScriptObject> {
+        if self.name == name {
+            Ok(&mut self.object)
+        } else {
+            Err(format!("Facet '{}' has no method '{}'", self.name, method).into())
+        }
+    }
+}
+
+impl ScriptObject {
+    fn facet(&mut self, name: &'static str) -> ScriptResult<ScriptFacet> {
+        if self.has_facet(name)? {
+            Ok(ScriptFacet { object: self.clone(), name })
+        } else {
+            Err(format!("Facet '{}' is not attached", name).into())
+        }
+    }
+}
+
+// Sandboxing applied to every script run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptLimits {
+    // Wall-clock time a single run may take
+    pub timeout: Duration,
+    // Operations (roughly, evaluated expressions) a single run may take;
+    // 0 for no limit
+    pub max_operations: u64,
+    pub max_call_levels: usize,
+    pub max_string_size: usize,
+    pub max_array_size: usize,
+    pub max_map_size: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(1),
+            max_operations: 1_000_000,
+            max_call_levels: 32,
+            max_string_size: 64 * 1024,
+            max_array_size: 10_000,
+            max_map_size: 10_000,
+        }
+    }
+}
+
+thread_local! {
+    // Deadline of the run evaluating on this thread, checked on progress
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+// Runs rhai scripts against registry objects. Only the whitelisted facet
+// methods registered here are callable; `obj` is bound to the target object.
+// Runs are limited by ScriptLimits::default() unless set otherwise.
+pub struct ScriptEngine {
+    engine: Engine,
+    bus: Arc<CommandBus>,
+    limits: ScriptLimits,
+}
+
+impl ScriptEngine {
+    pub fn new(bus: Arc<CommandBus>) -> Self {
+        let mut engine = Engine::new();
+        engine.on_progress(|_| {
+            let expired = DEADLINE.with(|deadline| deadline.get().is_some_and(|deadline| Instant::now() >= deadline));
+            expired.then(|| Dynamic::from("timeout"))
+        });
+
+        engine.register_type_with_name::<ScriptObject>("FacetedObject")
+            .register_get("id", |obj: &mut ScriptObject| obj.id.clone())
+            .register_get("account", |obj: &mut ScriptObject| obj.facet("account"))
+            .register_get("permissions", |obj: &mut ScriptObject| obj.facet("permissions"))
+            .register_get("audit", |obj: &mut ScriptObject| obj.facet("audit"))
+            .register_fn("has", ScriptObject::has_facet)
+            .register_fn("has_facet", ScriptObject::has_facet)
+            .register_fn("balance", ScriptObject::balance)
+            .register_fn("role", ScriptObject::role)
+            .register_fn("has_permission", ScriptObject::has_permission)
+            .register_fn("log", ScriptObject::log)
+            .register_fn("dispatch", ScriptObject::dispatch)
+            .register_fn("deposit", |obj: &mut ScriptObject, amount: f64| obj.amount_command("deposit", amount))
+            .register_fn("deposit", |obj: &mut ScriptObject, amount: i64| obj.amount_command("deposit", amount as f64))
+            .register_fn("withdraw", |obj: &mut ScriptObject, amount: f64| obj.amount_command("withdraw", amount))
+            .register_fn("withdraw", |obj: &mut ScriptObject, amount: i64| obj.amount_command("withdraw", amount as f64));
+
+        // Facet properties expose the methods of the facet they stand for
+        engine.register_type_with_name::<ScriptFacet>("Facet")
+            .register_fn("balance", |facet: &mut ScriptFacet| facet.on("account", "balance")?.balance())
+            .register_fn("deposit", |facet: &mut ScriptFacet, amount: f64| facet.on("account", "deposit")?.amount_command("deposit", amount))
+            .register_fn("deposit", |facet: &mut ScriptFacet, amount: i64| facet.on("account", "deposit")?.amount_command("deposit", amount as f64))
+            .register_fn("withdraw", |facet: &mut ScriptFacet, amount: f64| facet.on("account", "withdraw")?.amount_command("withdraw", amount))
+            .register_fn("withdraw", |facet: &mut ScriptFacet, amount: i64| facet.on("account", "withdraw")?.amount_command("withdraw", amount as f64))
+            .register_fn("role", |facet: &mut ScriptFacet| facet.on("permissions", "role")?.role())
+            .register_fn("has_permission", |facet: &mut ScriptFacet, permission: &str| -> ScriptResult<bool> {
+                Ok(facet.on("permissions", "has_permission")?.has_permission(permission))
+            })
+            .register_fn("log", |facet: &mut ScriptFacet, operation: &str, details: &str| facet.on("audit", "log")?.log(operation, details));
+
+        let mut engine = Self { engine, bus, limits: ScriptLimits::default() };
+        engine.apply_limits();
+        engine
+    }
+
+    pub fn with_limits(mut self, limits: ScriptLimits) -> Self {
+        self.limits = limits;
+        self.apply_limits();
+        self
+    }
+
+    fn apply_limits(&mut self) {
+        self.engine.set_max_operations(self.limits.max_operations)
+            .set_max_call_levels(self.limits.max_call_levels)
+            .set_max_string_size(self.limits.max_string_size)
+            .set_max_array_size(self.limits.max_array_size)
+            .set_max_map_size(self.limits.max_map_size);
+    }
+
+    // Direct access for registering additional whitelisted functions
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    // Evaluate a script with `obj` bound to the given object
+    pub fn run(&self, object_id: &str, script: &str) -> Result<Value, FacetError> {
+        let object = self.bus.objects().get(object_id)
+            .ok_or_else(|| FacetError::UnknownObject { id: object_id.to_string() })?;
+
+        let mut scope = Scope::new();
+        scope.push("obj", ScriptObject {
+            id: object_id.to_string(),
+            object,
+            bus: Arc::clone(&self.bus),
+        });
+
+        let previous = DEADLINE.with(|deadline| deadline.replace(Some(Instant::now() + self.limits.timeout)));
+        let result = self.engine.eval_with_scope::<Dynamic>(&mut scope, script);
+        DEADLINE.with(|deadline| deadline.set(previous));
+
+        let result = result.map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => {
+                FacetError::Other(format!("Script exceeded its time limit of {:?}", self.limits.timeout))
+            }
+            e => FacetError::Other(format!("Script failed: {}", e)),
+        })?;
+        rhai::serde::from_dynamic(&result)
+            .map_err(|e| FacetError::Other(format!("Script returned an unsupported value: {}", e)))
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::registry::ObjectRegistry;
+    use crate::Employee;
+    use serde_json::json;
+
+    fn engine_with_employee(role: &str) -> ScriptEngine {
+        let objects = Arc::new(ObjectRegistry::new());
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new(role)).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        objects.insert("TEST001", employee).unwrap();
+
+        let bus = Arc::new(CommandBus::new(objects));
+        bus.register_builtin_commands().unwrap();
+        ScriptEngine::new(bus)
+    }
+
+    #[test]
+    fn test_script_composes_operations() {
+        let engine = engine_with_employee("manager");
+        let script = r#"
+            obj.deposit(1000);
+            if obj.balance() > 500.0 {
+                obj.withdraw(250.0);
+            }
+            obj.log("bonus_check", "balance reviewed");
+            obj.balance()
+        "#;
+
+        assert_eq!(engine.run("TEST001", script).unwrap(), json!(750.0));
+
+        // Logging goes through the bus, which doesn't add its own entry
+        let object = engine.bus.objects().get("TEST001").unwrap();
+        let trail = object.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().to_vec()).unwrap();
+        assert_eq!(trail.iter().filter(|entry| entry.operation == "bonus_check").count(), 1);
+        assert!(trail.iter().all(|entry| entry.operation != "log"));
+    }
+
+    #[test]
+    fn test_script_respects_permissions() {
+        let engine = engine_with_employee("employee");
+
+        assert!(engine.run("TEST001", "obj.deposit(10.0)").is_err());
+        assert!(engine.run("TEST001", r#"obj.log("note", "unchecked")"#).is_err());
+        assert_eq!(engine.run("TEST001", r#"obj.has_permission("read")"#).unwrap(), json!(true));
+        assert!(engine.run("TEST001", r#"obj.has_facet("payroll")"#).is_err());
+    }
+
+    #[test]
+    fn test_facet_properties_and_limits() {
+        let engine = engine_with_employee("manager");
+        let script = r#"
+            if obj.has("permissions") && obj.permissions.has_permission("write") {
+                obj.account.deposit(100.0)
+            }
+        "#;
+        assert_eq!(engine.run("TEST001", script).unwrap(), json!(100.0));
+        assert!(engine.run("TEST001", "obj.audit.deposit(5)").is_err());
+
+        let engine = engine.with_limits(ScriptLimits { timeout: Duration::from_millis(50), max_operations: 0, ..ScriptLimits::default() });
+        let error = engine.run("TEST001", "loop { obj.balance(); }").unwrap_err();
+        assert!(error.to_string().contains("time limit"), "{}", error);
+
+        let engine = engine.with_limits(ScriptLimits { max_operations: 1_000, ..ScriptLimits::default() });
+        assert!(engine.run("TEST001", "let x = 0; loop { x += 1; }").is_err());
+        assert_eq!(engine.run("TEST001", "obj.balance()").unwrap(), json!(100.0));
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::command::CommandBus;
+use crate::registry::{FacetRegistry, ObjectRegistry};
+use crate::world::{EntityId, FacetWorld};
+use crate::{FacetError, FacetedObject, WriteLimits};
+
+// REST facade over a FacetWorld, so several clients can drive the same
+// objects:
+//
+//   POST /objects                            {"core": .., "facets": ["account:ACC001"]}
+//   POST /objects/{id}/facets                {"spec": "audit"} or {"spec": "account", "state": ..}
+//   POST /objects/{id}/operations/{command}  command parameters, e.g. {"amount": 25}
+//   GET  /objects/{id}/summary
+//
+// Facets are built by the FacetRegistry and operations run through the
+// CommandBus, so permissions are checked and operations audited as for
+// any other dispatch. Requests run concurrently; each facet access locks
+// only that facet, and objects created with write limits answer 503 once
+// too many writes queue up.
+pub struct FacetServer {
+    world: FacetWorld,
+    facets: FacetRegistry,
+    commands: CommandBus,
+    write_limits: Option<WriteLimits>,
+}
+
+impl FacetServer {
+    // Server with the built-in facets and commands
+    pub fn new() -> Result<Self, FacetError> {
+        let commands = CommandBus::new(Arc::new(ObjectRegistry::new()));
+        commands.register_builtin_commands()?;
+        Ok(Self::with(FacetRegistry::builtin(), commands))
+    }
+
+    // Server building facets from `facets` and running `commands`; the
+    // bus's own object registry is not used
+    pub fn with(facets: FacetRegistry, commands: CommandBus) -> Self {
+        Self { world: FacetWorld::new(), facets, commands, write_limits: None }
+    }
+
+    // Write limits for every object created from now on
+    pub fn with_write_limits(mut self, limits: WriteLimits) -> Self {
+        self.write_limits = Some(limits);
+        self
+    }
+
+    pub fn world(&self) -> &FacetWorld {
+        &self.world
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/objects", post(create_object))
+            .route("/objects/{id}/facets", post(attach_facet))
+            .route("/objects/{id}/operations/{command}", post(invoke))
+            .route("/objects/{id}/summary", get(summary))
+            .with_state(Arc::new(self))
+    }
+
+    // Serve on `listener` until the task is dropped
+    pub async fn serve(self, listener: tokio::net::TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.router()).await
+    }
+
+    fn object(&self, id: EntityId) -> Result<Arc<FacetedObject>, ApiError> {
+        self.world.get(id).ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("Object {} not found", id)))
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateObject {
+    #[serde(default)]
+    core: Value,
+    #[serde(default)]
+    facets: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AttachFacet {
+    spec: String,
+    // State to restore the facet registered as `spec` from, instead of
+    // building it from the spec
+    state: Option<Value>,
+}
+
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: String) -> Self {
+        Self { status, message }
+    }
+}
+
+impl From<FacetError> for ApiError {
+    fn from(error: FacetError) -> Self {
+        let status = match error {
+            FacetError::Busy | FacetError::LockTimeout { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            FacetError::PermissionDenied { .. } => StatusCode::FORBIDDEN,
+            FacetError::AlreadyAttached { .. } => StatusCode::CONFLICT,
+            FacetError::NotFound { .. }
+            | FacetError::UnknownFacet { .. }
+            | FacetError::UnknownObject { .. }
+            | FacetError::UnknownCommand { .. } => StatusCode::NOT_FOUND,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+async fn create_object(
+    State(server): State<Arc<FacetServer>>,
+    Json(request): Json<CreateObject>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let facets = request.facets.iter()
+        .map(|spec| server.facets.create(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut object = FacetedObject::new(request.core);
+    if let Some(limits) = server.write_limits {
+        object = object.with_write_limits(limits);
+    }
+    object.attach_facets_ordered(facets)?;
+    let id = server.world.insert(object)?;
+    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+}
+
+async fn attach_facet(
+    State(server): State<Arc<FacetServer>>,
+    Path(id): Path<EntityId>,
+    Json(request): Json<AttachFacet>,
+) -> Result<StatusCode, ApiError> {
+    let object = server.object(id)?;
+    let facet = match request.state {
+        Some(state) => server.facets.deserialize(&request.spec, state)?,
+        None => server.facets.create(&request.spec)?,
+    };
+    object.attach_facets_ordered(vec![facet])?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn invoke(
+    State(server): State<Arc<FacetServer>>,
+    Path((id, command)): Path<(EntityId, String)>,
+    params: Option<Json<Value>>,
+) -> Result<Json<Value>, ApiError> {
+    let object = server.object(id)?;
+    // Report a denial before any complaint about the parameters
+    server.commands.check_permission(&object, &command)?;
+
+    let params = match params {
+        Some(Json(params)) if !params.is_null() => params,
+        _ => json!({}),
+    };
+    Ok(Json(server.commands.execute(&object, &command, params)?))
+}
+
+async fn summary(
+    State(server): State<Arc<FacetServer>>,
+    Path(id): Path<EntityId>,
+) -> Result<Json<Value>, ApiError> {
+    let object = server.object(id)?;
+    Ok(Json(json!({ "id": id, "facets": object.describe()?, "summaries": object.summaries()? })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn call(router: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_create_attach_invoke_summarize() {
+        let router = FacetServer::new().unwrap().router();
+        let (status, created) = call(&router, "POST", "/objects", json!({
+            "core": {"name": "Test User"},
+            "facets": ["account:ACC001", "permissions:employee"],
+        })).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = created["id"].as_u64().unwrap();
+
+        let (status, body) = call(&router, "POST", &format!("/objects/{}/operations/deposit", id), json!({"amount": 25})).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+        let (status, _) = call(&router, "POST", &format!("/objects/{}/facets", id), json!({"spec": "audit"})).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&router, "POST", &format!("/objects/{}/facets", id), json!({"spec": "audit"})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call(&router, "POST", &format!("/objects/{}/operations/grant", id), json!({"permission": "write"})).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = call(&router, "POST", "/objects/99/operations/withdraw", json!({"amount": 1})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(&router, "POST", &format!("/objects/{}/operations/transfer", id), json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, summary) = call(&router, "GET", &format!("/objects/{}/summary", id), Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        let facets: Vec<&Value> = summary["facets"].as_array().unwrap().iter().map(|facet| &facet["metadata"]["name"]).collect();
+        assert_eq!(facets, [&json!("account"), &json!("permissions"), &json!("audit")]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_clients() {
+        let router = FacetServer::new().unwrap().router();
+        let (_, created) = call(&router, "POST", "/objects", json!({"facets": ["account:ACC001", "permissions:manager", "audit"]})).await;
+        let id = created["id"].as_u64().unwrap();
+
+        let clients: Vec<_> = (0..20).map(|_| {
+            let router = router.clone();
+            tokio::spawn(async move {
+                call(&router, "POST", &format!("/objects/{}/operations/deposit", id), json!({"amount": 1.5})).await.0
+            })
+        }).collect();
+        for client in clients {
+            assert_eq!(client.await.unwrap(), StatusCode::OK);
+        }
+
+        let (_, balance) = call(&router, "POST", &format!("/objects/{}/operations/balance", id), Value::Null).await;
+        assert_eq!(balance["balance"]["minor"], 3_000);
+        let (status, _) = call(&router, "POST", &format!("/objects/{}/operations/grant", id), json!({"permission": "delete"})).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = call(&router, "POST", &format!("/objects/{}/operations/withdraw", id), json!({"amount": 100})).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body["error"].as_str().unwrap().contains("Insufficient funds"));
+    }
+}
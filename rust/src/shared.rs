@@ -0,0 +1,121 @@
+use alloc::sync::{Arc, Weak};
+use core::ops::Deref;
+
+use crate::core::FacetedObject;
+
+// Cloneable handle to one faceted object, for handing the same object to
+// several threads or subsystems. Derefs to FacetedObject, so the whole
+// with_facet API is available on the handle.
+#[derive(Clone)]
+pub struct SharedFacetedObject {
+    object: Arc<FacetedObject>,
+}
+
+impl SharedFacetedObject {
+    pub fn new(object: FacetedObject) -> Self {
+        Self { object: Arc::new(object) }
+    }
+
+    // Handle that does not keep the object alive, e.g. for caches and
+    // back-references that must not form cycles
+    pub fn downgrade(&self) -> WeakFacetedObject {
+        WeakFacetedObject { object: Arc::downgrade(&self.object) }
+    }
+
+    // Handles (not counting weak ones) currently sharing the object
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.object)
+    }
+
+    // Whether both handles refer to the same object
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.object, &other.object)
+    }
+
+    pub fn as_arc(&self) -> &Arc<FacetedObject> {
+        &self.object
+    }
+
+    pub fn into_arc(self) -> Arc<FacetedObject> {
+        self.object
+    }
+}
+
+impl Deref for SharedFacetedObject {
+    type Target = FacetedObject;
+
+    fn deref(&self) -> &FacetedObject {
+        &self.object
+    }
+}
+
+impl From<FacetedObject> for SharedFacetedObject {
+    fn from(object: FacetedObject) -> Self {
+        Self::new(object)
+    }
+}
+
+impl From<Arc<FacetedObject>> for SharedFacetedObject {
+    fn from(object: Arc<FacetedObject>) -> Self {
+        Self { object }
+    }
+}
+
+// Weak handle from SharedFacetedObject::downgrade
+#[derive(Clone, Default)]
+pub struct WeakFacetedObject {
+    object: Weak<FacetedObject>,
+}
+
+impl WeakFacetedObject {
+    // None once every strong handle has been dropped
+    pub fn upgrade(&self) -> Option<SharedFacetedObject> {
+        self.object.upgrade().map(|object| SharedFacetedObject { object })
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Employee, Money};
+
+    fn employee() -> SharedFacetedObject {
+        let employee = SharedFacetedObject::new(FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering")));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee
+    }
+
+    #[test]
+    fn test_handles_share_one_object_across_threads() {
+        let employee = employee();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = employee.clone();
+                std::thread::spawn(move || {
+                    handle.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(25))).unwrap().unwrap();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(100));
+        assert_eq!(employee.handle_count(), 1);
+    }
+
+    #[test]
+    fn test_weak_handle_does_not_keep_object_alive() {
+        let employee = employee();
+        let weak = employee.downgrade();
+
+        let upgraded = weak.upgrade().unwrap();
+        assert!(upgraded.ptr_eq(&employee));
+        assert_eq!(upgraded.get_core::<Employee>().unwrap().name, "Test User");
+
+        drop(upgraded);
+        drop(employee);
+        assert!(weak.upgrade().is_none());
+    }
+}
@@ -0,0 +1,271 @@
+// Persisting faceted objects. A snapshot holds the serialized core object
+// and every attached facet that supports serialization, in attach order;
+// restoring rebuilds the facets by name through a FacetRegistry.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clock::Timestamp;
+use crate::core::DEFAULT_INSTANCE;
+use crate::registry::FacetRegistry;
+use crate::{Facet, FacetError, FacetedObject};
+
+// Facet whose state can be saved in a snapshot. Any serde-serializable
+// facet qualifies; it opts in by returning Some(self) from
+// Facet::as_serializable (or #[facet(serialize)] on the derive) and is
+// restored by the name it is registered under.
+pub trait SerializableFacet: Facet {
+    fn to_json(&self) -> Result<Value, FacetError>;
+}
+
+impl<F: Facet + Serialize> SerializableFacet for F {
+    fn to_json(&self) -> Result<Value, FacetError> {
+        serde_json::to_value(self)
+            .map_err(|e| FacetError::Invalid(format!("Cannot serialize facet '{}': {}", self.facet_name(), e)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedFacet {
+    pub name: String,
+    // Instance the facet was attached as; left out for the default one
+    #[serde(default = "default_instance", skip_serializing_if = "is_default_instance")]
+    pub instance: String,
+    // Facet::schema_version when saved; snapshots from before versioning
+    // count as version 1
+    #[serde(default = "first_version")]
+    pub version: u32,
+    // When the instance's TTL runs out; restored instances keep the same
+    // deadline, so one that expired meanwhile is absent after restore
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
+    pub state: Value,
+}
+
+impl SerializedFacet {
+    // State of the default instance of facet `name`, without a TTL
+    pub fn new(name: &str, version: u32, state: Value) -> Self {
+        Self { name: name.to_string(), instance: default_instance(), version, expires_at: None, state }
+    }
+}
+
+fn default_instance() -> String {
+    DEFAULT_INSTANCE.to_string()
+}
+
+fn is_default_instance(instance: &str) -> bool {
+    instance == DEFAULT_INSTANCE
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+// Upgrades the serialized state of one facet from `source_version` to the
+// next version. Registered per facet name with FacetRegistry::migration;
+// restore chains them until no migration for the payload's version is left.
+pub trait FacetMigration: Send + Sync {
+    fn source_version(&self) -> u32;
+
+    fn migrate(&self, state: Value) -> Result<Value, FacetError>;
+}
+
+// Facets that do not support serialization are left out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetedSnapshot {
+    pub core: Value,
+    pub facets: Vec<SerializedFacet>,
+}
+
+impl FacetedSnapshot {
+    pub fn facet(&self, name: &str) -> Option<&Value> {
+        self.facets.iter()
+            .find(|facet| facet.name == name)
+            .map(|facet| &facet.state)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, FacetError> {
+        serde_json::from_str(json).map_err(|e| FacetError::Invalid(format!("Invalid snapshot: {}", e)))
+    }
+}
+
+impl FacetedObject {
+    // Serialize the core, which must be a C, and all serializable facets
+    pub fn snapshot<C: Serialize + 'static>(&self) -> Result<FacetedSnapshot, FacetError> {
+        let core = self.get_core::<C>()
+            .ok_or(FacetError::CoreTypeMismatch { type_name: core::any::type_name::<C>() })?;
+        let core = serde_json::to_value(&*core)
+            .map_err(|e| FacetError::Invalid(format!("Cannot serialize core object: {}", e)))?;
+
+        let mut facets = Vec::new();
+        for (type_id, instance, cell) in self.cells_in_order() {
+            let slot = cell.read();
+            let Some(serializable) = slot.as_deref().and_then(Facet::as_serializable) else {
+                continue;
+            };
+            facets.push(SerializedFacet {
+                name: serializable.facet_name().to_string(),
+                version: serializable.schema_version(),
+                expires_at: self.instance_expiry(type_id, &instance),
+                instance,
+                state: serializable.to_json()?,
+            });
+        }
+
+        Ok(FacetedSnapshot { core, facets })
+    }
+
+    // Rebuild an object from a snapshot. Every facet in it must be
+    // registered under its saved name; older versions are migrated first.
+    pub fn restore<C>(snapshot: &FacetedSnapshot, registry: &FacetRegistry) -> Result<FacetedObject, FacetError>
+    where
+        C: DeserializeOwned + Send + Sync + 'static,
+    {
+        let core: C = serde_json::from_value(snapshot.core.clone())
+            .map_err(|e| FacetError::Invalid(format!("Invalid core object: {}", e)))?;
+        let facets = snapshot.facets.iter()
+            .map(|facet| {
+                let state = registry.migrate(&facet.name, facet.version, facet.state.clone())?;
+                Ok((facet.instance.clone(), registry.deserialize(&facet.name, state)?, facet.expires_at))
+            })
+            .collect::<Result<Vec<_>, FacetError>>()?;
+
+        let object = FacetedObject::new(core);
+        object.attach_instances_ordered(facets)?;
+        Ok(object)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet};
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(250))).unwrap().unwrap();
+        employee.with_facet_mut::<AuditFacet, _>(|audit| audit.log_operation("deposit", "250")).unwrap();
+
+        let json = employee.snapshot::<Employee>().unwrap().to_json();
+        let restored = FacetedObject::restore::<Employee>(&FacetedSnapshot::from_json(&json).unwrap(), &FacetRegistry::builtin()).unwrap();
+
+        assert_eq!(restored.get_core::<Employee>().unwrap().id, "TEST001");
+        assert_eq!(restored.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(250));
+        assert!(restored.with_facet::<PermissionFacet, _>(|permissions| permissions.has_permission("financial_operations")).unwrap());
+        assert_eq!(restored.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 1);
+        assert_eq!(restored.snapshot::<Employee>().unwrap().to_json(), json);
+    }
+
+    #[test]
+    fn test_named_instances_and_ttls_round_trip() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_named_facet("savings", AccountFacet::new("SAV001")).unwrap();
+        employee.attach_facet_with_ttl(PermissionFacet::new("admin"), std::time::Duration::from_secs(3600)).unwrap();
+        employee.with_named_facet_mut::<AccountFacet, _>("savings", |account| account.deposit(Money::usd(40))).unwrap().unwrap();
+
+        let snapshot = FacetedSnapshot::from_json(&employee.snapshot::<Employee>().unwrap().to_json()).unwrap();
+        assert_eq!(snapshot.facets.iter().map(|facet| facet.instance.as_str()).collect::<Vec<_>>(), ["default", "savings", "default"]);
+        let restored = FacetedObject::restore::<Employee>(&snapshot, &FacetRegistry::builtin()).unwrap();
+
+        let savings = restored.with_named_facet::<AccountFacet, _>("savings", |account| (account.get_account_number().to_string(), account.get_balance()));
+        assert_eq!(savings.unwrap(), ("SAV001".to_string(), Money::usd(40)));
+        assert_eq!(restored.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(0));
+        let permissions = core::any::TypeId::of::<PermissionFacet>();
+        assert!(restored.instance_expiry(permissions, "default").is_some());
+        assert_eq!(restored.instance_expiry(permissions, "default"), employee.instance_expiry(permissions, "default"));
+    }
+
+    #[test]
+    fn test_restore_rejects_unregistered_facets() {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        let snapshot = employee.snapshot::<Employee>().unwrap();
+
+        assert_eq!(
+            FacetedObject::restore::<Employee>(&snapshot, &FacetRegistry::new()).err(),
+            Some(FacetError::UnknownFacet { name: "account".to_string() }),
+        );
+        assert!(employee.snapshot::<String>().is_err());
+    }
+
+    // Version 3 of a contact card: v1 kept one "name", v2 split it into
+    // first and last, v3 added an optional email
+    #[derive(Debug, crate::Facet, Serialize, Deserialize)]
+    #[facet(name = "contact", serialize, version = 3)]
+    struct Contact {
+        first: String,
+        last: String,
+        email: Option<String>,
+    }
+
+    struct SplitName;
+
+    impl FacetMigration for SplitName {
+        fn source_version(&self) -> u32 {
+            1
+        }
+
+        fn migrate(&self, state: Value) -> Result<Value, FacetError> {
+            let name = state["name"].as_str().ok_or_else(|| FacetError::Invalid("missing name".to_string()))?;
+            let (first, last) = name.split_once(' ').unwrap_or((name, ""));
+            Ok(serde_json::json!({ "first": first, "last": last }))
+        }
+    }
+
+    struct AddEmail;
+
+    impl FacetMigration for AddEmail {
+        fn source_version(&self) -> u32 {
+            2
+        }
+
+        fn migrate(&self, mut state: Value) -> Result<Value, FacetError> {
+            state["email"] = Value::Null;
+            Ok(state)
+        }
+    }
+
+    #[test]
+    fn test_restore_migrates_old_versions() {
+        let registry = FacetRegistry::new()
+            .register_serializable::<Contact>("contact")
+            .migration("contact", AddEmail)
+            .migration("contact", SplitName);
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        let core = employee.snapshot::<Employee>().unwrap().core;
+
+        let saved = |version, state| FacetedSnapshot {
+            core: core.clone(),
+            facets: vec![SerializedFacet::new("contact", version, state)],
+        };
+        let contact = |snapshot: &FacetedSnapshot| {
+            FacetedObject::restore::<Employee>(snapshot, &registry).unwrap()
+                .with_facet::<Contact, _>(|contact| (contact.first.clone(), contact.last.clone(), contact.email.clone()))
+                .unwrap()
+        };
+
+        let expected = ("Test".to_string(), "User".to_string(), None);
+        assert_eq!(contact(&saved(1, serde_json::json!({ "name": "Test User" }))), expected);
+        assert_eq!(contact(&saved(2, serde_json::json!({ "first": "Test", "last": "User" }))), expected);
+
+        // Snapshots from before versioning carry no version and count as v1
+        let unversioned = r#"{"core": null, "facets": [{"name": "contact", "state": {"name": "Test User"}}]}"#;
+        let mut unversioned = FacetedSnapshot::from_json(unversioned).unwrap();
+        unversioned.core = core.clone();
+        assert_eq!(contact(&unversioned), expected);
+
+        let current = FacetedObject::restore::<Employee>(&saved(1, serde_json::json!({ "name": "Test User" })), &registry).unwrap();
+        assert_eq!(current.snapshot::<Employee>().unwrap().facets[0].version, 3);
+        assert!(FacetedObject::restore::<Employee>(&saved(1, serde_json::json!({})), &registry).is_err());
+    }
+}
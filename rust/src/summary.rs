@@ -0,0 +1,68 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::TypeId;
+use core::fmt;
+
+use serde::Serialize;
+
+use crate::core::{Facet, FacetVisitor};
+
+// Structured description of one facet's state
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FacetSummary {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+    pub items: Vec<String>,
+}
+
+impl FacetSummary {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn field(mut self, label: &str, value: impl fmt::Display) -> Self {
+        self.fields.push((label.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn item(mut self, item: impl fmt::Display) -> Self {
+        self.items.push(item.to_string());
+        self
+    }
+}
+
+impl fmt::Display for FacetSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.title)?;
+        for (label, value) in &self.fields {
+            writeln!(f, "  {}: {}", label, value)?;
+        }
+        for item in &self.items {
+            writeln!(f, "  - {}", item)?;
+        }
+        Ok(())
+    }
+}
+
+// Implemented by facets that contribute to object summaries. Facets opt in
+// by also overriding Facet::as_summarizable to return Some(self).
+pub trait Summarizable {
+    fn summarize(&self) -> FacetSummary;
+}
+
+// Visitor gathering summaries from every summarizable facet
+#[derive(Default)]
+pub(crate) struct SummaryCollector {
+    pub(crate) summaries: Vec<FacetSummary>,
+}
+
+impl FacetVisitor for SummaryCollector {
+    fn visit(&mut self, _type_id: TypeId, facet: &dyn Facet) {
+        if let Some(summarizable) = facet.as_summarizable() {
+            self.summaries.push(summarizable.summarize());
+        }
+    }
+}
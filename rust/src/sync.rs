@@ -0,0 +1,110 @@
+// Locks used by the core facet storage. With the `std` feature they are
+// parking_lot locks; without it spin locks are used so the core also runs
+// on targets without OS threads (embedded, wasm32-unknown-unknown). Neither
+// poisons: a panic while a facet is locked leaves the facet in whatever
+// state the panicking code left it, but the object stays usable.
+
+#[cfg(feature = "std")]
+use parking_lot as backend;
+
+#[cfg(not(feature = "std"))]
+use spin as backend;
+
+pub type ReadGuard<'a, T> = backend::RwLockReadGuard<'a, T>;
+pub type WriteGuard<'a, T> = backend::RwLockWriteGuard<'a, T>;
+
+#[cfg(feature = "std")]
+pub type RawFacetLock = parking_lot::RawRwLock;
+
+#[cfg(not(feature = "std"))]
+pub type RawFacetLock = spin::RwLock<()>;
+
+// Lock around a single attached facet. It does not poison, and its guards
+// keep the lock alive through an Arc instead of borrowing the facet table.
+pub type FacetLock<T> = lock_api::RwLock<RawFacetLock, T>;
+pub type FacetReadGuard<T> = lock_api::ArcRwLockReadGuard<RawFacetLock, T>;
+pub type FacetWriteGuard<T> = lock_api::ArcRwLockWriteGuard<RawFacetLock, T>;
+// Read guard narrowed to part of the locked value, e.g. the core object
+pub type MappedReadGuard<'a, T> = lock_api::MappedRwLockReadGuard<'a, RawFacetLock, T>;
+
+#[derive(Debug, Default)]
+pub struct RwLock<T> {
+    inner: backend::RwLock<T>,
+}
+
+impl<T> RwLock<T> {
+    pub const fn new(value: T) -> Self {
+        Self { inner: backend::RwLock::new(value) }
+    }
+
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        self.inner.read()
+    }
+
+    pub fn write(&self) -> WriteGuard<'_, T> {
+        self.inner.write()
+    }
+}
+
+// Copy-on-write cell for data read far more often than it changes, such as
+// an object's facet table. With `std` readers load the current version
+// without locking, so they never wait on a writer; writers are serialized
+// and publish an updated copy. Without `std` a spin lock guards the version.
+#[cfg(feature = "std")]
+pub type CowGuard<T> = arc_swap::Guard<alloc::sync::Arc<T>>;
+
+#[cfg(not(feature = "std"))]
+pub type CowGuard<T> = alloc::sync::Arc<T>;
+
+pub struct CowCell<T> {
+    #[cfg(feature = "std")]
+    current: arc_swap::ArcSwap<T>,
+    #[cfg(feature = "std")]
+    writer: parking_lot::Mutex<()>,
+    #[cfg(not(feature = "std"))]
+    current: spin::RwLock<alloc::sync::Arc<T>>,
+}
+
+impl<T: Clone> CowCell<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            current: arc_swap::ArcSwap::from_pointee(value),
+            #[cfg(feature = "std")]
+            writer: parking_lot::Mutex::new(()),
+            #[cfg(not(feature = "std"))]
+            current: spin::RwLock::new(alloc::sync::Arc::new(value)),
+        }
+    }
+
+    // The current version; later updates don't change it
+    #[cfg(feature = "std")]
+    pub fn load(&self) -> CowGuard<T> {
+        self.current.load()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn load(&self) -> CowGuard<T> {
+        alloc::sync::Arc::clone(&self.current.read())
+    }
+
+    // Apply `update` to a copy of the current version and publish it, unless
+    // `update` fails. Readers keep seeing the previous version until then.
+    #[cfg(feature = "std")]
+    pub fn update<R, E>(&self, update: impl FnOnce(&mut T) -> Result<R, E>) -> Result<R, E> {
+        let _writer = self.writer.lock();
+        let mut next = T::clone(&self.current.load());
+        let result = update(&mut next)?;
+        self.current.store(alloc::sync::Arc::new(next));
+        Ok(result)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn update<R, E>(&self, update: impl FnOnce(&mut T) -> Result<R, E>) -> Result<R, E> {
+        let mut current = self.current.write();
+        let mut next = T::clone(&current);
+        let result = update(&mut next)?;
+        *current = alloc::sync::Arc::new(next);
+        Ok(result)
+    }
+}
@@ -0,0 +1,489 @@
+// Test support for crates building composite operations on top of the
+// built-in facets, or writing facets of their own: preconfigured facets, a
+// deterministic clock, a harness recording what happens to an object, and
+// proptest strategies for facet state.
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use proptest::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::clock::{Clock, ManualClock, Timestamp};
+use crate::{
+    AccountFacet, AuditFacet, Currency, Employee, Facet, FacetAccess, FacetContext, FacetError, FacetEvent,
+    FacetInterceptor, FacetedObject, Money, PermissionFacet, Subscription,
+};
+
+// Fixed start time for fake clocks so audit timestamps are reproducible
+pub const TEST_EPOCH: Timestamp = Timestamp::from_millis(1_700_000_000_000);
+
+pub fn fake_clock() -> Arc<ManualClock> {
+    Arc::new(ManualClock::new(TEST_EPOCH))
+}
+
+// Audit facet stamping entries from the given fake clock
+pub fn recording_audit(clock: &Arc<ManualClock>) -> AuditFacet {
+    AuditFacet::with_clock(Arc::clone(clock) as _)
+}
+
+// Account already holding `balance`
+pub fn fake_account(account_number: &str, balance: Money) -> AccountFacet {
+    let mut account = AccountFacet::with_currency(account_number, balance.currency());
+    if balance.is_positive() {
+        account.deposit(balance).expect("positive opening balance");
+    }
+    account
+}
+
+// PermissionFacet answering exactly as scripted, independent of role tables
+#[derive(Debug, Default)]
+pub struct ScriptedPermissions {
+    allowed: Vec<String>,
+}
+
+impl ScriptedPermissions {
+    pub fn deny_all() -> Self {
+        Self::default()
+    }
+
+    pub fn allow(mut self, permission: &str) -> Self {
+        self.allowed.push(permission.to_string());
+        self
+    }
+
+    pub fn build(self) -> PermissionFacet {
+        let mut permissions = PermissionFacet::new("scripted");
+        for permission in &self.allowed {
+            permissions.grant_permission(permission);
+        }
+        permissions
+    }
+}
+
+// Bare employee object for tests to attach facets to
+pub fn test_employee() -> FacetedObject {
+    FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"))
+}
+
+// Captures a facet's serialized state so a test can assert whether an
+// operation mutated it
+pub struct FacetProbe<F> {
+    before: Option<Value>,
+    _facet: PhantomData<fn() -> F>,
+}
+
+impl<F: Facet + Serialize + 'static> FacetProbe<F> {
+    pub fn capture(object: &FacetedObject) -> Self {
+        Self {
+            before: Self::state(object),
+            _facet: PhantomData,
+        }
+    }
+
+    fn state(object: &FacetedObject) -> Option<Value> {
+        object.with_facet::<F, _>(|facet| serde_json::to_value(facet).ok())
+            .ok()
+            .flatten()
+    }
+
+    #[track_caller]
+    pub fn assert_changed(&self, object: &FacetedObject) {
+        let after = Self::state(object);
+        assert_ne!(self.before, after, "expected {} to be mutated", std::any::type_name::<F>());
+    }
+
+    #[track_caller]
+    pub fn assert_unchanged(&self, object: &FacetedObject) {
+        let after = Self::state(object);
+        assert_eq!(self.before, after, "expected {} to be left untouched", std::any::type_name::<F>());
+    }
+}
+
+#[track_caller]
+pub fn assert_facet_attached<F: Facet + 'static>(object: &FacetedObject) {
+    assert!(object.has_facet::<F>(), "expected {} to be attached", std::any::type_name::<F>());
+}
+
+#[track_caller]
+pub fn assert_facet_absent<F: Facet + 'static>(object: &FacetedObject) {
+    assert!(!object.has_facet::<F>(), "expected {} to be absent", std::any::type_name::<F>());
+}
+
+#[track_caller]
+pub fn assert_balance(object: &FacetedObject, expected: Money) {
+    let balance = object.with_facet::<AccountFacet, Money>(|account| account.get_balance())
+        .expect("AccountFacet attached");
+    assert_eq!(balance, expected, "unexpected account balance");
+}
+
+// Assert the audit trail contains `operation`, showing the trail on failure
+#[track_caller]
+pub fn assert_audit_logged(object: &FacetedObject, operation: &str) {
+    let trail = object.with_facet::<AuditFacet, Vec<String>>(|audit| {
+        audit.get_audit_trail().iter().map(|entry| entry.operation.clone()).collect()
+    }).expect("AuditFacet attached");
+
+    assert!(
+        trail.iter().any(|logged| logged == operation),
+        "expected audit entry '{}', trail was {:?}", operation, trail
+    );
+}
+
+#[track_caller]
+pub fn assert_audit_count(object: &FacetedObject, expected: usize) {
+    let count = object.with_facet::<AuditFacet, usize>(|audit| audit.get_audit_trail().len())
+        .expect("AuditFacet attached");
+    assert_eq!(count, expected, "unexpected number of audit entries");
+}
+
+// Advance a fake clock, returning the new time
+pub fn advance(clock: &ManualClock, duration: Duration) -> Timestamp {
+    clock.advance(duration);
+    clock.now()
+}
+
+// Core object for facets that don't care what they are attached to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCore {
+    pub id: String,
+}
+
+impl Default for MockCore {
+    fn default() -> Self {
+        Self { id: "MOCK001".to_string() }
+    }
+}
+
+// Facet remembering the lifecycle hooks and events it saw, which can be
+// told to refuse being attached or detached
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MockFacet {
+    pub value: i64,
+    calls: Vec<String>,
+    refuse_attach: bool,
+    refuse_detach: bool,
+}
+
+impl MockFacet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn refusing_attach(mut self) -> Self {
+        self.refuse_attach = true;
+        self
+    }
+
+    pub fn refusing_detach(mut self) -> Self {
+        self.refuse_detach = true;
+        self
+    }
+
+    // "attach", "detach", "core_changed" and "event:<name>", oldest first
+    pub fn calls(&self) -> &[String] {
+        &self.calls
+    }
+}
+
+impl Facet for MockFacet {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn on_attach(&mut self, _ctx: &FacetContext<'_>) -> Result<(), FacetError> {
+        self.calls.push("attach".to_string());
+        if self.refuse_attach {
+            return Err(FacetError::Invalid("MockFacet refused to attach".to_string()));
+        }
+        Ok(())
+    }
+
+    fn on_detach(&mut self) -> Result<(), FacetError> {
+        self.calls.push("detach".to_string());
+        if self.refuse_detach {
+            return Err(FacetError::Invalid("MockFacet refused to detach".to_string()));
+        }
+        Ok(())
+    }
+
+    fn on_core_changed(&mut self, _ctx: &FacetContext<'_>) {
+        self.calls.push("core_changed".to_string());
+    }
+
+    fn on_event(&mut self, event: &dyn FacetEvent) {
+        self.calls.push(format!("event:{}", event.event_name()));
+    }
+}
+
+// Something that happened to a FacetHarness's object. Facets are named by
+// their full type name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interaction {
+    Attached(&'static str),
+    Detached(&'static str),
+    // Mutable access to a tracked facet
+    Mutated(&'static str),
+    Event(&'static str),
+    // Intercepted with_facet or with_facet_mut call, and whether it succeeded
+    Access { facet: &'static str, mutable: bool, ok: bool },
+}
+
+type InteractionLog = Arc<Mutex<Vec<Interaction>>>;
+
+fn record(log: &InteractionLog, interaction: Interaction) {
+    log.lock().unwrap().push(interaction);
+}
+
+struct RecordingInterceptor {
+    log: InteractionLog,
+}
+
+impl FacetInterceptor for RecordingInterceptor {
+    fn name(&self) -> &str {
+        "harness"
+    }
+
+    fn after(&self, _object: &FacetedObject, access: &FacetAccess<'_>, outcome: Result<(), &FacetError>) {
+        record(&self.log, Interaction::Access { facet: access.type_name, mutable: access.mutable, ok: outcome.is_ok() });
+    }
+}
+
+struct EventRecorder {
+    log: InteractionLog,
+}
+
+impl Facet for EventRecorder {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn on_event(&mut self, event: &dyn FacetEvent) {
+        record(&self.log, Interaction::Event(event.event_name()));
+    }
+}
+
+// FacetedObject for testing a facet, recording every attach, detach,
+// mutation, event and intercepted access. Attaches, detaches and mutations
+// are recorded for facet types attached through the harness or tracked
+// with `track`; an extra facet attached at construction records events.
+pub struct FacetHarness {
+    object: FacetedObject,
+    log: InteractionLog,
+    names: Arc<Mutex<HashMap<TypeId, &'static str>>>,
+    subscriptions: Vec<Subscription>,
+}
+
+impl Default for FacetHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FacetHarness {
+    // Harness around a MockCore
+    pub fn new() -> Self {
+        Self::with_core(MockCore::default())
+    }
+
+    pub fn with_core<T: Any + Send + Sync>(core: T) -> Self {
+        let object = FacetedObject::new(core);
+        let log = InteractionLog::default();
+        object.attach_facet(EventRecorder { log: Arc::clone(&log) }).expect("fresh object");
+        object.add_interceptor(RecordingInterceptor { log: Arc::clone(&log) }).expect("fresh object");
+
+        let names: Arc<Mutex<HashMap<TypeId, &'static str>>> = Arc::default();
+        let layout = {
+            let (log, names) = (Arc::clone(&log), Arc::clone(&names));
+            object.observe_layout(Arc::new(move |object: &FacetedObject, type_id: TypeId| {
+                let Some(&name) = names.lock().unwrap().get(&type_id) else {
+                    return;
+                };
+                let mut log = log.lock().unwrap();
+                // Attaching and detaching also notify mutation observers
+                if log.last() == Some(&Interaction::Mutated(name)) {
+                    log.pop();
+                }
+                log.push(if object.facet_type_ids().contains(&type_id) {
+                    Interaction::Attached(name)
+                } else {
+                    Interaction::Detached(name)
+                });
+            })).expect("fresh object")
+        };
+        Self { object, log, names, subscriptions: vec![layout] }
+    }
+
+    // Attach `facet` and track its type; panics if attaching fails
+    #[track_caller]
+    pub fn attach<F: Facet + 'static>(self, facet: F) -> Self {
+        let harness = self.track::<F>();
+        if let Err(e) = harness.object.attach_facet(facet) {
+            panic!("attaching {} failed: {}", type_name::<F>(), e);
+        }
+        harness
+    }
+
+    // Record attaches, detaches and mutations of F
+    pub fn track<F: Facet + 'static>(mut self) -> Self {
+        if self.names.lock().unwrap().insert(TypeId::of::<F>(), type_name::<F>()).is_none() {
+            let log = Arc::clone(&self.log);
+            let subscription = self.object.observe::<F>(move |_| record(&log, Interaction::Mutated(type_name::<F>())));
+            self.subscriptions.push(subscription.expect("observer registered"));
+        }
+        self
+    }
+
+    pub fn object(&self) -> &FacetedObject {
+        &self.object
+    }
+
+    // Interactions so far, oldest first
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.log.lock().unwrap().clone()
+    }
+
+    // Forget the interactions so far, e.g. those of the test's setup
+    pub fn clear(&self) {
+        self.log.lock().unwrap().clear();
+    }
+
+    #[track_caller]
+    pub fn assert_facet_mutated<F: Facet + 'static>(&self) {
+        assert!(
+            self.interactions().contains(&Interaction::Mutated(type_name::<F>())),
+            "expected {} to be mutated, interactions were {:?}", type_name::<F>(), self.interactions(),
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_facet_not_mutated<F: Facet + 'static>(&self) {
+        assert!(
+            !self.interactions().contains(&Interaction::Mutated(type_name::<F>())),
+            "expected {} to be left untouched, interactions were {:?}", type_name::<F>(), self.interactions(),
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_event_emitted<E: FacetEvent>(&self) {
+        assert!(
+            self.interactions().contains(&Interaction::Event(type_name::<E>())),
+            "expected {} to be emitted, interactions were {:?}", type_name::<E>(), self.interactions(),
+        );
+    }
+}
+
+// Amounts of `currency` from zero up to `max_units` whole units
+pub fn arb_money(currency: Currency, max_units: i64) -> impl Strategy<Value = Money> {
+    (0..=max_units * 100).prop_map(move |minor| Money::from_minor(minor, currency))
+}
+
+// USD accounts after a random history of deposits and withdrawals; refused
+// withdrawals are left out of the history
+pub fn arb_account() -> impl Strategy<Value = AccountFacet> {
+    prop::collection::vec((any::<bool>(), arb_money(Currency::USD, 1_000)), 0..20).prop_map(|history| {
+        let mut account = AccountFacet::new("ACC-PROP");
+        for (deposit, amount) in history.into_iter().filter(|(_, amount)| amount.is_positive()) {
+            let _ = if deposit { account.deposit(amount) } else { account.withdraw(amount) };
+        }
+        account
+    })
+}
+
+// Permissions of one of the built-in roles, with a few extra grants
+pub fn arb_permissions() -> impl Strategy<Value = PermissionFacet> {
+    let role = prop::sample::select(vec!["admin", "manager", "employee", "guest"]);
+    let grants = prop::collection::vec(prop::sample::select(vec!["read", "write", "delete", "financial_operations"]), 0..3);
+    (role, grants).prop_map(|(role, grants)| {
+        let mut permissions = PermissionFacet::new(role);
+        for grant in grants {
+            permissions.grant_permission(grant);
+        }
+        permissions
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmployeeOperations;
+
+    #[test]
+    fn test_helpers_cover_financial_operation() {
+        let clock = fake_clock();
+        let employee = test_employee();
+        employee.attach_facet(fake_account("ACC001", Money::usd(100))).unwrap();
+        employee.attach_facet(ScriptedPermissions::deny_all().allow("financial_operations").build()).unwrap();
+        employee.attach_facet(recording_audit(&clock)).unwrap();
+
+        let probe = FacetProbe::<AccountFacet>::capture(&employee);
+        EmployeeOperations::perform_financial_operation(&employee, |account| account.deposit(Money::usd(50))).unwrap();
+
+        probe.assert_changed(&employee);
+        assert_balance(&employee, Money::usd(150));
+        assert_audit_logged(&employee, "financial_operation");
+        assert_audit_count(&employee, 1);
+    }
+
+    #[test]
+    fn test_denied_operation_leaves_account_untouched() {
+        let employee = test_employee();
+        employee.attach_facet(fake_account("ACC001", Money::usd(100))).unwrap();
+        employee.attach_facet(ScriptedPermissions::deny_all().build()).unwrap();
+
+        let probe = FacetProbe::<AccountFacet>::capture(&employee);
+        assert!(EmployeeOperations::perform_financial_operation(&employee, |account| account.deposit(Money::usd(50))).is_err());
+
+        probe.assert_unchanged(&employee);
+        assert_facet_absent::<AuditFacet>(&employee);
+    }
+
+    #[test]
+    fn test_harness_records_interactions() {
+        let harness = FacetHarness::new().attach(MockFacet::new()).track::<AccountFacet>();
+        let object = harness.object();
+        object.attach_facet(fake_account("ACC001", Money::usd(100))).unwrap();
+        harness.clear();
+
+        object.with_facet_mut::<MockFacet, _>(|mock| mock.value += 1).unwrap();
+        object.emit(&crate::PermissionGranted { role: "manager".to_string(), permission: "write".to_string() }).unwrap();
+        assert!(object.with_facet::<AuditFacet, _>(|_| ()).is_err());
+        harness.assert_facet_mutated::<MockFacet>();
+        harness.assert_facet_not_mutated::<AccountFacet>();
+        harness.assert_event_emitted::<crate::PermissionGranted>();
+
+        let mock = object.detach_facet::<MockFacet>().unwrap();
+        assert_eq!(mock.calls(), ["attach", &format!("event:{}", type_name::<crate::PermissionGranted>()), "detach"]);
+        assert_eq!(harness.interactions(), [
+            Interaction::Mutated(type_name::<MockFacet>()),
+            Interaction::Access { facet: type_name::<MockFacet>(), mutable: true, ok: true },
+            Interaction::Event(type_name::<crate::PermissionGranted>()),
+            Interaction::Access { facet: type_name::<AuditFacet>(), mutable: false, ok: false },
+            Interaction::Detached(type_name::<MockFacet>()),
+        ]);
+        assert!(object.attach_facet(MockFacet::new().refusing_attach()).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_strategies_produce_valid_facets(account in arb_account(), permissions in arb_permissions()) {
+            prop_assert!(!account.get_balance().is_negative());
+            let harness = FacetHarness::new().attach(account).attach(permissions);
+            harness.object().with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(1))).unwrap().unwrap();
+            harness.assert_facet_mutated::<AccountFacet>();
+            harness.assert_facet_not_mutated::<PermissionFacet>();
+        }
+    }
+}
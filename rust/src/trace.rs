@@ -0,0 +1,175 @@
+use tracing::field::{display, Empty};
+use tracing::span::EnteredSpan;
+
+use crate::core::FacetedObject;
+use crate::error::FacetError;
+use crate::interceptor::{FacetAccess, FacetInterceptor};
+
+// Events and spans are emitted under this target, so subscribers can
+// filter facet activity with e.g. RUST_LOG=facets=debug
+pub const TARGET: &str = "facets";
+
+fn short_name(type_name: &'static str) -> &'static str {
+    type_name.rsplit("::").next().unwrap_or(type_name)
+}
+
+// Debug span around one attach, detach or facet access, entered for the
+// duration of the operation
+pub(crate) struct FacetSpan(EnteredSpan);
+
+impl FacetSpan {
+    pub(crate) fn enter(operation: &'static str, object: u64, facet: &'static str, instance: &str) -> Self {
+        let span = tracing::debug_span!(
+            target: TARGET,
+            "facet",
+            operation,
+            object,
+            facet = short_name(facet),
+            instance,
+            outcome = Empty,
+        );
+        Self(span.entered())
+    }
+
+    // Record "ok" or the error on the span
+    pub(crate) fn finish<R>(self, result: &Result<R, FacetError>) {
+        match result {
+            Ok(_) => self.0.record("outcome", "ok"),
+            Err(e) => self.0.record("outcome", display(e)),
+        };
+    }
+}
+
+// Emits an info event for every mutable facet access, or a warning if it
+// failed, with the object id, facet and instance as fields
+pub struct LoggingInterceptor;
+
+impl FacetInterceptor for LoggingInterceptor {
+    fn name(&self) -> &str {
+        "logging"
+    }
+
+    fn after(&self, object: &FacetedObject, access: &FacetAccess<'_>, outcome: Result<(), &FacetError>) {
+        if !access.mutable {
+            return;
+        }
+        let facet = short_name(access.type_name);
+        match outcome {
+            Ok(()) => tracing::info!(target: TARGET, object = object.id(), facet, instance = access.instance, "facet mutated"),
+            Err(e) => tracing::warn!(target: TARGET, object = object.id(), facet, instance = access.instance, error = %e, "facet mutation failed"),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use std::fmt::Write as _;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use super::*;
+    use crate::{AccountFacet, Money};
+
+    // Renders span fields (as recorded by the time the span closes) and
+    // events as "name: field=value ..." lines
+    #[derive(Default)]
+    struct Capture {
+        next_id: AtomicU64,
+        spans: Mutex<Vec<String>>,
+        lines: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Fields<'a>(&'a mut String);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            let _ = write!(self.0, " {}={}", field.name(), value);
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut line = String::from(span.metadata().name());
+            line.push(':');
+            span.record(&mut Fields(&mut line));
+            self.spans.lock().unwrap().push(line);
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut Fields(&mut spans[span.into_u64() as usize - 1]));
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut line = format!("{}:", event.metadata().level());
+            event.record(&mut Fields(&mut line));
+            self.lines.lock().unwrap().push(line);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, span: &Id) {
+            let line = self.spans.lock().unwrap()[span.into_u64() as usize - 1].clone();
+            self.lines.lock().unwrap().push(line);
+        }
+    }
+
+    fn capture(operation: impl FnOnce()) -> Vec<String> {
+        let subscriber = Capture::default();
+        let lines = Arc::clone(&subscriber.lines);
+        tracing::subscriber::with_default(subscriber, operation);
+        let lines = lines.lock().unwrap().clone();
+        lines
+    }
+
+    #[test]
+    fn test_spans_carry_facet_and_outcome() {
+        let object = FacetedObject::new(());
+        let id = object.id();
+        let lines = capture(|| {
+            object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+            let _ = object.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(5)));
+            assert!(object.detach_facet::<AccountFacet>().is_ok());
+            assert!(object.with_facet::<AccountFacet, _>(|_| ()).is_err());
+        });
+
+        assert_eq!(lines, [
+            format!("facet: operation=attach object={} facet=AccountFacet instance=default outcome=ok", id),
+            format!("facet: operation=with_facet_mut object={} facet=AccountFacet instance=default outcome=ok", id),
+            format!("facet: operation=detach object={} facet=AccountFacet instance=default outcome=ok", id),
+            format!("facet: operation=with_facet object={} facet=AccountFacet instance=default outcome=Required facet not found: {}", id, std::any::type_name::<AccountFacet>()),
+        ]);
+    }
+
+    #[test]
+    fn test_logging_interceptor_records_mutations() {
+        let object = FacetedObject::new(());
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        object.add_interceptor(LoggingInterceptor).unwrap();
+        let events = capture(|| {
+            object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(5))).unwrap().unwrap();
+            object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+            let _ = object.with_named_facet_mut::<AccountFacet, _>("savings", |_| ());
+        });
+
+        let events: Vec<&String> = events.iter().filter(|line| !line.starts_with("facet:")).collect();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].starts_with("INFO: message=facet mutated") && events[0].contains("facet=AccountFacet"));
+        assert!(events[1].starts_with("WARN: message=facet mutation failed") && events[1].contains("instance=savings"));
+    }
+}
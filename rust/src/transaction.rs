@@ -0,0 +1,250 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+use crate::core::{Facet, FacetedObject, DEFAULT_INSTANCE};
+use crate::error::FacetError;
+#[cfg(feature = "std")]
+use crate::event::DomainEvent;
+#[cfg(feature = "builtin-facets")]
+use crate::facets::audit::Auditable;
+
+// Facet whose state can be saved before a transaction touches it and put
+// back if the transaction fails
+pub trait TransactionalFacet: Facet + Sized {
+    type Savepoint: 'static;
+
+    fn savepoint(&self) -> Self::Savepoint;
+
+    fn rollback(&mut self, savepoint: Self::Savepoint);
+
+    // Events for the changes made since `savepoint`, logged when the
+    // transaction that made them commits; facets without events log none
+    #[cfg(feature = "std")]
+    fn changes_since(&self, _savepoint: &Self::Savepoint) -> Vec<DomainEvent> {
+        Vec::new()
+    }
+
+    // Redo the change one of this facet's events records
+    #[cfg(feature = "std")]
+    fn apply_event(&mut self, event: &DomainEvent) -> Result<(), FacetError> {
+        Err(FacetError::Invalid(format!("{} cannot apply {} events", self.facet_name(), event.kind)))
+    }
+
+    // Apply, in order, the events of facets named like this one; a new
+    // facet replaying its whole stream ends up in the logged state
+    #[cfg(feature = "std")]
+    fn replay<'e>(&mut self, events: impl IntoIterator<Item = &'e DomainEvent>) -> Result<(), FacetError> {
+        let name = self.facet_name();
+        for event in events.into_iter().filter(|event| event.facet == name) {
+            self.apply_event(event)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+type Committed = Vec<DomainEvent>;
+#[cfg(not(feature = "std"))]
+type Committed = ();
+
+// Settles one touched facet once the outcome is known: rolls it back, or on
+// commit returns its events. Neither goes through interceptors, guards or
+// write admission again; they already let the transaction's steps through,
+// and a rate limit must not leave a failed transaction half applied.
+type Settle = Box<dyn FnOnce(&FacetedObject, bool) -> Result<Committed, FacetError>>;
+
+#[cfg(feature = "std")]
+fn committed<F: TransactionalFacet>(object: &FacetedObject, instance: &str, savepoint: &F::Savepoint) -> Result<Committed, FacetError> {
+    object.with_facet_unchecked::<F, _>(instance, |facet| {
+        let mut events = facet.changes_since(savepoint);
+        for event in &mut events {
+            event.facet = facet.facet_name().to_string();
+            event.instance = instance.to_string();
+        }
+        events
+    })
+}
+
+#[cfg(not(feature = "std"))]
+fn committed<F: TransactionalFacet>(object: &FacetedObject, instance: &str, _savepoint: &F::Savepoint) -> Result<Committed, FacetError> {
+    object.with_facet_unchecked::<F, _>(instance, |_| ())
+}
+
+// Facets detached during the transaction have nothing left to settle
+fn unless_detached<F: Facet, T: Default>(result: Result<T, FacetError>) -> Result<T, FacetError> {
+    match result {
+        Err(FacetError::NotFound { type_name }) if type_name == core::any::type_name::<F>() => Ok(T::default()),
+        result => result,
+    }
+}
+
+// Mutations made through a transaction are undone, most recent first, if
+// the transaction closure returns an error. Facets are only locked while
+// each step runs, so other threads can observe intermediate state.
+pub struct Transaction<'a> {
+    object: &'a FacetedObject,
+    touched: Vec<(TypeId, String)>,
+    settle: Vec<Settle>,
+}
+
+impl<'a> Transaction<'a> {
+    pub fn object(&self) -> &'a FacetedObject {
+        self.object
+    }
+
+    pub fn with_facet<F: Facet + 'static, R>(&self, operation: impl FnOnce(&F) -> R) -> Result<R, FacetError> {
+        self.object.with_facet(operation)
+    }
+
+    pub fn with_facet_mut<F: TransactionalFacet, R>(
+        &mut self,
+        operation: impl FnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        self.with_named_facet_mut(DEFAULT_INSTANCE, operation)
+    }
+
+    // The first mutation of each facet instance saves its state, in the
+    // same critical section as the mutation itself
+    pub fn with_named_facet_mut<F: TransactionalFacet, R>(
+        &mut self,
+        name: &str,
+        operation: impl FnOnce(&mut F) -> R,
+    ) -> Result<R, FacetError> {
+        let type_id = TypeId::of::<F>();
+        let first = !self.touched.iter().any(|(touched, instance)| *touched == type_id && instance == name);
+        let (savepoint, result) = self.object.with_named_facet_mut::<F, _>(name, |facet| {
+            (first.then(|| facet.savepoint()), operation(facet))
+        })?;
+
+        if let Some(savepoint) = savepoint {
+            let instance = name.to_string();
+            self.touched.push((type_id, instance.clone()));
+            self.settle.push(Box::new(move |object: &FacetedObject, commit: bool| {
+                if commit {
+                    return unless_detached::<F, _>(committed::<F>(object, &instance, &savepoint));
+                }
+                let restored = object.with_facet_unchecked_mut::<F, _>(&instance, |facet| facet.rollback(savepoint));
+                unless_detached::<F, _>(restored).map(|()| Committed::default())
+            }));
+        }
+        Ok(result)
+    }
+
+    // Facets detached during the transaction cannot be rolled back and are
+    // skipped. The others are all restored even if one fails; the first
+    // failure is returned.
+    fn rollback(self) -> Result<(), FacetError> {
+        let mut result = Ok(());
+        for settle in self.settle.into_iter().rev() {
+            result = result.and(settle(self.object, false).map(drop));
+        }
+        result
+    }
+
+    // Log the events of every touched facet, in the order the facets were
+    // first touched, with the object's Auditable
+    fn commit(self) -> Result<(), FacetError> {
+        let committed = self.settle.into_iter()
+            .map(|settle| settle(self.object, true))
+            .collect::<Result<Vec<Committed>, FacetError>>()?;
+        #[cfg(feature = "builtin-facets")]
+        {
+            let events: Vec<DomainEvent> = committed.into_iter().flatten().collect();
+            if !events.is_empty() {
+                match self.object.with_facet_as_unchecked_mut::<dyn Auditable, _>(|audit| audit.append_events(events)) {
+                    // Objects without an audit trail keep no events
+                    Ok(()) | Err(FacetError::NotFound { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        #[cfg(not(feature = "builtin-facets"))]
+        drop(committed);
+        Ok(())
+    }
+}
+
+impl FacetedObject {
+    // Run `operation` as one unit: if it returns an error, every facet it
+    // mutated through the transaction is restored before the error is
+    // returned. With the `validation` feature, leaving the object invalid
+    // by its ValidationFacet counts as an error. If a facet cannot be
+    // restored, that failure is returned instead. On success the domain
+    // events of the mutated facets are appended to the object's audit log.
+    pub fn transaction<R>(
+        &self,
+        operation: impl FnOnce(&mut Transaction<'_>) -> Result<R, FacetError>,
+    ) -> Result<R, FacetError> {
+        let mut tx = Transaction { object: self, touched: Vec::new(), settle: Vec::new() };
+        let result = operation(&mut tx);
+        #[cfg(feature = "validation")]
+        let result = result.and_then(|value| self.validate()?.into_result().map(|()| value));
+        match result {
+            Ok(value) => tx.commit().map(|()| value),
+            Err(e) => tx.rollback().and(Err(e)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, RateLimitInterceptor, RateLimiterFacet};
+
+    fn employee() -> FacetedObject {
+        let employee = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        employee.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        employee.attach_facet(AuditFacet::new()).unwrap();
+        employee.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(100))).unwrap().unwrap();
+        employee
+    }
+
+    #[test]
+    fn test_failed_transaction_rolls_back_every_facet() {
+        let employee = employee();
+
+        let result = employee.transaction(|tx| {
+            tx.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(30)))??;
+            tx.with_facet_mut::<AuditFacet, _>(|audit| audit.log_operation("withdraw", "30"))?;
+            tx.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(30)))??;
+            tx.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(500)))?
+        });
+
+        assert!(result.is_err());
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(100));
+        assert!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().is_empty()).unwrap());
+    }
+
+    #[test]
+    fn test_successful_transaction_commits() {
+        let employee = employee();
+
+        let balance = employee.transaction(|tx| {
+            tx.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(Money::usd(30)))??;
+            tx.with_facet_mut::<AuditFacet, _>(|audit| audit.log_operation("withdraw", "30"))?;
+            tx.with_facet::<AccountFacet, _>(|account| account.get_balance())
+        });
+
+        assert_eq!(balance.unwrap(), Money::usd(70));
+        assert_eq!(employee.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rollback_bypasses_interceptors() {
+        let employee = employee();
+        employee.attach_facet(RateLimiterFacet::per_minute(1)).unwrap();
+        employee.add_interceptor(RateLimitInterceptor::new().limit::<AccountFacet>()).unwrap();
+
+        let result = employee.transaction(|tx| {
+            tx.with_facet_mut::<AccountFacet, _>(|account| {
+                account.deposit(Money::usd(10))?;
+                account.withdraw(Money::usd(500))
+            })?
+        });
+
+        assert!(matches!(result, Err(FacetError::InsufficientFunds { .. })));
+        assert_eq!(employee.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(100));
+    }
+}
@@ -0,0 +1,103 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::any::TypeId;
+use core::time::Duration;
+
+use crate::clock::{Clock, Timestamp};
+use crate::core::{Facet, FacetedObject, DEFAULT_INSTANCE};
+use crate::error::FacetError;
+
+// Facet instance removed because its TTL ran out
+pub struct ExpiredFacet {
+    pub name: String,
+    pub expired_at: Timestamp,
+    pub facet: Box<dyn Facet>,
+}
+
+// Called, with no locks held, after an expired facet was evicted
+pub(crate) type ExpiryCallback = Arc<dyn Fn(&FacetedObject, ExpiredFacet) + Send + Sync>;
+
+impl FacetedObject {
+    // Measure facet TTLs against `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    // Hand facets to `callback` as they are evicted on expiry, e.g. to log
+    // the end of a temporary permission
+    pub fn on_facet_expired(mut self, callback: impl Fn(&FacetedObject, ExpiredFacet) + Send + Sync + 'static) -> Self {
+        self.on_expired = Some(Arc::new(callback));
+        self
+    }
+
+    // Attach a facet that lasts `ttl`, e.g. elevated permissions or a
+    // session token. Once expired the facet is treated as absent and is
+    // evicted the next time it is looked up. A TTL too long to represent,
+    // such as Duration::MAX, never expires.
+    pub fn attach_facet_with_ttl<F: Facet + 'static>(&self, facet: F, ttl: Duration) -> Result<(), FacetError> {
+        self.attach_named_facet_with_ttl(DEFAULT_INSTANCE, facet, ttl)
+    }
+
+    pub fn attach_named_facet_with_ttl<F: Facet + 'static>(&self, name: &str, facet: F, ttl: Duration) -> Result<(), FacetError> {
+        let clock = self.clock.as_ref().ok_or_else(|| FacetError::Invalid("No clock to measure the TTL against".into()))?;
+        let expires_at = clock.now().checked_add(ttl);
+        self.attach_boxed_until(TypeId::of::<F>(), name, Box::new(facet), expires_at)
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{Employee, ManualClock, PermissionFacet};
+    use std::sync::Mutex;
+
+    fn employee(clock: &Arc<ManualClock>) -> FacetedObject {
+        FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering")).with_clock(clock.clone())
+    }
+
+    #[test]
+    fn test_expired_facets_are_absent() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let employee = employee(&clock);
+        employee.attach_facet_with_ttl(PermissionFacet::new("admin"), Duration::from_secs(60)).unwrap();
+        employee.attach_named_facet("base", PermissionFacet::new("employee")).unwrap();
+        employee.attach_named_facet_with_ttl("forever", PermissionFacet::new("admin"), Duration::MAX).unwrap();
+
+        clock.advance(Duration::from_secs(59));
+        assert!(employee.has_facet::<PermissionFacet>());
+        assert_eq!(employee.summaries().unwrap().len(), 3);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(employee.summaries().unwrap().len(), 2);
+        assert!(employee.with_facet::<PermissionFacet, _>(|permissions| permissions.get_role().to_string()).is_err());
+        assert!(!employee.has_facet::<PermissionFacet>());
+        assert!(employee.has_named_facet::<PermissionFacet>("base"));
+        assert!(employee.has_named_facet::<PermissionFacet>("forever"));
+
+        // The name is free again once the expired instance is gone
+        employee.attach_facet(PermissionFacet::new("manager")).unwrap();
+        assert!(employee.has_facet::<PermissionFacet>());
+    }
+
+    #[test]
+    fn test_expiry_callback_gets_the_facet() {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_millis(0)));
+        let expired = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&expired);
+        let employee = employee(&clock).on_facet_expired(move |_, expired: ExpiredFacet| {
+            let role = expired.facet.as_any().downcast_ref::<PermissionFacet>().unwrap().get_role().to_string();
+            log.lock().unwrap().push((expired.name, expired.expired_at, role));
+        });
+
+        employee.attach_named_facet_with_ttl("elevated", PermissionFacet::new("admin"), Duration::from_secs(5)).unwrap();
+        clock.advance(Duration::from_secs(10));
+        assert!(expired.lock().unwrap().is_empty());
+
+        // Re-attaching under the name evicts the expired instance first
+        employee.attach_named_facet("elevated", PermissionFacet::new("manager")).unwrap();
+        assert_eq!(*expired.lock().unwrap(), [("elevated".to_string(), Timestamp::from_millis(5_000), "admin".to_string())]);
+        assert!(employee.has_named_facet::<PermissionFacet>("elevated"));
+    }
+}
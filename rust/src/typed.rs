@@ -0,0 +1,202 @@
+use alloc::vec::Vec;
+use core::any::{type_name, Any};
+use core::marker::PhantomData;
+
+use crate::core::{CoreRef, Facet, FacetedObject};
+use crate::error::FacetError;
+
+// Tuple of facet types that must all be attached, e.g. (AccountFacet, PermissionFacet)
+pub trait FacetSet {
+    // Type names of the facets in the set that are missing from `object`
+    fn missing(object: &FacetedObject) -> Vec<&'static str>;
+}
+
+// Type-level proof that facet F is in a FacetSet; `I` is the position of F
+// in the tuple and is always inferred by the compiler
+pub trait Contains<F, I> {}
+
+// Type-level append used when attaching another required facet
+pub trait Push<F> {
+    type Output;
+}
+
+// Tuple positions for Contains
+pub struct Index0;
+pub struct Index1;
+pub struct Index2;
+pub struct Index3;
+
+macro_rules! impl_facet_set {
+    ($($ty:ident),*) => {
+        impl<$($ty: Facet),*> FacetSet for ($($ty,)*) {
+            #[allow(unused_mut, unused_variables)]
+            fn missing(object: &FacetedObject) -> Vec<&'static str> {
+                let mut missing = Vec::new();
+                $(
+                    if !object.has_facet::<$ty>() {
+                        missing.push(type_name::<$ty>());
+                    }
+                )*
+                missing
+            }
+        }
+    };
+}
+
+impl_facet_set!();
+impl_facet_set!(A);
+impl_facet_set!(A, B);
+impl_facet_set!(A, B, C);
+impl_facet_set!(A, B, C, D);
+
+macro_rules! impl_contains {
+    ($index:ident => $target:ident; $($ty:ident),+) => {
+        impl<$($ty),+> Contains<$target, $index> for ($($ty,)+) {}
+    };
+}
+
+impl_contains!(Index0 => A; A);
+impl_contains!(Index0 => A; A, B);
+impl_contains!(Index1 => B; A, B);
+impl_contains!(Index0 => A; A, B, C);
+impl_contains!(Index1 => B; A, B, C);
+impl_contains!(Index2 => C; A, B, C);
+impl_contains!(Index0 => A; A, B, C, D);
+impl_contains!(Index1 => B; A, B, C, D);
+impl_contains!(Index2 => C; A, B, C, D);
+impl_contains!(Index3 => D; A, B, C, D);
+
+impl<F> Push<F> for () {
+    type Output = (F,);
+}
+
+impl<A, F> Push<F> for (A,) {
+    type Output = (A, F);
+}
+
+impl<A, B, F> Push<F> for (A, B) {
+    type Output = (A, B, F);
+}
+
+impl<A, B, C, F> Push<F> for (A, B, C) {
+    type Output = (A, B, C, F);
+}
+
+// FacetedObject whose core type and required facets are part of its type.
+// Presence is checked once on construction, so accessors for required
+// facets cannot fail with "facet not found"; they still go through
+// interceptors, guards and write admission, and fail with their errors
+// (RateLimited, PermissionDenied, Busy, LockTimeout). Other facets can
+// still be reached dynamically through `object()`.
+pub struct Faceted<C, Required> {
+    object: FacetedObject,
+    _marker: PhantomData<fn() -> (C, Required)>,
+}
+
+impl<C: Any + Send + Sync> Faceted<C, ()> {
+    pub fn new(core: C) -> Self {
+        Self {
+            object: FacetedObject::new(core),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: Any + Send + Sync, Required: FacetSet> Faceted<C, Required> {
+    // Wrap an existing object after checking its core type and required facets
+    pub fn require(object: FacetedObject) -> Result<Self, FacetError> {
+        if object.get_core::<C>().is_none() {
+            return Err(FacetError::CoreTypeMismatch { type_name: type_name::<C>() });
+        }
+
+        let missing = Required::missing(&object);
+        if !missing.is_empty() {
+            return Err(FacetError::MissingFacets { type_names: missing });
+        }
+
+        Ok(Self { object, _marker: PhantomData })
+    }
+
+    // Attach a facet and add it to the required set
+    pub fn attach<F: Facet + 'static>(self, facet: F) -> Result<Faceted<C, Required::Output>, FacetError>
+    where
+        Required: Push<F>,
+    {
+        self.object.attach_facet(facet)?;
+        Ok(Faceted { object: self.object, _marker: PhantomData })
+    }
+
+    pub fn core(&self) -> CoreRef<'_, C> {
+        self.object.get_core::<C>()
+            .expect("core type checked on construction")
+    }
+
+    pub fn with<F: Facet + 'static, I, R>(&self, operation: impl FnOnce(&F) -> R) -> Result<R, FacetError>
+    where
+        Required: Contains<F, I>,
+    {
+        self.object.with_facet::<F, R>(operation)
+    }
+
+    pub fn with_mut<F: Facet + 'static, I, R>(&self, operation: impl FnOnce(&mut F) -> R) -> Result<R, FacetError>
+    where
+        Required: Contains<F, I>,
+    {
+        self.object.with_facet_mut::<F, R>(operation)
+    }
+
+    // Dynamic access for optional facets. Detaching a required facet
+    // through this breaks the type's guarantee and makes `with`/`with_mut`
+    // fail with NotFound; use `into_inner` first to drop the guarantee.
+    pub fn object(&self) -> &FacetedObject {
+        &self.object
+    }
+
+    pub fn into_inner(self) -> FacetedObject {
+        self.object
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, Money, PermissionFacet, RateLimitInterceptor, RateLimiterFacet};
+
+    #[test]
+    fn test_typed_attach_and_access() {
+        let employee = Faceted::new(Employee::new("Test User", "TEST001", "Engineering"))
+            .attach(AccountFacet::new("ACC001")).unwrap()
+            .attach(PermissionFacet::new("manager")).unwrap();
+
+        let balance = employee.with_mut::<AccountFacet, _, _>(|account| account.deposit(Money::usd(100))).unwrap().unwrap();
+        assert_eq!(balance, Money::usd(100));
+        assert!(employee.with::<PermissionFacet, _, _>(|permissions| permissions.has_permission("write")).unwrap());
+        assert_eq!(employee.core().id, "TEST001");
+    }
+
+    #[test]
+    fn test_accessors_report_interceptor_failures() {
+        let employee = Faceted::new(Employee::new("Test User", "TEST001", "Engineering"))
+            .attach(AccountFacet::new("ACC001")).unwrap()
+            .attach(RateLimiterFacet::per_minute(1)).unwrap();
+        employee.object().add_interceptor(RateLimitInterceptor::new().limit::<AccountFacet>()).unwrap();
+
+        employee.with_mut::<AccountFacet, _, _>(|account| account.deposit(Money::usd(100))).unwrap().unwrap();
+        let limited = employee.with_mut::<AccountFacet, _, _>(|account| account.deposit(Money::usd(100)));
+        assert!(matches!(limited, Err(FacetError::RateLimited { .. })));
+        assert_eq!(employee.with::<AccountFacet, _, _>(|account| account.get_balance()).unwrap(), Money::usd(100));
+    }
+
+    #[test]
+    fn test_require_reports_missing_facets() {
+        let object = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+
+        let error = Faceted::<Employee, (AccountFacet, AuditFacet)>::require(object).err().unwrap();
+        assert!(error.to_string().contains("AuditFacet"));
+
+        let object = FacetedObject::new(Employee::new("Test User", "TEST001", "Engineering"));
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        assert!(Faceted::<Employee, (AccountFacet,)>::require(object).is_ok());
+    }
+}
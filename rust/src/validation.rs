@@ -0,0 +1,392 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::core::{Facet, FacetContext, FacetedObject};
+use crate::error::FacetError;
+use crate::metadata::FacetMetadata;
+use crate::reflect::FieldValue;
+
+// What a rule's field is read from: the core object (serialized, see
+// ValidationFacet::core) or the reflectable facet with the given name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Core,
+    Facet(&'static str),
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Core => f.write_str("core"),
+            Target::Facet(name) => f.write_str(name),
+        }
+    }
+}
+
+type FieldCheck = Arc<dyn Fn(&FieldValue) -> Result<(), String> + Send + Sync>;
+type ObjectCheck = Arc<dyn Fn(&FacetedObject) -> Result<(), String> + Send + Sync>;
+type CoreReader = Arc<dyn Fn(&FacetContext<'_>) -> Option<Value> + Send + Sync>;
+
+#[derive(Clone)]
+enum Check {
+    // Present and, for text, not empty
+    Required,
+    Matches(Regex),
+    Range { min: f64, max: f64 },
+    Custom(FieldCheck),
+}
+
+#[derive(Clone)]
+struct Rule {
+    target: Target,
+    field: String,
+    check: Check,
+}
+
+impl Rule {
+    // Violation message, if `value` breaks the rule. Only Required rules
+    // fail on absent values.
+    fn violation(&self, value: Option<&FieldValue>) -> Option<String> {
+        let value = match (value, &self.check) {
+            (None, Check::Required) => return Some("is required".to_string()),
+            (Some(FieldValue::Text(text)), Check::Required) if text.is_empty() => return Some("is required".to_string()),
+            (None, _) => return None,
+            (Some(value), _) => value,
+        };
+        match (&self.check, value) {
+            (Check::Required, _) => None,
+            (Check::Matches(pattern), FieldValue::Text(text)) => {
+                (!pattern.is_match(text)).then(|| format!("'{}' does not match {}", text, pattern))
+            }
+            (Check::Matches(_), _) => Some("must be text".to_string()),
+            (Check::Range { min, max }, FieldValue::Number(number)) => {
+                (number < min || number > max).then(|| format!("{} is outside {}..={}", number, min, max))
+            }
+            (Check::Range { .. }, _) => Some("must be a number".to_string()),
+            (Check::Custom(check), value) => check(value).err(),
+        }
+    }
+}
+
+// Named group of rules, e.g. "employee_core" or "account_limits"; sets are
+// combined on a ValidationFacet or merged with `extend`
+#[derive(Clone)]
+pub struct RuleSet {
+    name: String,
+    rules: Vec<Rule>,
+    checks: Vec<(String, ObjectCheck)>,
+}
+
+impl RuleSet {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), rules: Vec::new(), checks: Vec::new() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn required(self, target: Target, field: &str) -> Self {
+        self.rule(target, field, Check::Required)
+    }
+
+    // Text field matching `pattern`; fails with Invalid if the pattern is
+    // not a valid regex
+    pub fn matches(self, target: Target, field: &str, pattern: &str) -> Result<Self, FacetError> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| FacetError::Invalid(format!("Invalid pattern for {}.{}: {}", target, field, e)))?;
+        Ok(self.rule(target, field, Check::Matches(pattern)))
+    }
+
+    // Number field within min..=max
+    pub fn range(self, target: Target, field: &str, min: f64, max: f64) -> Self {
+        self.rule(target, field, Check::Range { min, max })
+    }
+
+    // Field checked by `check`, which returns the violation message
+    pub fn custom(
+        self,
+        target: Target,
+        field: &str,
+        check: impl Fn(&FieldValue) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.rule(target, field, Check::Custom(Arc::new(check)))
+    }
+
+    // Check across the whole object, e.g. between facets. Object checks
+    // run when the object is validated, not when a facet is attached.
+    pub fn check(
+        mut self,
+        description: &str,
+        check: impl Fn(&FacetedObject) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.checks.push((description.to_string(), Arc::new(check)));
+        self
+    }
+
+    // Add the rules of `other` to this set
+    pub fn extend(mut self, other: RuleSet) -> Self {
+        self.rules.extend(other.rules);
+        self.checks.extend(other.checks);
+        self
+    }
+
+    fn rule(mut self, target: Target, field: &str, check: Check) -> Self {
+        self.rules.push(Rule { target, field: field.to_string(), check });
+        self
+    }
+}
+
+// One broken rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub rule_set: String,
+    // "core", a facet name, or "object" for object checks
+    pub target: String,
+    // Field, or the description of an object check
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} {}", self.target, self.field, self.message)
+    }
+}
+
+// Every violation found by one validation run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    // Ok if valid, otherwise Invalid listing the violations
+    pub fn into_result(self) -> Result<(), FacetError> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(FacetError::Invalid(self.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Validation failed: ")?;
+        for (index, violation) in self.violations.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+// Declarative rules for the object it is attached to. While attached:
+//   - attaching it fails if the core breaks a core rule
+//   - attaching another facet fails if that facet breaks a rule on it
+//   - transactions are rolled back if they leave the object invalid
+// Plain with_facet_mut calls are not checked; run them in a transaction
+// or call FacetedObject::validate.
+#[derive(Clone, Default)]
+pub struct ValidationFacet {
+    rule_sets: Vec<RuleSet>,
+    core: Option<CoreReader>,
+}
+
+impl ValidationFacet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Read core rules' fields from the core serialized as T; without it
+    // core rules see no fields
+    pub fn core<T: Serialize + 'static>(mut self) -> Self {
+        self.core = Some(Arc::new(|ctx: &FacetContext<'_>| {
+            ctx.core::<T>().and_then(|core| serde_json::to_value(core).ok())
+        }));
+        self
+    }
+
+    pub fn rules(mut self, rule_set: RuleSet) -> Self {
+        self.rule_sets.push(rule_set);
+        self
+    }
+
+    pub fn rule_set_names(&self) -> Vec<&str> {
+        self.rule_sets.iter().map(RuleSet::name).collect()
+    }
+
+    // Violations of the core rules
+    pub fn check_core(&self, ctx: &FacetContext<'_>) -> ValidationReport {
+        let core = self.core.as_ref().and_then(|read| read(ctx));
+        self.check_target(Target::Core, |field| core.as_ref().and_then(|core| core.get(field)).and_then(field_value))
+    }
+
+    // Violations of the rules on `facet`, matched by its facet name
+    pub fn check_facet(&self, facet: &dyn Facet) -> ValidationReport {
+        let reflect = facet.as_reflect();
+        self.check_target(Target::Facet(facet.facet_name()), |field| reflect.and_then(|reflect| reflect.get_field(field)))
+    }
+
+    fn check_target(&self, target: Target, read: impl Fn(&str) -> Option<FieldValue>) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for rule_set in &self.rule_sets {
+            for rule in rule_set.rules.iter().filter(|rule| rule.target == target) {
+                if let Some(message) = rule.violation(read(&rule.field).as_ref()) {
+                    report.violations.push(Violation {
+                        rule_set: rule_set.name.clone(),
+                        target: target.to_string(),
+                        field: rule.field.clone(),
+                        message,
+                    });
+                }
+            }
+        }
+        report
+    }
+}
+
+// Null and absent fields are missing; arrays and objects are compared as
+// JSON text
+fn field_value(value: &Value) -> Option<FieldValue> {
+    match value {
+        Value::Null => None,
+        Value::Bool(flag) => Some(FieldValue::Bool(*flag)),
+        Value::Number(number) => number.as_f64().map(FieldValue::Number),
+        Value::String(text) => Some(FieldValue::Text(text.clone())),
+        other => Some(FieldValue::Text(other.to_string())),
+    }
+}
+
+impl Facet for ValidationFacet {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn facet_name(&self) -> &'static str {
+        "validation"
+    }
+
+    fn metadata(&self) -> FacetMetadata {
+        FacetMetadata::of(self).description("Declarative rules for the core object and facets").tags(&["validation"])
+    }
+
+    fn on_attach(&mut self, ctx: &FacetContext<'_>) -> Result<(), FacetError> {
+        self.check_core(ctx).into_result()
+    }
+}
+
+impl FacetedObject {
+    // Check the core, every attached facet and the object checks against
+    // the object's ValidationFacet; objects without one are valid
+    pub fn validate(&self) -> Result<ValidationReport, FacetError> {
+        if !self.has_facet::<ValidationFacet>() {
+            return Ok(ValidationReport::default());
+        }
+        let validation = match self.with_facet::<ValidationFacet, _>(ValidationFacet::clone) {
+            Ok(validation) => validation,
+            Err(FacetError::NotFound { .. }) => return Ok(ValidationReport::default()),
+            Err(e) => return Err(e),
+        };
+
+        let mut report = self.with_context(|ctx| validation.check_core(ctx));
+        self.visit_facets(&mut |_, facet: &dyn Facet| {
+            report.violations.extend(validation.check_facet(facet).violations);
+        })?;
+        for rule_set in &validation.rule_sets {
+            for (description, check) in &rule_set.checks {
+                if let Err(message) = check(self) {
+                    report.violations.push(Violation {
+                        rule_set: rule_set.name.clone(),
+                        target: "object".to_string(),
+                        field: description.clone(),
+                        message,
+                    });
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    // Reject a facet about to be attached if it breaks the rules on it
+    pub(crate) fn validate_attach(&self, facet: &dyn Facet) -> Result<(), FacetError> {
+        if !self.has_facet::<ValidationFacet>() {
+            return Ok(());
+        }
+        match self.with_facet::<ValidationFacet, _>(|validation| validation.check_facet(facet)) {
+            Ok(report) => report.into_result(),
+            Err(FacetError::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Employee, Money};
+
+    fn rules() -> ValidationFacet {
+        let core = RuleSet::new("employee")
+            .required(Target::Core, "name")
+            .matches(Target::Core, "id", r"^EMP\d{3}$")
+            .unwrap();
+        let limits = RuleSet::new("account_limits")
+            .range(Target::Facet("account"), "balance", 0.0, 1_000.0)
+            .custom(Target::Facet("account"), "account_number", |number| match number {
+                FieldValue::Text(number) if number.starts_with("ACC") => Ok(()),
+                _ => Err("must start with ACC".to_string()),
+            })
+            .check("has account", |object| {
+                object.has_facet::<AccountFacet>().then_some(()).ok_or_else(|| "is missing".to_string())
+            });
+        ValidationFacet::new().core::<Employee>().rules(core).rules(limits)
+    }
+
+    #[test]
+    fn test_report_lists_every_violation() {
+        let invalid = FacetedObject::new(Employee::new("", "X1", "Engineering"));
+        let Err(FacetError::Invalid(message)) = invalid.attach_facet(rules()) else { panic!("invalid core accepted") };
+        assert_eq!(message, "Validation failed: core.name is required; core.id 'X1' does not match ^EMP\\d{3}$");
+        assert!(RuleSet::new("bad").matches(Target::Core, "id", "(").is_err());
+
+        let object = FacetedObject::new(Employee::new("Test User", "EMP001", "Engineering"));
+        object.attach_facet(rules()).unwrap();
+        let report = object.validate().unwrap();
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!((report.violations[0].rule_set.as_str(), report.violations[0].target.as_str()), ("account_limits", "object"));
+
+        // Facets breaking a rule are not attached
+        assert!(object.attach_facet(AccountFacet::new("X-1")).is_err());
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        assert!(object.validate().unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_invalid_transaction_rolls_back() {
+        let object = FacetedObject::new(Employee::new("Test User", "EMP001", "Engineering"));
+        object.attach_facet(AccountFacet::new("ACC001")).unwrap();
+        object.attach_facet(rules()).unwrap();
+
+        object.transaction(|tx| tx.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(600)))?).unwrap();
+        let over_limit = object.transaction(|tx| tx.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(600)))?);
+        assert!(matches!(over_limit, Err(FacetError::Invalid(message)) if message.contains("account.balance 1200 is outside 0..=1000")));
+        assert_eq!(object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(600));
+    }
+}
@@ -0,0 +1,208 @@
+// JavaScript bindings through wasm-bindgen, so a browser front end drives
+// the same composition model as the server. Facets are addressed by the
+// names they are registered under in a FacetRegistry and cross the
+// boundary as JSON. Build the module with
+// `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`
+// and generate the JavaScript glue with `wasm-bindgen --target web`.
+
+use std::sync::Arc;
+
+use js_sys::{Function, JSON};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::core::FacetCell;
+use crate::registry::FacetRegistry;
+use crate::{FacetError, FacetedObject};
+
+// Faceted object whose core is a JSON value, exported to JavaScript as
+// `FacetedObject`
+#[wasm_bindgen(js_name = FacetedObject)]
+pub struct JsFacetedObject {
+    object: FacetedObject,
+    registry: Arc<FacetRegistry>,
+}
+
+#[wasm_bindgen(js_class = FacetedObject)]
+impl JsFacetedObject {
+    // `core` is the core object as JSON; facets come from the built-in
+    // registry
+    #[wasm_bindgen(constructor)]
+    pub fn new(core: &str) -> Result<JsFacetedObject, JsError> {
+        Self::with_registry(core, Arc::new(FacetRegistry::builtin())).map_err(js_error)
+    }
+
+    // Attach a facet built from a spec such as "permissions:manager"
+    pub fn attach(&self, spec: &str) -> Result<(), JsError> {
+        self.attach_spec(spec).map_err(js_error)
+    }
+
+    // Attach the facet registered as `name`, restored from JSON state
+    #[wasm_bindgen(js_name = attachState)]
+    pub fn attach_state(&self, name: &str, state: &str) -> Result<(), JsError> {
+        self.attach_json(name, state).map_err(js_error)
+    }
+
+    // Detach the facet and return its last state as JSON
+    pub fn detach(&self, name: &str) -> Result<String, JsError> {
+        self.detach_json(name).map_err(js_error)
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.find(name).is_ok()
+    }
+
+    // Names of the attached facets in attach order
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let _ = self.object.visit_facets(&mut |_, facet: &dyn crate::Facet| names.push(facet.facet_name().to_string()));
+        names
+    }
+
+    pub fn state(&self, name: &str) -> Result<String, JsError> {
+        self.state_json(name).map_err(js_error)
+    }
+
+    // Replace the facet's state, running its attach and detach hooks
+    #[wasm_bindgen(js_name = setState)]
+    pub fn set_state(&self, name: &str, state: &str) -> Result<(), JsError> {
+        self.set_state_json(name, state).map_err(js_error)
+    }
+
+    // Call `operation` with the facet's state and return its result
+    #[wasm_bindgen(js_name = withFacet)]
+    pub fn with_facet(&self, name: &str, operation: &Function) -> Result<JsValue, JsValue> {
+        let state = JSON::parse(&self.state_json(name).map_err(js_value)?)?;
+        operation.call1(&JsValue::NULL, &state)
+    }
+
+    // Call `operation` with the facet's state; a returned object becomes
+    // the facet's new state, undefined leaves it unchanged
+    #[wasm_bindgen(js_name = withFacetMut)]
+    pub fn with_facet_mut(&self, name: &str, operation: &Function) -> Result<(), JsValue> {
+        let state = JSON::parse(&self.state_json(name).map_err(js_value)?)?;
+        let updated = operation.call1(&JsValue::NULL, &state)?;
+        if updated.is_undefined() {
+            return Ok(());
+        }
+        let updated: String = JSON::stringify(&updated)?.into();
+        self.set_state_json(name, &updated).map_err(js_value)
+    }
+}
+
+impl JsFacetedObject {
+    // Object whose facets come from `registry`, for registries with facets
+    // beyond the built-in ones
+    pub fn with_registry(core: &str, registry: Arc<FacetRegistry>) -> Result<Self, FacetError> {
+        Ok(Self { object: FacetedObject::new(parse(core)?), registry })
+    }
+
+    pub fn object(&self) -> &FacetedObject {
+        &self.object
+    }
+
+    fn attach_spec(&self, spec: &str) -> Result<(), FacetError> {
+        self.object.attach_facets_ordered(vec![self.registry.create(spec)?])
+    }
+
+    fn attach_json(&self, name: &str, state: &str) -> Result<(), FacetError> {
+        self.object.attach_facets_ordered(vec![self.registry.deserialize(name, parse(state)?)?])
+    }
+
+    fn detach_json(&self, name: &str) -> Result<String, FacetError> {
+        let (type_id, instance, _) = self.find(name)?;
+        let facet = self.object.detach_instance(type_id, &instance)?
+            .ok_or_else(|| FacetError::UnknownFacet { name: name.to_string() })?;
+        facet_json(facet.as_ref())
+    }
+
+    fn state_json(&self, name: &str) -> Result<String, FacetError> {
+        let (type_id, instance, cell) = self.find(name)?;
+        let slot = cell.read();
+        let facet = slot.as_deref().ok_or_else(|| FacetError::UnknownFacet { name: name.to_string() })?;
+        let state = facet_json(facet);
+        drop(slot);
+        self.object.record_instance_access(type_id, &instance, false);
+        state
+    }
+
+    fn set_state_json(&self, name: &str, state: &str) -> Result<(), FacetError> {
+        let (type_id, instance, _) = self.find(name)?;
+        let facet = self.registry.deserialize(name, parse(state)?)?;
+        if facet.as_any().type_id() != type_id {
+            return Err(FacetError::DowncastFailed { type_name: facet.facet_type_name() });
+        }
+        self.object.swap_boxed(&instance, facet).map(drop)
+    }
+
+    // First attached facet registered as `name`
+    fn find(&self, name: &str) -> Result<(std::any::TypeId, String, FacetCell), FacetError> {
+        self.object.find_cell(|facet| facet.facet_name() == name)?
+            .ok_or_else(|| FacetError::UnknownFacet { name: name.to_string() })
+    }
+}
+
+fn facet_json(facet: &dyn crate::Facet) -> Result<String, FacetError> {
+    let serializable = facet.as_serializable()
+        .ok_or_else(|| FacetError::Invalid(format!("Facet '{}' is not serializable", facet.facet_name())))?;
+    Ok(serializable.to_json()?.to_string())
+}
+
+fn parse(json: &str) -> Result<Value, FacetError> {
+    serde_json::from_str(json).map_err(|e| FacetError::Invalid(format!("Invalid JSON: {}", e)))
+}
+
+fn js_error(e: FacetError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+fn js_value(e: FacetError) -> JsValue {
+    js_error(e).into()
+}
+
+// The JsError/JsValue wrappers need a JavaScript host, so tests drive the
+// JSON layer underneath them
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, Money, PermissionFacet};
+
+    fn object() -> JsFacetedObject {
+        JsFacetedObject::with_registry(r#"{"name": "Test User", "id": "TEST001"}"#, Arc::new(FacetRegistry::builtin())).unwrap()
+    }
+
+    #[test]
+    fn test_attach_detach_by_name() {
+        let object = object();
+        object.attach_spec("account:ACC001").unwrap();
+        object.attach_spec("permissions:manager").unwrap();
+        assert!(object.has("account"));
+        assert_eq!(object.names(), ["account", "permissions"]);
+
+        let state: Value = serde_json::from_str(&object.detach_json("permissions").unwrap()).unwrap();
+        assert_eq!(state["role"], "manager");
+        assert!(!object.has("permissions"));
+        assert!(object.detach_json("permissions").is_err());
+
+        object.attach_json("permissions", &state.to_string()).unwrap();
+        assert!(object.object().with_facet::<PermissionFacet, _>(|permissions| permissions.has_permission("write")).unwrap());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let object = object();
+        object.attach_spec("account:ACC001").unwrap();
+        object.object().with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(40))).unwrap().unwrap();
+
+        let mut state: Value = serde_json::from_str(&object.state_json("account").unwrap()).unwrap();
+        assert_eq!(state["balances"]["USD"], 4_000);
+
+        state["account_number"] = "ACC002".into();
+        object.set_state_json("account", &state.to_string()).unwrap();
+        assert_eq!(object.object().with_facet::<AccountFacet, _>(|account| account.get_account_number().to_string()).unwrap(), "ACC002");
+        assert_eq!(object.object().with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(40));
+
+        assert!(object.set_state_json("account", "not json").is_err());
+        assert!(object.state_json("audit").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+use crate::core::{Facet, FacetedObject};
+use crate::error::FacetError;
+use crate::observe::Subscription;
+use crate::sync::RwLock;
+
+// Id a FacetWorld assigns an object on insert; never reused
+pub type EntityId = u64;
+
+// Facet types a FacetWorld query asks for, as a tuple, e.g.
+// `(AccountFacet, PermissionFacet)` or `(AuditFacet,)`
+pub trait FacetQuery {
+    fn type_ids() -> Vec<TypeId>;
+}
+
+macro_rules! facet_query {
+    ($($facet:ident),+) => {
+        impl<$($facet: Facet + 'static),+> FacetQuery for ($($facet,)+) {
+            fn type_ids() -> Vec<TypeId> {
+                alloc::vec![$(TypeId::of::<$facet>()),+]
+            }
+        }
+    };
+}
+
+facet_query!(A);
+facet_query!(A, B);
+facet_query!(A, B, C);
+facet_query!(A, B, C, D);
+facet_query!(A, B, C, D, E);
+facet_query!(A, B, C, D, E, G);
+
+struct Entity {
+    object: Arc<FacetedObject>,
+    // Keeps the index observer registered while the object is in the world
+    _layout: Subscription,
+}
+
+#[derive(Default)]
+struct WorldState {
+    entities: BTreeMap<EntityId, Entity>,
+    // Entities with at least one instance of each facet type
+    index: BTreeMap<TypeId, BTreeSet<EntityId>>,
+    next_id: EntityId,
+}
+
+impl WorldState {
+    fn update_index(&mut self, id: EntityId, type_id: TypeId, present: bool) {
+        if present {
+            self.index.entry(type_id).or_default().insert(id);
+        } else if let Some(ids) = self.index.get_mut(&type_id) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.index.remove(&type_id);
+            }
+        }
+    }
+}
+
+// Collection of faceted objects queried by the facets they carry, ECS
+// style. Each facet type has an index of the objects carrying it, kept up
+// to date as facets are attached and detached, so a query only visits
+// objects in its smallest index rather than every object.
+#[derive(Default)]
+pub struct FacetWorld {
+    state: Arc<RwLock<WorldState>>,
+}
+
+impl FacetWorld {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, object: FacetedObject) -> Result<EntityId, FacetError> {
+        let id = {
+            let mut state = self.state.write();
+            state.next_id += 1;
+            state.next_id
+        };
+
+        // Observe before indexing, so no attach in between is missed
+        let index: Weak<RwLock<WorldState>> = Arc::downgrade(&self.state);
+        let layout = object.observe_layout(Arc::new(move |object: &FacetedObject, type_id| {
+            let present = object.facet_type_ids().contains(&type_id);
+            if let Some(state) = index.upgrade() {
+                let mut state = state.write();
+                if state.entities.contains_key(&id) {
+                    state.update_index(id, type_id, present);
+                }
+            }
+        }))?;
+
+        let mut state = self.state.write();
+        for type_id in object.facet_type_ids() {
+            state.update_index(id, type_id, true);
+        }
+        state.entities.insert(id, Entity { object: Arc::new(object), _layout: layout });
+        Ok(id)
+    }
+
+    // Take the object out of the world; its facets are left attached
+    pub fn remove(&self, id: EntityId) -> Option<Arc<FacetedObject>> {
+        let mut state = self.state.write();
+        let entity = state.entities.remove(&id)?;
+        state.index.retain(|_, ids| {
+            ids.remove(&id);
+            !ids.is_empty()
+        });
+        Some(entity.object)
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<Arc<FacetedObject>> {
+        let state = self.state.read();
+        state.entities.get(&id).map(|entity| Arc::clone(&entity.object))
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Objects carrying at least one instance of `type_id`, in insertion order
+    #[cfg(feature = "rayon")]
+    pub(crate) fn carriers(&self, type_id: TypeId) -> Result<Vec<(EntityId, Arc<FacetedObject>)>, FacetError> {
+        let state = self.state.read();
+        Ok(state.index.get(&type_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| Some((*id, Arc::clone(&state.entities.get(id)?.object))))
+            .collect())
+    }
+
+    // Objects carrying every facet type in Q, in insertion order. The
+    // result is collected up front, so the world isn't locked while it is
+    // iterated.
+    pub fn query<Q: FacetQuery>(&self) -> Result<impl Iterator<Item = (EntityId, Arc<FacetedObject>)>, FacetError> {
+        let state = self.state.read();
+        let mut indexes = Vec::new();
+        for type_id in Q::type_ids() {
+            match state.index.get(&type_id) {
+                Some(ids) => indexes.push(ids),
+                None => return Ok(Vec::new().into_iter()),
+            }
+        }
+        indexes.sort_by_key(|ids| ids.len());
+
+        let Some((smallest, rest)) = indexes.split_first() else {
+            return Ok(Vec::new().into_iter());
+        };
+        let matches: Vec<(EntityId, Arc<FacetedObject>)> = smallest.iter()
+            .filter(|id| rest.iter().all(|ids| ids.contains(id)))
+            .filter_map(|id| Some((*id, Arc::clone(&state.entities.get(id)?.object))))
+            .collect();
+        Ok(matches.into_iter())
+    }
+}
+
+#[cfg(all(test, feature = "examples"))]
+mod tests {
+    use super::*;
+    use crate::{AccountFacet, AuditFacet, Employee, PermissionFacet};
+
+    fn employee(id: &str) -> FacetedObject {
+        FacetedObject::new(Employee::new("Test User", id, "Engineering"))
+    }
+
+    #[test]
+    fn test_query_by_facet_presence() {
+        let world = FacetWorld::new();
+        let both = world.insert(FacetedObject::builder(Employee::new("Test User", "TEST001", "Engineering"))
+            .with(AccountFacet::new("ACC001"))
+            .with(PermissionFacet::new("manager"))
+            .build()
+            .unwrap()).unwrap();
+        let account_only = world.insert(FacetedObject::builder(Employee::new("Test User", "TEST002", "Engineering"))
+            .with(AccountFacet::new("ACC002"))
+            .build()
+            .unwrap()).unwrap();
+        world.insert(employee("TEST003")).unwrap();
+
+        let ids = |ids: Vec<(EntityId, Arc<FacetedObject>)>| ids.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+        assert_eq!(ids(world.query::<(AccountFacet,)>().unwrap().collect()), [both, account_only]);
+        assert_eq!(ids(world.query::<(AccountFacet, PermissionFacet)>().unwrap().collect()), [both]);
+        assert_eq!(world.query::<(AuditFacet,)>().unwrap().count(), 0);
+        assert_eq!(world.len(), 3);
+    }
+
+    #[test]
+    fn test_index_follows_attach_detach_and_remove() {
+        let world = FacetWorld::new();
+        let id = world.insert(employee("TEST001")).unwrap();
+        let object = world.get(id).unwrap();
+
+        object.attach_facet(AuditFacet::new()).unwrap();
+        assert_eq!(world.query::<(AuditFacet,)>().unwrap().count(), 1);
+
+        object.attach_named_facet("secondary", AuditFacet::new()).unwrap();
+        object.detach_facet::<AuditFacet>().unwrap();
+        assert_eq!(world.query::<(AuditFacet,)>().unwrap().count(), 1);
+        object.detach_named_facet::<AuditFacet>("secondary").unwrap();
+        assert_eq!(world.query::<(AuditFacet,)>().unwrap().count(), 0);
+
+        object.attach_facet(AuditFacet::new()).unwrap();
+        let removed = world.remove(id).unwrap();
+        assert!(world.query::<(AuditFacet,)>().unwrap().next().is_none());
+
+        // Removed objects no longer update the world
+        removed.detach_facet::<AuditFacet>().unwrap();
+        removed.attach_facet(AuditFacet::new()).unwrap();
+        assert!(world.is_empty());
+        assert!(world.query::<(AuditFacet,)>().unwrap().next().is_none());
+    }
+}
@@ -0,0 +1,66 @@
+// Integration tests against the public library surface, the way a
+// dependent crate uses it: only items re-exported from the crate root.
+
+use dynamic_entities::{
+    AccountFacet, AuditFacet, Employee, EmployeeOperations, FacetError, FacetRegistry, FacetedObject, FacetedSnapshot,
+    Money, PermissionFacet,
+};
+
+fn employee(role: &str) -> FacetedObject {
+    FacetedObject::builder(Employee::new("Alice Johnson", "EMP001", "Engineering"))
+        .with(AccountFacet::new("ACC001"))
+        .with(PermissionFacet::new(role))
+        .with(AuditFacet::new())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_financial_operations_are_authorized_and_audited() {
+    let manager = employee("manager");
+    EmployeeOperations::perform_financial_operation(&manager, |account| account.deposit(Money::usd(1000))).unwrap();
+    EmployeeOperations::perform_financial_operation(&manager, |account| account.withdraw(Money::usd(250))).unwrap();
+    assert_eq!(manager.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(750));
+    assert_eq!(manager.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap(), 2);
+
+    // A failed withdrawal leaves the balance untouched
+    let overdrawn = EmployeeOperations::perform_financial_operation(&manager, |account| account.withdraw(Money::usd(5000)));
+    assert!(matches!(overdrawn, Err(FacetError::InsufficientFunds { .. })));
+    assert_eq!(manager.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(750));
+
+    let employee = employee("employee");
+    let denied = EmployeeOperations::perform_financial_operation(&employee, |account| account.deposit(Money::usd(10)));
+    assert!(matches!(denied, Err(FacetError::PermissionDenied { .. })));
+    assert!(EmployeeOperations::get_employee_summary(&employee).contains("Alice Johnson"));
+}
+
+#[test]
+fn test_runtime_composition() {
+    let object = FacetedObject::new(Employee::new("Bob Smith", "EMP002", "Sales"));
+    assert!(!object.has_facet::<AccountFacet>());
+    assert!(matches!(
+        object.with_facet::<AccountFacet, _>(|account| account.get_balance()),
+        Err(FacetError::NotFound { .. }),
+    ));
+
+    FacetRegistry::builtin().attach_all(&object, &["account:ACC002", "permissions:admin"]).unwrap();
+    assert!(matches!(object.attach_facet(AccountFacet::new("ACC003")), Err(FacetError::AlreadyAttached { .. })));
+    assert_eq!(object.facet_count(), 2);
+
+    let account = object.detach_facet::<AccountFacet>().unwrap();
+    assert_eq!(account.get_account_number(), "ACC002");
+    assert!(!object.has_facet::<AccountFacet>());
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let original = employee("manager");
+    original.with_facet_mut::<AccountFacet, _>(|account| account.deposit(Money::usd(40))).unwrap().unwrap();
+
+    let json = original.snapshot::<Employee>().unwrap().to_json();
+    let restored = FacetedObject::restore::<Employee>(&FacetedSnapshot::from_json(&json).unwrap(), &FacetRegistry::builtin()).unwrap();
+
+    assert_eq!(restored.get_core::<Employee>().unwrap().id, "EMP001");
+    assert_eq!(restored.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap(), Money::usd(40));
+    assert!(restored.with_facet::<PermissionFacet, _>(|permissions| permissions.has_permission("write")).unwrap());
+}
@@ -0,0 +1,136 @@
+// Property tests for core FacetedObject invariants over random sequences of
+// attach and mutate operations, checked against a simple model.
+
+use dynamic_entities::{AccountFacet, AuditFacet, Currency, Employee, FacetedObject, Money, PermissionFacet};
+use proptest::prelude::*;
+
+#[derive(Debug, Clone)]
+enum Op {
+    AttachAccount,
+    AttachAudit,
+    AttachPermissions(&'static str),
+    // Amounts in cents
+    Deposit(i64),
+    Withdraw(i64),
+    Log,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        Just(Op::AttachAccount),
+        Just(Op::AttachAudit),
+        prop::sample::select(vec!["admin", "manager", "employee", "guest"]).prop_map(Op::AttachPermissions),
+        (-10_000..100_000i64).prop_map(Op::Deposit),
+        (-10_000..100_000i64).prop_map(Op::Withdraw),
+        Just(Op::Log),
+    ]
+}
+
+// Expected state of the object after a sequence of operations
+#[derive(Debug, Default)]
+struct Model {
+    // Balance in cents
+    account: Option<i64>,
+    audit: Option<usize>,
+    permissions: bool,
+}
+
+fn cents(amount: i64) -> Money {
+    Money::from_minor(amount, Currency::USD)
+}
+
+fn apply(object: &FacetedObject, model: &mut Model, op: &Op) {
+    match op {
+        Op::AttachAccount => {
+            let attached = object.attach_facet(AccountFacet::new("ACC001")).is_ok();
+            assert_eq!(attached, model.account.is_none());
+            model.account.get_or_insert(0);
+        }
+        Op::AttachAudit => {
+            let attached = object.attach_facet(AuditFacet::new()).is_ok();
+            assert_eq!(attached, model.audit.is_none());
+            model.audit.get_or_insert(0);
+        }
+        Op::AttachPermissions(role) => {
+            let attached = object.attach_facet(PermissionFacet::new(role)).is_ok();
+            assert_eq!(attached, !model.permissions);
+            model.permissions = true;
+        }
+        Op::Deposit(amount) => {
+            let result = object.with_facet_mut::<AccountFacet, _>(|account| account.deposit(cents(*amount)));
+            match model.account.as_mut() {
+                Some(balance) => {
+                    if *amount > 0 {
+                        *balance += amount;
+                        assert_eq!(result.unwrap().unwrap(), cents(*balance));
+                    } else {
+                        assert!(result.unwrap().is_err());
+                    }
+                }
+                None => assert!(result.is_err()),
+            }
+        }
+        Op::Withdraw(amount) => {
+            let result = object.with_facet_mut::<AccountFacet, _>(|account| account.withdraw(cents(*amount)));
+            match model.account.as_mut() {
+                Some(balance) => {
+                    if *amount > 0 && *amount <= *balance {
+                        *balance -= amount;
+                        assert_eq!(result.unwrap().unwrap(), cents(*balance));
+                    } else {
+                        assert!(result.unwrap().is_err());
+                    }
+                }
+                None => assert!(result.is_err()),
+            }
+        }
+        Op::Log => {
+            let result = object.with_facet_mut::<AuditFacet, _>(|audit| audit.log_operation("op", "details"));
+            match model.audit.as_mut() {
+                Some(count) => {
+                    assert!(result.is_ok());
+                    *count += 1;
+                }
+                None => assert!(result.is_err()),
+            }
+        }
+    }
+}
+
+fn assert_invariants(object: &FacetedObject, model: &Model) {
+    // has_facet agrees with with_facet for every facet type
+    assert_eq!(object.has_facet::<AccountFacet>(), object.with_facet::<AccountFacet, _>(|_| ()).is_ok());
+    assert_eq!(object.has_facet::<AuditFacet>(), object.with_facet::<AuditFacet, _>(|_| ()).is_ok());
+    assert_eq!(object.has_facet::<PermissionFacet>(), object.with_facet::<PermissionFacet, _>(|_| ()).is_ok());
+
+    // Facet presence and state match the model
+    assert_eq!(object.has_facet::<AccountFacet>(), model.account.is_some());
+    assert_eq!(object.has_facet::<AuditFacet>(), model.audit.is_some());
+    assert_eq!(object.has_facet::<PermissionFacet>(), model.permissions);
+
+    if let Some(expected) = model.account {
+        let balance = object.with_facet::<AccountFacet, _>(|account| account.get_balance()).unwrap();
+        assert_eq!(balance, cents(expected));
+        assert!(!balance.is_negative());
+    }
+    if let Some(expected) = model.audit {
+        let count = object.with_facet::<AuditFacet, _>(|audit| audit.get_audit_trail().len()).unwrap();
+        assert_eq!(count, expected);
+    }
+
+    // Core object is never disturbed by facet operations
+    assert_eq!(object.get_core::<Employee>().unwrap().id, "PROP001");
+}
+
+proptest! {
+    #[test]
+    fn facet_operations_preserve_invariants(ops in prop::collection::vec(op(), 0..64)) {
+        let object = FacetedObject::new(Employee::new("Prop User", "PROP001", "Engineering"));
+        let mut model = Model::default();
+
+        for op in &ops {
+            apply(&object, &mut model, op);
+            assert_invariants(&object, &model);
+        }
+    }
+}